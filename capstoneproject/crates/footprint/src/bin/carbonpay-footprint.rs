@@ -0,0 +1,77 @@
+//! Sizes an offset purchase from activity data.
+//!
+//! ```sh
+//! carbonpay-footprint [--rounding up|nearest|down] [--factors FILE] category:key:quantity...
+//! carbonpay-footprint --list [--factors FILE]
+//! ```
+
+use std::process::ExitCode;
+
+use carbonpay_footprint::{Activity, EmissionFactors, Footprint, Rounding};
+
+const USAGE: &str = "usage: carbonpay-footprint [--rounding up|nearest|down] [--factors FILE] category:key:quantity...
+       carbonpay-footprint --list [--factors FILE]
+
+example: carbonpay-footprint electricity:us:1200 flight:economy:3500 fuel:diesel:40";
+
+fn main() -> ExitCode {
+    match run(std::env::args().skip(1).collect()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Vec<String>) -> Result<(), String> {
+    let mut rounding = Rounding::default();
+    let mut factors_path = None;
+    let mut list = false;
+    let mut activities = Vec::new();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rounding" => {
+                let value = args.next().ok_or("--rounding needs a value")?;
+                rounding = value.parse().map_err(|e| format!("{e}"))?;
+            }
+            "--factors" => factors_path = Some(args.next().ok_or("--factors needs a file")?),
+            "--list" => list = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            activity => activities.push(activity.parse::<Activity>().map_err(|e| format!("{e}"))?),
+        }
+    }
+
+    let factors = match factors_path {
+        Some(path) => {
+            let text = std::fs::read_to_string(&path).map_err(|e| format!("{path}: {e}"))?;
+            EmissionFactors::parse(&text).map_err(|e| format!("{path}: {e}"))?
+        }
+        None => EmissionFactors::bundled(),
+    };
+
+    if list {
+        println!("emission factors {}", factors.version());
+        for factor in factors.iter() {
+            println!(
+                "{}:{}  {} gCO2e/{}",
+                factor.category, factor.key, factor.grams_per_unit, factor.unit
+            );
+        }
+        return Ok(());
+    }
+    if activities.is_empty() {
+        return Err("no activities given".to_string());
+    }
+
+    let footprint = Footprint::calculate(&factors, &activities).map_err(|e| format!("{e}"))?;
+    println!("emission factors: {}", footprint.factors_version);
+    println!("footprint: {:.3} tCO2e", footprint.tonnes());
+    println!("amount: {}", footprint.token_amount(rounding));
+    Ok(())
+}
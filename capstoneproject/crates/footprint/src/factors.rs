@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::FootprintError;
+
+/// The emission factor table bundled with the crate
+pub const BUNDLED_FACTORS: &str = include_str!("../data/emission_factors.csv");
+
+/// Kind of activity an emission factor applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    Electricity, // kWh consumed, keyed by grid region
+    Flight,      // passenger-km flown, keyed by cabin class
+    Freight,     // tonne-km shipped, keyed by transport mode
+    Fuel,        // litres burned, keyed by fuel type
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Category::Electricity => "electricity",
+            Category::Flight => "flight",
+            Category::Freight => "freight",
+            Category::Fuel => "fuel",
+        })
+    }
+}
+
+impl FromStr for Category {
+    type Err = FootprintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "electricity" => Ok(Category::Electricity),
+            "flight" => Ok(Category::Flight),
+            "freight" => Ok(Category::Freight),
+            "fuel" => Ok(Category::Fuel),
+            other => Err(FootprintError::UnknownCategory(other.to_string())),
+        }
+    }
+}
+
+/// One row of the table: grams of CO2e emitted per unit of activity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmissionFactor {
+    pub category: Category,
+    pub key: String,
+    pub grams_per_unit: u64,
+    pub unit: String,
+}
+
+/// A versioned table of emission factors.
+///
+/// The text format is line based: `#` starts a comment, a `version,<id>` line
+/// names the table and every other line is `category,key,grams_co2e_per_unit,unit`.
+#[derive(Debug, Clone)]
+pub struct EmissionFactors {
+    version: String,
+    factors: HashMap<(Category, String), EmissionFactor>,
+}
+
+impl EmissionFactors {
+    /// The table shipped in `data/emission_factors.csv`
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED_FACTORS).expect("bundled emission factors are valid")
+    }
+
+    /// Parse a table in the bundled file's format
+    pub fn parse(text: &str) -> Result<Self, FootprintError> {
+        let mut version = None;
+        let mut factors = HashMap::new();
+
+        for (index, raw) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = raw.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let parse_error = |message: &str| FootprintError::Parse {
+                line: line_number,
+                message: message.to_string(),
+            };
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+
+            if fields[0] == "version" {
+                if fields.len() != 2 || fields[1].is_empty() {
+                    return Err(parse_error("expected `version,<id>`"));
+                }
+                if version.replace(fields[1].to_string()).is_some() {
+                    return Err(parse_error("duplicate version line"));
+                }
+                continue;
+            }
+
+            if fields.len() != 4 {
+                return Err(parse_error("expected `category,key,grams_co2e_per_unit,unit`"));
+            }
+            let category = fields[0]
+                .parse::<Category>()
+                .map_err(|_| parse_error("unknown category"))?;
+            let grams_per_unit = fields[2]
+                .parse::<u64>()
+                .map_err(|_| parse_error("grams_co2e_per_unit must be a whole number"))?;
+            let factor = EmissionFactor {
+                category,
+                key: fields[1].to_string(),
+                grams_per_unit,
+                unit: fields[3].to_string(),
+            };
+            if factors.insert((category, factor.key.clone()), factor).is_some() {
+                return Err(parse_error("duplicate factor"));
+            }
+        }
+
+        Ok(Self {
+            version: version.ok_or(FootprintError::MissingVersion)?,
+            factors,
+        })
+    }
+
+    /// Identifier of this table, recorded on every footprint computed with it
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Look up the factor for `key` (grid region, cabin class, mode or fuel)
+    pub fn get(&self, category: Category, key: &str) -> Result<&EmissionFactor, FootprintError> {
+        self.factors
+            .get(&(category, key.to_string()))
+            .ok_or_else(|| FootprintError::UnknownFactor {
+                category,
+                key: key.to_string(),
+            })
+    }
+
+    /// All factors, sorted by category and key
+    pub fn iter(&self) -> impl Iterator<Item = &EmissionFactor> {
+        let mut factors: Vec<_> = self.factors.values().collect();
+        factors.sort_by(|a, b| (a.category as u8, &a.key).cmp(&(b.category as u8, &b.key)));
+        factors.into_iter()
+    }
+}
@@ -0,0 +1,245 @@
+//! Footprint calculator for CarbonPay.
+//!
+//! Turns activity data (electricity, flights, freight, fuel) into tonnes of CO2e
+//! using a versioned emission factor table, and sizes the integer token `amount`
+//! to pass to `purchase_carbon_credits`. One credit token retires one tonne of CO2e.
+
+use std::fmt;
+use std::str::FromStr;
+
+mod factors;
+
+pub use factors::{Category, EmissionFactor, EmissionFactors, BUNDLED_FACTORS};
+
+/// Grams of CO2e retired by one credit token (one tonne)
+pub const GRAMS_PER_TOKEN: u64 = 1_000_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FootprintError {
+    UnknownCategory(String),
+    UnknownFactor { category: Category, key: String },
+    InvalidQuantity(f64),
+    InvalidActivity(String),
+    UnknownRounding(String),
+    MissingVersion,
+    Parse { line: usize, message: String },
+    Overflow,
+}
+
+impl fmt::Display for FootprintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FootprintError::UnknownCategory(category) => write!(f, "unknown category `{category}`"),
+            FootprintError::UnknownFactor { category, key } => {
+                write!(f, "no {category} emission factor for `{key}`")
+            }
+            FootprintError::InvalidQuantity(quantity) => {
+                write!(f, "quantity must be a finite, non-negative number, got {quantity}")
+            }
+            FootprintError::InvalidActivity(activity) => {
+                write!(f, "expected `category:key:quantity`, got `{activity}`")
+            }
+            FootprintError::UnknownRounding(rounding) => write!(f, "unknown rounding policy `{rounding}`"),
+            FootprintError::MissingVersion => f.write_str("emission factor table has no version line"),
+            FootprintError::Parse { line, message } => write!(f, "line {line}: {message}"),
+            FootprintError::Overflow => f.write_str("footprint is too large"),
+        }
+    }
+}
+
+impl std::error::Error for FootprintError {}
+
+/// An amount of activity, e.g. 1 200 kWh on the `us` grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct Activity {
+    pub category: Category,
+    pub key: String,
+    pub quantity: f64,
+}
+
+impl Activity {
+    /// Electricity consumed, in kWh, on the grid of `region`
+    pub fn electricity(kwh: f64, region: &str) -> Self {
+        Self::new(Category::Electricity, region, kwh)
+    }
+
+    /// Distance flown, in passenger-km, in cabin `class`
+    pub fn flight(km: f64, class: &str) -> Self {
+        Self::new(Category::Flight, class, km)
+    }
+
+    /// Goods shipped, in tonne-km, by transport `mode`
+    pub fn freight(tonne_km: f64, mode: &str) -> Self {
+        Self::new(Category::Freight, mode, tonne_km)
+    }
+
+    /// Fuel burned, in litres
+    pub fn fuel(litres: f64, fuel: &str) -> Self {
+        Self::new(Category::Fuel, fuel, litres)
+    }
+
+    fn new(category: Category, key: &str, quantity: f64) -> Self {
+        Self {
+            category,
+            key: key.to_string(),
+            quantity,
+        }
+    }
+}
+
+/// Parses the `category:key:quantity` form used by the CLI, e.g. `flight:economy:3500`
+impl FromStr for Activity {
+    type Err = FootprintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || FootprintError::InvalidActivity(s.to_string());
+        let mut parts = s.split(':');
+        let (Some(category), Some(key), Some(quantity), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        Ok(Self {
+            category: category.parse()?,
+            key: key.to_string(),
+            quantity: quantity.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// How a footprint in grams is turned into whole tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rounding {
+    /// Round up, so the whole footprint is always covered
+    #[default]
+    Up,
+    /// Round to the nearest tonne, halves up
+    Nearest,
+    /// Round down, never offsetting more than the footprint
+    Down,
+}
+
+impl FromStr for Rounding {
+    type Err = FootprintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "up" => Ok(Rounding::Up),
+            "nearest" => Ok(Rounding::Nearest),
+            "down" => Ok(Rounding::Down),
+            other => Err(FootprintError::UnknownRounding(other.to_string())),
+        }
+    }
+}
+
+/// Emissions of a set of activities, with the factor table they were computed with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Footprint {
+    pub grams_co2e: u64,
+    pub factors_version: String,
+}
+
+impl Footprint {
+    /// Sum the emissions of `activities`. Each activity is rounded to the nearest gram.
+    pub fn calculate(factors: &EmissionFactors, activities: &[Activity]) -> Result<Self, FootprintError> {
+        let mut grams_co2e: u64 = 0;
+        for activity in activities {
+            if !activity.quantity.is_finite() || activity.quantity < 0.0 {
+                return Err(FootprintError::InvalidQuantity(activity.quantity));
+            }
+            let factor = factors.get(activity.category, &activity.key)?;
+            let grams = (activity.quantity * factor.grams_per_unit as f64).round();
+            if grams >= u64::MAX as f64 {
+                return Err(FootprintError::Overflow);
+            }
+            grams_co2e = grams_co2e
+                .checked_add(grams as u64)
+                .ok_or(FootprintError::Overflow)?;
+        }
+        Ok(Self {
+            grams_co2e,
+            factors_version: factors.version().to_string(),
+        })
+    }
+
+    /// Footprint in tonnes of CO2e
+    pub fn tonnes(&self) -> f64 {
+        self.grams_co2e as f64 / GRAMS_PER_TOKEN as f64
+    }
+
+    /// Token `amount` for `purchase_carbon_credits`. Can be 0 with `Down` or `Nearest`
+    /// for footprints under a tonne; the program rejects 0-token purchases.
+    pub fn token_amount(&self, rounding: Rounding) -> u64 {
+        let whole = self.grams_co2e / GRAMS_PER_TOKEN;
+        let rest = self.grams_co2e % GRAMS_PER_TOKEN;
+        match rounding {
+            Rounding::Up if rest > 0 => whole + 1,
+            Rounding::Nearest if rest >= GRAMS_PER_TOKEN / 2 => whole + 1,
+            _ => whole,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_table_is_versioned() {
+        let factors = EmissionFactors::bundled();
+        assert_eq!(factors.version(), "2025.1");
+        assert_eq!(factors.get(Category::Fuel, "diesel").unwrap().grams_per_unit, 2660);
+        assert_eq!(factors.iter().count(), 21);
+    }
+
+    #[test]
+    fn sums_activities_in_grams() {
+        let factors = EmissionFactors::parse(
+            "version,test\nelectricity,grid,500,kWh\nflight,economy,150,passenger-km\n",
+        )
+        .unwrap();
+        let footprint = Footprint::calculate(
+            &factors,
+            &[Activity::electricity(1_000.0, "grid"), Activity::flight(2_000.0, "economy")],
+        )
+        .unwrap();
+        assert_eq!(footprint.grams_co2e, 800_000);
+        assert_eq!(footprint.factors_version, "test");
+    }
+
+    #[test]
+    fn rounding_policies() {
+        let footprint = |grams_co2e| Footprint {
+            grams_co2e,
+            factors_version: String::new(),
+        };
+        assert_eq!(footprint(2_000_000).token_amount(Rounding::Up), 2);
+        assert_eq!(footprint(2_000_001).token_amount(Rounding::Up), 3);
+        assert_eq!(footprint(2_499_999).token_amount(Rounding::Nearest), 2);
+        assert_eq!(footprint(2_500_000).token_amount(Rounding::Nearest), 3);
+        assert_eq!(footprint(2_999_999).token_amount(Rounding::Down), 2);
+        assert_eq!(footprint(400_000).token_amount(Rounding::Down), 0);
+    }
+
+    #[test]
+    fn rejects_unknown_factors_and_bad_input() {
+        let factors = EmissionFactors::bundled();
+        assert_eq!(
+            Footprint::calculate(&factors, &[Activity::electricity(1.0, "mars")]),
+            Err(FootprintError::UnknownFactor {
+                category: Category::Electricity,
+                key: "mars".to_string(),
+            })
+        );
+        assert_eq!(
+            Footprint::calculate(&factors, &[Activity::fuel(-1.0, "diesel")]),
+            Err(FootprintError::InvalidQuantity(-1.0))
+        );
+        assert!(EmissionFactors::parse("fuel,diesel,2660,litre\n").is_err());
+        assert!("freight:road".parse::<Activity>().is_err());
+        assert_eq!(
+            "freight:road:12.5".parse::<Activity>().unwrap(),
+            Activity::freight(12.5, "road")
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! Example integration: a SOL transfer that retires carbon credits in the same
+//! instruction, by CPI into carbon_pay's `retire_from_pool`. The program owns an
+//! offset pool through its `["pool_authority"]` PDA and signs for it.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token::Token;
+use carbonpay_marketplace::cpi::accounts::{CreateOffsetPool, RetireFromPool};
+use carbonpay_marketplace::program::CarbonPay;
+
+declare_id!("BJUEwdNybiTbfzG57HY7qtKUbQcAmUayhDfvybynQWaw");
+
+#[program]
+pub mod carbon_neutral_transfer {
+    use super::*;
+
+    /// Creates the program's offset pool on carbon_pay, funded with `deposit` lamports
+    pub fn initialize(ctx: Context<Initialize>, deposit: u64) -> Result<()> {
+        let signer_seeds: &[&[&[u8]]] = &[&[b"pool_authority", &[ctx.bumps.pool_authority]]];
+        carbonpay_marketplace::cpi::create_offset_pool(
+            CpiContext::new_with_signer(
+                ctx.accounts.carbon_pay_program.to_account_info(),
+                CreateOffsetPool {
+                    payer: ctx.accounts.payer.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                    offset_pool: ctx.accounts.offset_pool.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            deposit,
+        )
+    }
+
+    /// Sends `lamports` to `recipient` and retires `credits` credits of `project`
+    /// from the program's pool, paying at most `max_total_lamports` for them.
+    /// The project's beneficiaries are passed through as remaining accounts.
+    pub fn transfer_sol<'info>(
+        ctx: Context<'_, '_, '_, 'info, CarbonNeutralTransfer<'info>>,
+        lamports: u64,
+        credits: u64,
+        max_total_lamports: u64,
+    ) -> Result<()> {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.sender.to_account_info(),
+                    to: ctx.accounts.recipient.to_account_info(),
+                },
+            ),
+            lamports,
+        )?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[b"pool_authority", &[ctx.bumps.pool_authority]]];
+        carbonpay_marketplace::cpi::retire_from_pool(
+            CpiContext::new_with_signer(
+                ctx.accounts.carbon_pay_program.to_account_info(),
+                RetireFromPool {
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                    payer: ctx.accounts.sender.to_account_info(),
+                    offset_pool: ctx.accounts.offset_pool.to_account_info(),
+                    project: ctx.accounts.project.to_account_info(),
+                    token_mint: ctx.accounts.token_mint.to_account_info(),
+                    vault: ctx.accounts.vault.to_account_info(),
+                    carbon_credits: ctx.accounts.carbon_credits.to_account_info(),
+                    price_feed: ctx.accounts.price_feed.as_ref().map(|feed| feed.to_account_info()),
+                    offset_request: ctx.accounts.offset_request.to_account_info(),
+                    project_stats: ctx.accounts.project_stats.to_account_info(),
+                    platform_stats: ctx.accounts.platform_stats.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                },
+                signer_seeds,
+            )
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            credits,
+            max_total_lamports,
+        )?;
+
+        msg!("Sent {} lamports, retired {} credits", lamports, credits);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: PDA of this program, the authority of its offset pool
+    #[account(seeds = [b"pool_authority"], bump)]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    /// CHECK: created by carbon_pay at `["offset_pool", pool_authority]`
+    #[account(mut)]
+    pub offset_pool: UncheckedAccount<'info>,
+
+    pub carbon_pay_program: Program<'info, CarbonPay>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CarbonNeutralTransfer<'info> {
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    /// CHECK: PDA of this program, the authority of its offset pool
+    #[account(seeds = [b"pool_authority"], bump)]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    /// CHECK: checked by carbon_pay
+    #[account(mut)]
+    pub offset_pool: UncheckedAccount<'info>,
+
+    /// CHECK: checked by carbon_pay
+    #[account(mut)]
+    pub project: UncheckedAccount<'info>,
+
+    /// CHECK: checked by carbon_pay
+    #[account(mut)]
+    pub token_mint: UncheckedAccount<'info>,
+
+    /// CHECK: checked by carbon_pay
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+
+    /// CHECK: checked by carbon_pay
+    #[account(mut)]
+    pub carbon_credits: UncheckedAccount<'info>,
+
+    /// CHECK: checked by carbon_pay, only needed for USD-priced projects
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: created by carbon_pay at `["offset_request", pool_authority, default, "pool-<n>"]`
+    #[account(mut)]
+    pub offset_request: UncheckedAccount<'info>,
+
+    /// CHECK: checked by carbon_pay
+    #[account(mut)]
+    pub project_stats: UncheckedAccount<'info>,
+
+    /// CHECK: checked by carbon_pay
+    #[account(mut)]
+    pub platform_stats: UncheckedAccount<'info>,
+
+    pub carbon_pay_program: Program<'info, CarbonPay>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
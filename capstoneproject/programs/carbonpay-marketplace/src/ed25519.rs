@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+
+use crate::errors::ContractError;
+
+/// Size of the Ed25519 precompile header: signature count and padding
+const HEADER_LEN: usize = 2;
+/// Size of one `Ed25519SignatureOffsets` entry: seven u16 fields
+const OFFSETS_LEN: usize = 14;
+const PUBKEY_LEN: usize = 32;
+/// Instruction index meaning "this instruction" in the signature offsets
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Check that the instruction right before the current one is an Ed25519
+/// precompile instruction verifying one signature by `signer` over `message`.
+/// The precompile has already checked the signature when the transaction ran, so
+/// only the signer and message it covered are compared here.
+pub fn verify_ed25519_signature(instructions: &AccountInfo, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    let current = load_current_index_checked(instructions)?;
+    require!(current > 0, ContractError::MissingEd25519Instruction);
+    let ix = load_instruction_at_checked(current as usize - 1, instructions)?;
+    require!(
+        ix.program_id == ed25519_program::ID && ix.accounts.is_empty(),
+        ContractError::MissingEd25519Instruction
+    );
+
+    let data = &ix.data;
+    require!(
+        data.len() >= HEADER_LEN + OFFSETS_LEN && data[0] == 1,
+        ContractError::InvalidEd25519Instruction
+    );
+    let offsets = &data[HEADER_LEN..HEADER_LEN + OFFSETS_LEN];
+    let field = |i: usize| u16::from_le_bytes([offsets[2 * i], offsets[2 * i + 1]]);
+    let (signature_ix, pubkey_offset, pubkey_ix) = (field(1), field(2) as usize, field(3));
+    let (message_offset, message_size, message_ix) = (field(4) as usize, field(5) as usize, field(6));
+    // all data must live in the precompile instruction itself
+    require!(
+        signature_ix == CURRENT_INSTRUCTION && pubkey_ix == CURRENT_INSTRUCTION && message_ix == CURRENT_INSTRUCTION,
+        ContractError::InvalidEd25519Instruction
+    );
+
+    let signed_pubkey = data
+        .get(pubkey_offset..pubkey_offset + PUBKEY_LEN)
+        .ok_or(ContractError::InvalidEd25519Instruction)?;
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(ContractError::InvalidEd25519Instruction)?;
+    require!(signed_pubkey == signer.as_ref(), ContractError::InvalidAttestationSigner);
+    require!(signed_message == message, ContractError::InvalidAttestation);
+    Ok(())
+}
@@ -71,4 +71,229 @@ pub enum ContractError {
     
     #[msg("Insufficient fungible tokens in account")]
     InsufficientFungibleTokens,
+
+    #[msg("Request ID is too long")]
+    RequestIdTooLong,
+
+    #[msg("Beneficiary name is too long")]
+    BeneficiaryNameTooLong,
+
+    #[msg("Retirement reason is too long")]
+    RetirementReasonTooLong,
+
+    #[msg("Reporting period end must not be before its start")]
+    InvalidReportingPeriod,
+
+    #[msg("Signer is not a registered verifier")]
+    UnauthorizedVerifier,
+
+    #[msg("Project is not pending review")]
+    ProjectNotPendingReview,
+
+    #[msg("Document hash does not match the submitted documents")]
+    DocumentHashMismatch,
+
+    #[msg("A non-zero reason code is required")]
+    InvalidReasonCode,
+
+    #[msg("Verifier is already registered")]
+    VerifierAlreadyRegistered,
+
+    #[msg("Verifier is not registered")]
+    VerifierNotRegistered,
+
+    #[msg("Verifier registry is full")]
+    VerifierRegistryFull,
+
+    #[msg("Threshold must be between 1 and the number of verifiers")]
+    InvalidThreshold,
+
+    #[msg("Verifier has already voted")]
+    AlreadyVoted,
+
+    #[msg("Vote has already reached a decision")]
+    VoteAlreadyResolved,
+
+    #[msg("SOL/USD price feed is missing or not the configured one")]
+    InvalidPriceFeed,
+
+    #[msg("Oracle price is not currently trading")]
+    PriceNotTrading,
+
+    #[msg("Oracle price is stale")]
+    StalePrice,
+
+    #[msg("Oracle price confidence interval is too wide")]
+    PriceConfidenceTooLow,
+
+    #[msg("Total price exceeds the buyer's maximum")]
+    SlippageExceeded,
+
+    #[msg("Platform fee is higher than the buyer expected")]
+    FeeAboveExpected,
+
+    #[msg("A project needs between 1 and 5 beneficiaries")]
+    InvalidBeneficiaryCount,
+
+    #[msg("Beneficiary shares must be non-zero and sum to 10,000 bps")]
+    InvalidBeneficiaryShares,
+
+    #[msg("Beneficiary is listed more than once")]
+    DuplicateBeneficiary,
+
+    #[msg("Beneficiary accounts do not match the project's beneficiaries")]
+    BeneficiaryAccountMismatch,
+
+    #[msg("Platform collection has already been created")]
+    PlatformCollectionExists,
+
+    #[msg("Collection mint does not match the expected collection")]
+    InvalidCollection,
+
+    #[msg("Metadata base URI is too long")]
+    MetadataUriTooLong,
+
+    #[msg("Vintage does not belong to this project or purchase")]
+    InvalidVintage,
+
+    #[msg("Purchase still has credits that have not been offset")]
+    PurchaseNotFullyOffset,
+
+    #[msg("Offset request has not been processed yet")]
+    RequestNotProcessed,
+
+    #[msg("Project still has unsold credits, unretired credits or pending offset requests")]
+    ProjectNotFinished,
+
+    #[msg("Presale start must be before its end and caps must be non-zero")]
+    InvalidPresaleConfig,
+
+    #[msg("Sales have not opened yet")]
+    PresaleNotStarted,
+
+    #[msg("Wallet is not on the presale allowlist")]
+    NotOnAllowlist,
+
+    #[msg("Purchase exceeds the presale per-transaction cap")]
+    PresaleTxCapExceeded,
+
+    #[msg("Purchase exceeds the presale per-wallet cap")]
+    PresaleWalletCapExceeded,
+
+    #[msg("Buyer allocation account is required during the presale")]
+    BuyerAllocationRequired,
+
+    #[msg("Referral share cannot exceed 10,000 bps")]
+    InvalidReferralShare,
+
+    #[msg("Buyers cannot refer their own purchases")]
+    SelfReferral,
+
+    #[msg("No referral fees to claim")]
+    NothingToClaim,
+
+    #[msg("Subscription needs 1 to 3 distinct projects and non-zero tokens and period")]
+    InvalidSubscription,
+
+    #[msg("Project is not one of the subscription's projects")]
+    ProjectNotInSubscription,
+
+    #[msg("Subscription period has not elapsed yet")]
+    SubscriptionNotDue,
+
+    #[msg("Price is above the subscription's maximum")]
+    SubscriptionPriceTooHigh,
+
+    #[msg("Subscription balance cannot cover this period")]
+    InsufficientSubscriptionFunds,
+
+    #[msg("Offset request review deadline has not passed yet")]
+    ReviewDeadlineNotReached,
+
+    #[msg("Offset review period cannot be negative")]
+    InvalidReviewPeriod,
+
+    #[msg("Processing an offset request needs its purchase, and to refund it the token mint and requester token account")]
+    MissingRefundAccounts,
+
+    #[msg("Credits are past their sale expiry")]
+    CreditsExpired,
+
+    #[msg("Credits are not past their sale expiry yet")]
+    CreditsNotExpired,
+
+    #[msg("Registry name is too long")]
+    RegistryNameTooLong,
+
+    #[msg("Attestation does not match the project being tokenized")]
+    InvalidAttestation,
+
+    #[msg("Attested amount must match the serial range and the tokenized amount")]
+    AttestationAmountMismatch,
+
+    #[msg("Attestation was not signed by the registry oracle")]
+    InvalidAttestationSigner,
+
+    #[msg("Ed25519 signature verification instruction must precede this instruction")]
+    MissingEd25519Instruction,
+
+    #[msg("Ed25519 instruction must verify exactly one signature with inline data")]
+    InvalidEd25519Instruction,
+
+    #[msg("Partial offsets need the remaining-balance NFT mint, token account and metadata")]
+    MissingRemainderNft,
+
+    #[msg("Account is already in the current layout")]
+    AccountAlreadyMigrated,
+
+    #[msg("Account size matches no known layout")]
+    UnknownAccountLayout,
+
+    #[msg("Account must be migrated to the current layout first")]
+    AccountNotMigrated,
+
+    #[msg("Offset pool balance cannot cover this retirement")]
+    InsufficientPoolFunds,
+
+    #[msg("Retirement fund needs 1 to 5 distinct projects and a non-zero period")]
+    InvalidRetirementFund,
+
+    #[msg("Project is not one of the retirement fund's projects")]
+    ProjectNotInFund,
+
+    #[msg("Retirement fund period has not elapsed yet")]
+    FundRetirementNotDue,
+
+    #[msg("Price is above the retirement fund's maximum")]
+    FundPriceTooHigh,
+
+    #[msg("Retirement fund balance cannot cover this retirement")]
+    InsufficientFundBalance,
+
+    #[msg("Signer does not hold the project NFT")]
+    NotProjectNftHolder,
+
+    #[msg("Signer already owns the project")]
+    AlreadyProjectOwner,
+
+    #[msg("Vintage still has unsold or unretired credits, or offsets pending review")]
+    VintageNotFinished,
+
+    #[msg("Project vintages must be closed before the project")]
+    ProjectHasOpenVintages,
+
+    #[msg("Account is not the purchase of the offset request")]
+    InvalidPurchase,
+
+    #[msg("Attestation was already used to issue credits")]
+    AttestationAlreadyUsed,
+
+    #[msg("Purchase has offset requests pending review")]
+    PurchaseHasPendingRequests,
+
+    #[msg("Direct retirements are closed while the presale runs")]
+    PresaleActive,
+
+    #[msg("Settling a deposit from an ended fund epoch needs that epoch's record")]
+    MissingFundEpoch,
 }
@@ -0,0 +1,207 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{ExpiryAction, ProjectStatus, RetirementDetails};
+
+/// Emitted when a buyer retires credits, so indexers and certificate renderers can
+/// pick up the retirement without polling OffsetRequest accounts.
+#[event]
+pub struct OffsetRequested {
+    pub offset_request: Pubkey,
+    pub offset_requester: Pubkey,
+    pub beneficiary: Pubkey,
+    pub beneficiary_name: String,
+    pub reason: String,
+    pub reporting_period_start: i64,
+    pub reporting_period_end: i64,
+    pub purchase: Pubkey,
+    pub project: Pubkey,
+    pub vintage: Pubkey,
+    pub amount: u64,
+    pub request_id: String,
+    pub request_date: i64,
+}
+
+/// Emitted whenever a verifier or the platform authority changes a project's listing status
+#[event]
+pub struct ProjectStatusChanged {
+    pub project: Pubkey,
+    pub status: ProjectStatus,
+    pub reviewer: Pubkey,
+    pub reason_code: u16,
+    pub timestamp: i64,
+}
+
+/// Emitted for every verifier vote, whether or not it reached the quorum
+#[event]
+pub struct VerifierVoted {
+    pub target: Pubkey,
+    pub verifier: Pubkey,
+    pub approve: bool,
+    pub approvals: u8,
+    pub rejections: u8,
+    pub threshold: u8,
+}
+
+/// Emitted when verifier votes on an offset request reach the quorum
+#[event]
+pub struct OffsetRequestProcessed {
+    pub offset_request: Pubkey,
+    pub approved: bool,
+    pub processors: Vec<Pubkey>,
+    pub processed_date: i64,
+}
+
+/// Emitted when additional credits are issued for an existing project
+#[event]
+pub struct CreditsIssued {
+    pub project: Pubkey,
+    pub amount: u64,
+    pub registry_oracle: Pubkey,
+    pub attestation_hash: [u8; 32],
+    pub document_hash: [u8; 32],
+    pub total_amount: u64,
+}
+
+/// Emitted when a new vintage tranche is added to a project
+#[event]
+pub struct VintageAdded {
+    pub project: Pubkey,
+    pub vintage: Pubkey,
+    pub vintage_year: u16,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    pub price_per_token: u64,
+    pub registry_oracle: Pubkey,
+    pub attestation_hash: [u8; 32],
+}
+
+/// Emitted when a processed offset request is closed, so the retirement record
+/// stays available to indexers after the account is gone
+#[event]
+pub struct OffsetRequestClosed {
+    pub offset_request: Pubkey,
+    pub offset_requester: Pubkey,
+    pub retirement: RetirementDetails,
+    pub purchase: Pubkey,
+    pub project: Pubkey,
+    pub amount: u64,
+    pub request_id: String,
+    pub approved: bool,
+    pub request_date: i64,
+    pub processed_date: i64,
+    pub processors: Vec<Pubkey>,
+}
+
+/// Emitted when a referred purchase credits a referrer
+#[event]
+pub struct ReferralCredited {
+    pub referrer: Pubkey,
+    pub purchase: Pubkey,
+    pub volume: u64,
+    pub referral_fee: u64,
+}
+
+/// Emitted when a subscription is cranked and its credits retired
+#[event]
+pub struct SubscriptionExecuted {
+    pub subscription: Pubkey,
+    pub owner: Pubkey,
+    pub project: Pubkey,
+    pub offset_request: Pubkey,
+    pub amount: u64,
+    pub total_lamports: u64,
+    pub cranker: Pubkey,
+    pub crank_tip: u64,
+    pub execution: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a pending offset request passes its review deadline and the
+/// platform's default is applied
+#[event]
+pub struct OffsetRequestExpired {
+    pub offset_request: Pubkey,
+    pub offset_requester: Pubkey,
+    pub project: Pubkey,
+    pub amount: u64,
+    pub action: ExpiryAction,
+    pub review_deadline: i64,
+    pub expired_at: i64,
+}
+
+/// Emitted when a project owner burns unsold credits that are past their sale expiry
+#[event]
+pub struct ExpiredCreditsBurned {
+    pub project: Pubkey,
+    pub vintage: Pubkey,
+    pub amount: u64,
+    pub expired_amount: u64,
+    pub sale_expiry: i64,
+}
+
+/// Emitted when a project is tokenized against a registry attestation; the project
+/// only stores the attestation hash
+#[event]
+pub struct TokenizationAttested {
+    pub project: Pubkey,
+    pub registry_oracle: Pubkey,
+    pub registry_project_id: String,
+    pub serial_start: u64,
+    pub serial_end: u64,
+    pub amount: u64,
+    pub vintage_year: u16,
+    pub attestation_hash: [u8; 32],
+}
+
+/// Emitted when an account is migrated in place to the current layout
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+/// Emitted when credits are retired from an offset pool, for its authority
+#[event]
+pub struct PoolRetirement {
+    pub offset_pool: Pubkey,
+    pub authority: Pubkey,
+    pub project: Pubkey,
+    pub offset_request: Pubkey,
+    pub amount: u64,
+    pub total_lamports: u64,
+    pub retirement: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when lamports are deposited into a retirement fund
+#[event]
+pub struct FundDeposited {
+    pub retirement_fund: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub unspent: u64,
+    pub total_unspent: u64,
+}
+
+/// Emitted when a retirement fund buys and retires credits for its depositors
+#[event]
+pub struct FundRetired {
+    pub retirement_fund: Pubkey,
+    pub project: Pubkey,
+    pub offset_request: Pubkey,
+    pub amount: u64,
+    pub total_lamports: u64,
+    pub caller: Pubkey,
+    pub retirement: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when the holder of the project NFT takes over the project
+#[event]
+pub struct ProjectOwnershipTransferred {
+    pub project: Pubkey,
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub timestamp: i64,
+}
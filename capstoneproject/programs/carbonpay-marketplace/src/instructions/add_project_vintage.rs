@@ -0,0 +1,175 @@
+use crate::ed25519::verify_ed25519_signature;
+use crate::errors::ContractError;
+use crate::events::VintageAdded;
+use crate::state::{CarbonCredits, Project, ProjectVintage, RegistryOracle, TokenizationAttestation};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use anchor_spl::token::{mint_to, set_authority, Mint, MintTo, SetAuthority, Token, TokenAccount};
+
+/// Adds a vintage tranche to an active project. Like `issue_credits`, `amount`
+/// must be backed by an `attestation` of that vintage year signed by a registered
+/// registry oracle, verified by an Ed25519 program instruction placed right before this one.
+/// `token_mint` and the `vault` ATA of the `carbon_credits` PDA must exist before
+/// the call, with nothing minted yet; once the tranche is minted, mint authority
+/// moves to `carbon_credits` and the freeze authority is dropped.
+#[derive(Accounts)]
+#[instruction(vintage_year: u16)]
+pub struct AddProjectVintage<'info> {
+    #[account(
+        mut,
+        constraint = project_owner.key() == project.owner @ ContractError::InvalidProjectOwner
+    )]
+    pub project_owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"project", project.seed_owner().as_ref(), project.mint.as_ref()],
+        bump = project.project_bump,
+        constraint = project.is_active() @ ContractError::ProjectInactive,
+    )]
+    pub project: Box<Account<'info, Project>>,
+
+    /// On-chain state of the vintage
+    #[account(
+        init,
+        payer = project_owner,
+        space = ProjectVintage::DISCRIMINATOR_SIZE + ProjectVintage::INIT_SPACE,
+        seeds = [b"vintage", project.key().as_ref(), &vintage_year.to_le_bytes()],
+        bump
+    )]
+    pub vintage: Box<Account<'info, ProjectVintage>>,
+
+    /// The vintage's fungible token mint
+    #[account(
+        mut,
+        mint::decimals = 0,
+        mint::authority = project_owner,
+        mint::freeze_authority = project_owner,
+        constraint = token_mint.supply == 0 @ ContractError::InvalidProjectMint,
+    )]
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    /// ATA of the `carbon_credits` PDA for the vintage's tokens
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = carbon_credits,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Box<Account<'info, CarbonCredits>>,
+
+    /// Registry oracle that signed the attestation
+    #[account(
+        mut,
+        seeds = [b"registry_oracle", registry_oracle.key.as_ref()],
+        bump = registry_oracle.bump,
+    )]
+    pub registry_oracle: Box<Account<'info, RegistryOracle>>,
+
+    /// CHECK: instructions sysvar, read to find the Ed25519 verification instruction
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AddProjectVintage<'info> {
+    pub fn handler(
+        &mut self,
+        vintage_year: u16,
+        amount: u64,
+        price_per_token: u64,
+        attestation: TokenizationAttestation,
+        bumps: &AddProjectVintageBumps,
+    ) -> Result<()> {
+        require!(amount > 0, ContractError::InvalidAmount);
+
+        // 0) the tranche must be attested by a registry oracle, for this vintage
+        attestation.validate(amount, &self.project.owner, &self.token_mint.key())?;
+        require!(attestation.vintage_year == vintage_year, ContractError::InvalidAttestation);
+        verify_ed25519_signature(
+            &self.instructions,
+            &self.registry_oracle.key,
+            &attestation.message()?,
+        )?;
+        let attestation_hash = attestation.hash()?;
+        self.registry_oracle.record_attestation()?;
+
+        // 1) record the vintage
+        self.vintage.set_inner(ProjectVintage {
+            project: self.project.key(),
+            vintage_year,
+            token_mint: self.token_mint.key(),
+            vault: self.vault.key(),
+            amount,
+            remaining_amount: amount,
+            offset_amount: 0,
+            price_per_token,
+            bump: bumps.vintage,
+            sale_expiry: None,
+            expired_amount: 0,
+        });
+
+        // 2) mint the tranche into the vault
+        mint_to(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                MintTo {
+                    mint: self.token_mint.to_account_info(),
+                    to: self.vault.to_account_info(),
+                    authority: self.project_owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        // 3) hand the mint authority to the carbon_credits PDA
+        set_authority(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                SetAuthority {
+                    account_or_mint: self.token_mint.to_account_info(),
+                    current_authority: self.project_owner.to_account_info(),
+                },
+            ),
+            anchor_spl::token::spl_token::instruction::AuthorityType::MintTokens,
+            Some(self.carbon_credits.key()),
+        )?;
+        // and drop the freeze authority, so the owner cannot freeze buyers' credits
+        set_authority(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                SetAuthority {
+                    account_or_mint: self.token_mint.to_account_info(),
+                    current_authority: self.project_owner.to_account_info(),
+                },
+            ),
+            anchor_spl::token::spl_token::instruction::AuthorityType::FreezeAccount,
+            None,
+        )?;
+
+        // 4) roll the tranche up into project and platform totals
+        self.project.record_issuance(amount)?;
+        self.project.record_vintage_added()?;
+        self.carbon_credits.add_issued_credits(amount)?;
+
+        emit!(VintageAdded {
+            project: self.project.key(),
+            vintage: self.vintage.key(),
+            vintage_year,
+            token_mint: self.token_mint.key(),
+            amount,
+            price_per_token,
+            registry_oracle: self.registry_oracle.key,
+            attestation_hash,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use crate::errors::ContractError;
+use crate::events::ExpiredCreditsBurned;
+use crate::state::{CarbonCredits, Project, ProjectVintage};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{burn, Burn, Mint, Token, TokenAccount};
+
+/// Project owner burns unsold credits past their sale expiry from the vault of the
+/// project or of `vintage`. The burned amount is tracked as expired, not offset.
+#[derive(Accounts)]
+pub struct BurnExpiredCredits<'info> {
+    #[account(
+        constraint = project_owner.key() == project.owner @ ContractError::InvalidProjectOwner
+    )]
+    pub project_owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"project", project.seed_owner().as_ref(), project.mint.as_ref()],
+        bump = project.project_bump,
+    )]
+    pub project: Box<Account<'info, Project>>,
+
+    /// vintage tranche the credits are burned from, if any
+    #[account(
+        mut,
+        constraint = vintage.project == project.key() @ ContractError::InvalidVintage,
+    )]
+    pub vintage: Option<Box<Account<'info, ProjectVintage>>>,
+
+    /// The fungible token mint of the project or of the vintage
+    #[account(
+        mut,
+        constraint = token_mint.key() == vintage.as_ref().map_or(project.token_mint, |v| v.token_mint) @ ContractError::InvalidProjectMint,
+    )]
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    /// The vault holding the unsold tokens
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = carbon_credits,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Box<Account<'info, CarbonCredits>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> BurnExpiredCredits<'info> {
+    pub fn handler(&mut self, amount: u64) -> Result<()> {
+        require!(amount > 0, ContractError::InvalidAmount);
+        let now = Clock::get()?.unix_timestamp;
+        let (expired, sale_expiry, remaining) = match self.vintage.as_ref() {
+            Some(vintage) => (vintage.is_sale_expired(now), vintage.sale_expiry, vintage.remaining_amount),
+            None => (self.project.is_sale_expired(now), self.project.sale_expiry, self.project.remaining_amount),
+        };
+        require!(expired, ContractError::CreditsNotExpired);
+        require!(
+            amount <= remaining && amount <= self.vault.amount,
+            ContractError::InsufficientTokens
+        );
+
+        burn(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Burn {
+                    mint: self.token_mint.to_account_info(),
+                    from: self.vault.to_account_info(),
+                    authority: self.carbon_credits.to_account_info(),
+                },
+                &[&[b"carbon_credits", &[self.carbon_credits.bump]]],
+            ),
+            amount,
+        )?;
+
+        // vintage amounts are rolled up into the project, so both are updated
+        self.project.record_expired_burn(amount)?;
+        if let Some(vintage) = self.vintage.as_mut() {
+            vintage.record_expired_burn(amount)?;
+        }
+        self.carbon_credits.record_expired_burn(amount)?;
+
+        emit!(ExpiredCreditsBurned {
+            project: self.project.key(),
+            vintage: self.vintage.as_ref().map_or(Pubkey::default(), |v| v.key()),
+            amount,
+            expired_amount: self.project.expired_amount,
+            sale_expiry: sale_expiry.unwrap_or_default(),
+        });
+        Ok(())
+    }
+}
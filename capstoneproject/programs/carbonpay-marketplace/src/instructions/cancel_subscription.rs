@@ -0,0 +1,32 @@
+use crate::errors::ContractError;
+use crate::state::Subscription;
+use anchor_lang::prelude::*;
+
+/// Owner cancels a subscription; its remaining balance and rent are returned
+#[derive(Accounts)]
+pub struct CancelSubscription<'info> {
+    #[account(
+        mut,
+        constraint = owner.key() == subscription.owner @ ContractError::Unauthorized
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"subscription", subscription.owner.as_ref(), &subscription.subscription_id.to_le_bytes()],
+        bump = subscription.bump,
+    )]
+    pub subscription: Box<Account<'info, Subscription>>,
+}
+
+impl<'info> CancelSubscription<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        msg!(
+            "Cancelled subscription after {} executions, {} tokens retired",
+            self.subscription.executions,
+            self.subscription.total_retired
+        );
+        Ok(())
+    }
+}
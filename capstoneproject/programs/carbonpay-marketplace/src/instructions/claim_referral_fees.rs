@@ -0,0 +1,29 @@
+use crate::errors::ContractError;
+use crate::state::Referrer;
+use anchor_lang::prelude::*;
+
+/// Referrer withdraws its accrued referral fees
+#[derive(Accounts)]
+pub struct ClaimReferralFees<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"referrer", wallet.key().as_ref()],
+        bump = referrer.bump,
+    )]
+    pub referrer: Account<'info, Referrer>,
+}
+
+impl<'info> ClaimReferralFees<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        let amount = self.referrer.accrued_lamports;
+        require!(amount > 0, ContractError::NothingToClaim);
+
+        self.referrer.accrued_lamports = 0;
+        self.referrer.sub_lamports(amount)?;
+        self.wallet.add_lamports(amount)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use crate::errors::ContractError;
+use crate::state::OffsetPool;
+use anchor_lang::prelude::*;
+
+/// Authority closes its offset pool; the remaining balance and rent go to `receiver`
+#[derive(Accounts)]
+pub struct CloseOffsetPool<'info> {
+    #[account(
+        constraint = authority.key() == offset_pool.authority @ ContractError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        close = receiver,
+        seeds = [b"offset_pool", offset_pool.authority.as_ref()],
+        bump = offset_pool.bump,
+    )]
+    pub offset_pool: Box<Account<'info, OffsetPool>>,
+
+    /// CHECK: any account chosen by the authority to receive the lamports
+    #[account(mut)]
+    pub receiver: UncheckedAccount<'info>,
+}
+
+impl<'info> CloseOffsetPool<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        msg!(
+            "Closed offset pool after {} retirements, {} tokens retired",
+            self.offset_pool.retirements,
+            self.offset_pool.total_retired
+        );
+        Ok(())
+    }
+}
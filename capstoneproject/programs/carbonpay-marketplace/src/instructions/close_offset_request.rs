@@ -0,0 +1,46 @@
+use crate::errors::ContractError;
+use crate::events::OffsetRequestClosed;
+use crate::state::{OffsetRequest, RequestStatus};
+use anchor_lang::prelude::*;
+
+/// Requester closes an approved, rejected or expired offset request and gets its rent back.
+/// The full retirement record is emitted as an event before the account is closed.
+#[derive(Accounts)]
+pub struct CloseOffsetRequest<'info> {
+    #[account(mut)]
+    pub offset_requester: Signer<'info>,
+
+    #[account(
+        mut,
+        close = offset_requester,
+        seeds = [
+            b"offset_request",
+            offset_requester.key().as_ref(),
+            offset_request.purchase.as_ref(),
+            offset_request.request_id.as_bytes(),
+        ],
+        bump = offset_request.request_bump,
+        constraint = offset_request.status != RequestStatus::Pending @ ContractError::RequestNotProcessed,
+    )]
+    pub offset_request: Box<Account<'info, OffsetRequest>>,
+}
+
+impl<'info> CloseOffsetRequest<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        let request = &self.offset_request;
+        emit!(OffsetRequestClosed {
+            offset_request: request.key(),
+            offset_requester: request.offset_requester,
+            retirement: request.retirement.clone(),
+            purchase: request.purchase,
+            project: request.project,
+            amount: request.amount,
+            request_id: request.request_id.clone(),
+            approved: request.is_retired(),
+            request_date: request.request_date,
+            processed_date: request.processed_date,
+            processors: request.processors.clone(),
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use crate::errors::ContractError;
+use crate::migration::ACCOUNT_VERSION;
+use crate::state::{CarbonCredits, Project};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{close_account, CloseAccount, Token, TokenAccount};
+
+/// Project owner closes a finished project (sold out, everything offset and no
+/// offset request left to review) once its vintages are closed. Rent of the
+/// project and its empty vault goes back to the owner.
+#[derive(Accounts)]
+pub struct CloseProject<'info> {
+    #[account(
+        mut,
+        constraint = project_owner.key() == project.owner @ ContractError::InvalidProjectOwner
+    )]
+    pub project_owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = project_owner,
+        seeds = [b"project", project.seed_owner().as_ref(), project.mint.as_ref()],
+        bump = project.project_bump,
+        constraint = project.is_finished() @ ContractError::ProjectNotFinished,
+        constraint = project.open_vintages == 0 @ ContractError::ProjectHasOpenVintages,
+        // its open vintages are only known once migrated
+        constraint = project.version == ACCOUNT_VERSION @ ContractError::AccountNotMigrated,
+    )]
+    pub project: Box<Account<'info, Project>>,
+
+    /// The project's vault, must be empty
+    #[account(
+        mut,
+        token::authority = carbon_credits,
+        constraint = vault.mint == project.token_mint @ ContractError::InvalidProjectMint,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Box<Account<'info, CarbonCredits>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> CloseProject<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.vault.to_account_info(),
+                destination: self.project_owner.to_account_info(),
+                authority: self.carbon_credits.to_account_info(),
+            },
+            &[&[b"carbon_credits", &[self.carbon_credits.bump]]],
+        ))
+    }
+}
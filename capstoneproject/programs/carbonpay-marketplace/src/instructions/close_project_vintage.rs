@@ -0,0 +1,67 @@
+use crate::errors::ContractError;
+use crate::migration::ACCOUNT_VERSION;
+use crate::state::{CarbonCredits, Project, ProjectVintage};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{close_account, CloseAccount, Token, TokenAccount};
+
+/// Project owner closes a finished vintage tranche (sold out and everything offset
+/// or burned after expiry, with no offset request of the project left to review).
+/// Rent of the vintage and its empty vault goes back to the owner. Every vintage
+/// must be closed before the project itself.
+#[derive(Accounts)]
+pub struct CloseProjectVintage<'info> {
+    #[account(
+        mut,
+        constraint = project_owner.key() == project.owner @ ContractError::InvalidProjectOwner
+    )]
+    pub project_owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"project", project.seed_owner().as_ref(), project.mint.as_ref()],
+        bump = project.project_bump,
+        constraint = project.pending_offsets == 0 @ ContractError::VintageNotFinished,
+        // its open vintages are only known once migrated
+        constraint = project.version == ACCOUNT_VERSION @ ContractError::AccountNotMigrated,
+    )]
+    pub project: Box<Account<'info, Project>>,
+
+    #[account(
+        mut,
+        close = project_owner,
+        seeds = [b"vintage", project.key().as_ref(), &vintage.vintage_year.to_le_bytes()],
+        bump = vintage.bump,
+        constraint = vintage.is_finished() @ ContractError::VintageNotFinished,
+    )]
+    pub vintage: Box<Account<'info, ProjectVintage>>,
+
+    /// The vintage's vault, must be empty
+    #[account(
+        mut,
+        address = vintage.vault @ ContractError::InvalidVintage,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Box<Account<'info, CarbonCredits>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> CloseProjectVintage<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        self.project.record_vintage_closed()?;
+        close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.vault.to_account_info(),
+                destination: self.project_owner.to_account_info(),
+                authority: self.carbon_credits.to_account_info(),
+            },
+            &[&[b"carbon_credits", &[self.carbon_credits.bump]]],
+        ))
+    }
+}
@@ -0,0 +1,31 @@
+use crate::errors::ContractError;
+use crate::migration::ACCOUNT_VERSION;
+use crate::state::Purchase;
+use anchor_lang::prelude::*;
+
+/// Buyer closes a purchase whose credits have all been offset, once none of its
+/// offset requests is left to review, and gets its rent back
+#[derive(Accounts)]
+pub struct ClosePurchase<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        mut,
+        close = buyer,
+        seeds = [b"purchase", buyer.key().as_ref(), purchase.project.as_ref(), purchase.nft_mint.as_ref()],
+        bump = purchase.purchase_bump,
+        constraint = purchase.remaining_amount == 0 @ ContractError::PurchaseNotFullyOffset,
+        // requests from before version 3 are only counted once migrated
+        constraint = purchase.version == ACCOUNT_VERSION @ ContractError::AccountNotMigrated,
+        constraint = purchase.pending_requests == 0 @ ContractError::PurchaseHasPendingRequests,
+    )]
+    pub purchase: Box<Account<'info, Purchase>>,
+}
+
+impl<'info> ClosePurchase<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        msg!("Closed purchase {}", self.purchase.key());
+        Ok(())
+    }
+}
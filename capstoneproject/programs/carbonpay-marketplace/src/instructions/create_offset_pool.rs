@@ -0,0 +1,50 @@
+use crate::state::OffsetPool;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+/// Creates the offset pool of `authority` and funds it with `deposit` lamports
+/// from `payer`. A program signs as `authority` with one of its PDAs.
+#[derive(Accounts)]
+pub struct CreateOffsetPool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = OffsetPool::DISCRIMINATOR_SIZE + OffsetPool::INIT_SPACE,
+        seeds = [b"offset_pool", authority.key().as_ref()],
+        bump
+    )]
+    pub offset_pool: Box<Account<'info, OffsetPool>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateOffsetPool<'info> {
+    pub fn handler(&mut self, deposit: u64, bumps: &CreateOffsetPoolBumps) -> Result<()> {
+        self.offset_pool.set_inner(OffsetPool {
+            authority: self.authority.key(),
+            retirements: 0,
+            total_retired: 0,
+            total_spent: 0,
+            bump: bumps.offset_pool,
+        });
+
+        if deposit > 0 {
+            transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.payer.to_account_info(),
+                        to: self.offset_pool.to_account_info(),
+                    },
+                ),
+                deposit,
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use crate::state::RetirementFund;
+use anchor_lang::prelude::*;
+
+/// Manager creates a pooled retirement fund buying from `projects` at up to
+/// `max_price_per_token`. Anyone may retire from it once `period_seconds` have
+/// passed since the last retirement; the manager at any time.
+#[derive(Accounts)]
+#[instruction(fund_id: u64)]
+pub struct CreateRetirementFund<'info> {
+    #[account(mut)]
+    pub manager: Signer<'info>,
+
+    #[account(
+        init,
+        payer = manager,
+        space = RetirementFund::DISCRIMINATOR_SIZE + RetirementFund::INIT_SPACE,
+        seeds = [b"retirement_fund", manager.key().as_ref(), &fund_id.to_le_bytes()],
+        bump
+    )]
+    pub retirement_fund: Box<Account<'info, RetirementFund>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateRetirementFund<'info> {
+    pub fn handler(
+        &mut self,
+        fund_id: u64,
+        projects: Vec<Pubkey>,
+        max_price_per_token: u64,
+        period_seconds: i64,
+        bumps: &CreateRetirementFundBumps,
+    ) -> Result<()> {
+        RetirementFund::validate_config(&projects, period_seconds)?;
+
+        self.retirement_fund.set_inner(RetirementFund {
+            manager: self.manager.key(),
+            fund_id,
+            projects,
+            max_price_per_token,
+            period_seconds,
+            next_retirement: Clock::get()?.unix_timestamp,
+            epoch: 0,
+            total_unspent: 0,
+            unspent_per_lamport: RetirementFund::ACC_PRECISION,
+            acc_retired_per_lamport: 0,
+            total_deposited: 0,
+            total_retired: 0,
+            total_spent: 0,
+            retirements: 0,
+            bump: bumps.retirement_fund,
+        });
+        Ok(())
+    }
+}
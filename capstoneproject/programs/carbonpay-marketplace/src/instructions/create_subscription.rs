@@ -0,0 +1,68 @@
+use crate::state::Subscription;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+/// Customer creates a recurring offset subscription and funds it with `deposit`
+/// lamports. The first period can be executed right away.
+#[derive(Accounts)]
+#[instruction(subscription_id: u64)]
+pub struct CreateSubscription<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = Subscription::DISCRIMINATOR_SIZE + Subscription::INIT_SPACE,
+        seeds = [b"subscription", owner.key().as_ref(), &subscription_id.to_le_bytes()],
+        bump
+    )]
+    pub subscription: Box<Account<'info, Subscription>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateSubscription<'info> {
+    pub fn handler(
+        &mut self,
+        subscription_id: u64,
+        projects: Vec<Pubkey>,
+        tokens_per_period: u64,
+        period_seconds: i64,
+        max_price_per_token: u64,
+        crank_tip: u64,
+        deposit: u64,
+        bumps: &CreateSubscriptionBumps,
+    ) -> Result<()> {
+        Subscription::validate_config(&projects, tokens_per_period, period_seconds)?;
+
+        self.subscription.set_inner(Subscription {
+            owner: self.owner.key(),
+            subscription_id,
+            projects,
+            tokens_per_period,
+            period_seconds,
+            max_price_per_token,
+            crank_tip,
+            next_execution: Clock::get()?.unix_timestamp,
+            executions: 0,
+            total_retired: 0,
+            total_spent: 0,
+            bump: bumps.subscription,
+        });
+
+        if deposit > 0 {
+            transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.owner.to_account_info(),
+                        to: self.subscription.to_account_info(),
+                    },
+                ),
+                deposit,
+            )?;
+        }
+        Ok(())
+    }
+}
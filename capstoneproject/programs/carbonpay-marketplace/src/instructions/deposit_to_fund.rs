@@ -0,0 +1,83 @@
+use crate::events::FundDeposited;
+use crate::state::{FundEpoch, FundShare, RetirementFund};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+/// Depositor adds `amount` lamports to a retirement fund, after settling what the
+/// fund spent and retired of their earlier deposits. Deposits are donations: they
+/// are only ever spent on retirements and cannot be withdrawn.
+#[derive(Accounts)]
+pub struct DepositToFund<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"retirement_fund", retirement_fund.manager.as_ref(), &retirement_fund.fund_id.to_le_bytes()],
+        bump = retirement_fund.bump,
+    )]
+    pub retirement_fund: Box<Account<'info, RetirementFund>>,
+
+    /// the depositor's shares, created on the first deposit
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        space = FundShare::DISCRIMINATOR_SIZE + FundShare::INIT_SPACE,
+        seeds = [b"fund_share", retirement_fund.key().as_ref(), depositor.key().as_ref()],
+        bump
+    )]
+    pub fund_share: Box<Account<'info, FundShare>>,
+
+    /// record of the epoch of the depositor's last deposit, required once it ended
+    #[account(
+        seeds = [b"fund_epoch", retirement_fund.key().as_ref(), &fund_share.epoch.to_le_bytes()],
+        bump = ended_epoch.bump,
+    )]
+    pub ended_epoch: Option<Box<Account<'info, FundEpoch>>>,
+
+    /// record of the epoch after it, required once that one ended too
+    #[account(
+        seeds = [b"fund_epoch", retirement_fund.key().as_ref(), &fund_share.epoch.saturating_add(1).to_le_bytes()],
+        bump = next_epoch.bump,
+    )]
+    pub next_epoch: Option<Box<Account<'info, FundEpoch>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DepositToFund<'info> {
+    pub fn handler(&mut self, amount: u64, bumps: &DepositToFundBumps) -> Result<()> {
+        if self.fund_share.depositor == Pubkey::default() {
+            self.fund_share.fund = self.retirement_fund.key();
+            self.fund_share.depositor = self.depositor.key();
+            self.fund_share.bump = bumps.fund_share;
+        }
+
+        self.retirement_fund.record_deposit(amount)?;
+        self.fund_share.record_deposit(
+            &self.retirement_fund,
+            self.ended_epoch.as_deref().map(|epoch| &**epoch),
+            self.next_epoch.as_deref().map(|epoch| &**epoch),
+            amount,
+        )?;
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.depositor.to_account_info(),
+                    to: self.retirement_fund.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        emit!(FundDeposited {
+            retirement_fund: self.retirement_fund.key(),
+            depositor: self.depositor.key(),
+            amount,
+            unspent: self.fund_share.unspent,
+            total_unspent: self.retirement_fund.total_unspent,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+use crate::errors::ContractError;
+use crate::events::SubscriptionExecuted;
+use crate::state::{CarbonCredits, OffsetRequest, Project, RetirementDetails, SalesStats, Subscription};
+use crate::vault_retirement::{spendable_lamports, Payer, VaultRetirement};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+/// Permissionless crank: once a period has elapsed, buys `tokens_per_period` credits
+/// from one of the subscription's projects with the subscription's balance and
+/// retires them straight from the vault. The retirement is recorded for review like
+/// a checkout retirement, as an offset request of the subscription's owner with id
+/// `sub-<subscription_id>-<execution>`; the subscription's balance covers its rent,
+/// which the owner gets back when closing it. The cranker is paid `crank_tip`.
+/// The project's beneficiaries must be passed as writable remaining accounts,
+/// in the same order as `project.beneficiaries`.
+#[derive(Accounts)]
+pub struct ExecuteSubscription<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"subscription", subscription.owner.as_ref(), &subscription.subscription_id.to_le_bytes()],
+        bump = subscription.bump,
+        constraint = subscription.projects.contains(&project.key()) @ ContractError::ProjectNotInSubscription,
+    )]
+    pub subscription: Box<Account<'info, Subscription>>,
+
+    #[account(
+        mut,
+        seeds = [b"project", project.seed_owner().as_ref(), project.mint.as_ref()],
+        bump = project.project_bump,
+        constraint = project.is_active() @ ContractError::ProjectInactive,
+    )]
+    pub project: Box<Account<'info, Project>>,
+
+    /// The project's fungible token mint
+    #[account(
+        mut,
+        constraint = token_mint.key() == project.token_mint @ ContractError::InvalidProjectMint,
+    )]
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    /// The project's vault, the retired credits are burned from it
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = carbon_credits,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Box<Account<'info, CarbonCredits>>,
+
+    /// SOL/USD price account, required when the project is priced in USD cents
+    /// CHECK: must be the feed configured on carbon_credits; decoded in the handler
+    #[account(
+        constraint = price_feed.key() == carbon_credits.price_feed @ ContractError::InvalidPriceFeed
+    )]
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// The retirement record, its rent is refunded to the cranker by the subscription
+    #[account(
+        init,
+        payer = cranker,
+        space = OffsetRequest::DISCRIMINATOR_SIZE + OffsetRequest::INIT_SPACE,
+        seeds = [
+            b"offset_request",
+            subscription.owner.as_ref(),
+            Pubkey::default().as_ref(),
+            subscription.next_request_id().as_bytes(),
+        ],
+        bump
+    )]
+    pub offset_request: Box<Account<'info, OffsetRequest>>,
+
+    /// daily figures of the project, created on first use
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = SalesStats::DISCRIMINATOR_SIZE + SalesStats::INIT_SPACE,
+        seeds = [b"project_stats", project.key().as_ref()],
+        bump
+    )]
+    pub project_stats: Box<Account<'info, SalesStats>>,
+
+    /// daily figures of the platform, created on first use
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = SalesStats::DISCRIMINATOR_SIZE + SalesStats::INIT_SPACE,
+        seeds = [b"platform_stats"],
+        bump
+    )]
+    pub platform_stats: Box<Account<'info, SalesStats>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ExecuteSubscription<'info> {
+    pub fn handler(
+        &mut self,
+        beneficiary_accounts: &[AccountInfo<'info>],
+        bumps: &ExecuteSubscriptionBumps,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= self.subscription.next_execution, ContractError::SubscriptionNotDue);
+
+        let subscription_info = self.subscription.to_account_info();
+        let mut retirement = VaultRetirement {
+            project: &mut self.project,
+            token_mint: &self.token_mint,
+            vault: &self.vault,
+            carbon_credits: &mut self.carbon_credits,
+            price_feed: self.price_feed.as_ref().map(|feed| feed.as_ref()),
+            project_stats: &mut self.project_stats,
+            project_stats_bump: bumps.project_stats,
+            platform_stats: &mut self.platform_stats,
+            platform_stats_bump: bumps.platform_stats,
+            token_program: &self.token_program,
+        };
+
+        // 1) price the period
+        let amount = self.subscription.tokens_per_period;
+        let (total, fee) = retirement.quote(amount, now)?;
+        let max_total = amount
+            .checked_mul(self.subscription.max_price_per_token)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        require!(total <= max_total, ContractError::SubscriptionPriceTooHigh);
+        let tip = self.subscription.crank_tip;
+        let spent = total.checked_add(tip).ok_or(ContractError::ArithmeticOverflow)?;
+        let record_rent = self.offset_request.get_lamports();
+
+        // 2) the subscription must stay rent exempt after paying
+        require!(
+            spent.checked_add(record_rent).ok_or(ContractError::ArithmeticOverflow)?
+                <= spendable_lamports(&subscription_info)?,
+            ContractError::InsufficientSubscriptionFunds
+        );
+
+        // 3) pay, retire the credits straight from the vault and record them for review
+        let payer = Payer::Balance(&subscription_info);
+        retirement.settle(payer, amount, total, fee, beneficiary_accounts, now)?;
+        let owner = self.subscription.owner;
+        retirement.record_request(
+            &mut self.offset_request,
+            owner,
+            RetirementDetails::for_beneficiary(owner, now),
+            self.subscription.next_request_id(),
+            bumps.offset_request,
+            amount,
+            now,
+        )?;
+
+        // 4) tip the cranker and refund the record's rent
+        let to_cranker = tip.checked_add(record_rent).ok_or(ContractError::ArithmeticOverflow)?;
+        Payer::Balance(&subscription_info).pay(&self.cranker.to_account_info(), to_cranker)?;
+        self.subscription.record_execution(now, amount, spent)?;
+
+        emit!(SubscriptionExecuted {
+            subscription: self.subscription.key(),
+            owner: self.subscription.owner,
+            project: self.project.key(),
+            offset_request: self.offset_request.key(),
+            amount,
+            total_lamports: total,
+            cranker: self.cranker.key(),
+            crank_tip: tip,
+            execution: self.subscription.executions,
+            timestamp: now,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+use crate::errors::ContractError;
+use crate::events::OffsetRequestExpired;
+use crate::offset_refund::OffsetRefund;
+use crate::state::{CarbonCredits, ExpiryAction, OffsetRequest, Project, ProjectVintage, RequestStatus};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+/// Permissionless: once a pending offset request is past its review deadline, it is
+/// marked Expired and the platform's `expiry_action` is applied. The request's
+/// `purchase` is passed so its pending count drops. With AutoRefund the
+/// offset is reversed: the credits are minted back to the requester as fungible
+/// tokens and put back into the purchase's balance, so `token_mint` and
+/// `requester_token_account` must be passed too. When the offset had used up the
+/// purchase, its burned balance NFT is minted back too (`balance_nft_mint` and
+/// `requester_nft_account`). Checkout retirements, and requests whose purchase the
+/// buyer has since closed, have no balance to go back to and stay retired.
+#[derive(Accounts)]
+pub struct ExpireOffsetRequest<'info> {
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = offset_request.status == RequestStatus::Pending @ ContractError::RequestAlreadyProcessed,
+    )]
+    pub offset_request: Box<Account<'info, OffsetRequest>>,
+
+    /// the project the request is for
+    #[account(
+        mut,
+        constraint = project.key() == offset_request.project @ ContractError::InvalidProject,
+    )]
+    pub project: Box<Account<'info, Project>>,
+
+    /// the vintage the credits came from, required for refunds when `offset_request.vintage` is set
+    #[account(
+        mut,
+        constraint = vintage.key() == offset_request.vintage @ ContractError::InvalidVintage,
+    )]
+    pub vintage: Option<Box<Account<'info, ProjectVintage>>>,
+
+    /// the purchase the credits came from, its pending count drops
+    /// CHECK: must be the request's purchase; decoded in the handler unless the buyer closed it
+    #[account(
+        mut,
+        address = offset_request.purchase @ ContractError::InvalidPurchase,
+    )]
+    pub purchase: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Box<Account<'info, CarbonCredits>>,
+
+    /// The fungible token mint of the project or of the vintage, for refunds
+    #[account(mut)]
+    pub token_mint: Option<Box<Account<'info, Mint>>>,
+
+    /// Requester's token account the refunded credits are minted to
+    #[account(
+        mut,
+        constraint = requester_token_account.owner == offset_request.offset_requester @ ContractError::Unauthorized,
+    )]
+    pub requester_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// The purchase's current balance NFT mint, for refunds of requests that used it up
+    #[account(mut)]
+    pub balance_nft_mint: Option<Box<Account<'info, Mint>>>,
+
+    /// Requester's token account the balance NFT is minted back to
+    #[account(
+        mut,
+        constraint = requester_nft_account.owner == offset_request.offset_requester @ ContractError::Unauthorized,
+    )]
+    pub requester_nft_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> ExpireOffsetRequest<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(self.offset_request.is_expired(now), ContractError::ReviewDeadlineNotReached);
+
+        let amount = self.offset_request.amount;
+        let expiry_action = self.carbon_credits.expiry_action;
+        let mut refund = self.offset_refund();
+        let (action, refunded_amount) = match expiry_action {
+            ExpiryAction::AutoRefund if refund.has_open_purchase()? => {
+                refund.refund(amount)?;
+                (ExpiryAction::AutoRefund, amount)
+            }
+            // nothing to refund into: the credits stay retired
+            _ => (ExpiryAction::AutoApprove, 0),
+        };
+        refund.release_purchase()?;
+
+        let offset_request = &mut self.offset_request;
+        offset_request.status = RequestStatus::Expired;
+        offset_request.processed_date = now;
+        offset_request.expiry_action = Some(action);
+        self.project.record_offset_processed()?;
+        self.carbon_credits.record_expiry(refunded_amount)?;
+
+        emit!(OffsetRequestExpired {
+            offset_request: offset_request.key(),
+            offset_requester: offset_request.offset_requester,
+            project: offset_request.project,
+            amount,
+            action,
+            review_deadline: offset_request.review_deadline,
+            expired_at: now,
+        });
+        Ok(())
+    }
+
+    fn offset_refund(&mut self) -> OffsetRefund<'_, 'info> {
+        OffsetRefund {
+            offset_request: &self.offset_request,
+            project: &mut self.project,
+            vintage: self.vintage.as_deref_mut(),
+            purchase: self.purchase.as_ref().map(|purchase| purchase.as_ref()),
+            carbon_credits: &self.carbon_credits,
+            token_mint: self.token_mint.as_deref(),
+            requester_token_account: self.requester_token_account.as_deref(),
+            balance_nft_mint: self.balance_nft_mint.as_deref(),
+            requester_nft_account: self.requester_nft_account.as_deref(),
+            token_program: &self.token_program,
+        }
+    }
+}
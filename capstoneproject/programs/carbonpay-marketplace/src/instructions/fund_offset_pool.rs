@@ -0,0 +1,34 @@
+use crate::state::OffsetPool;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+/// Adds lamports to an offset pool's balance
+#[derive(Accounts)]
+pub struct FundOffsetPool<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"offset_pool", offset_pool.authority.as_ref()],
+        bump = offset_pool.bump,
+    )]
+    pub offset_pool: Box<Account<'info, OffsetPool>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> FundOffsetPool<'info> {
+    pub fn handler(&mut self, amount: u64) -> Result<()> {
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.funder.to_account_info(),
+                    to: self.offset_pool.to_account_info(),
+                },
+            ),
+            amount,
+        )
+    }
+}
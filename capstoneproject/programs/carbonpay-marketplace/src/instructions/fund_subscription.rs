@@ -0,0 +1,34 @@
+use crate::state::Subscription;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+/// Adds lamports to a subscription's balance
+#[derive(Accounts)]
+pub struct FundSubscription<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"subscription", subscription.owner.as_ref(), &subscription.subscription_id.to_le_bytes()],
+        bump = subscription.bump,
+    )]
+    pub subscription: Box<Account<'info, Subscription>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> FundSubscription<'info> {
+    pub fn handler(&mut self, amount: u64) -> Result<()> {
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.funder.to_account_info(),
+                    to: self.subscription.to_account_info(),
+                },
+            ),
+            amount,
+        )
+    }
+}
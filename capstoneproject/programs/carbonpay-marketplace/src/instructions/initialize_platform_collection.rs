@@ -0,0 +1,141 @@
+use crate::errors::ContractError;
+use crate::state::CarbonCredits;
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{
+        create_master_edition_v3, create_metadata_accounts_v3,
+        mpl_token_metadata::types::{CollectionDetails, Creator, DataV2},
+        CreateMasterEditionV3, CreateMetadataAccountsV3, Metadata,
+    },
+    token::{mint_to, Mint, MintTo, Token, TokenAccount},
+};
+
+/// Creates the CarbonPay platform collection NFT. The `carbon_credits` PDA is its
+/// mint authority, holder and update authority, so only the program can verify
+/// project NFTs into it.
+#[derive(Accounts)]
+pub struct InitializePlatformCollection<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == carbon_credits.authority @ ContractError::UnauthorizedAdmin
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+        constraint = carbon_credits.collection_mint == Pubkey::default() @ ContractError::PlatformCollectionExists,
+    )]
+    pub carbon_credits: Box<Account<'info, CarbonCredits>>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"platform_collection"],
+        bump,
+        mint::decimals = 0,
+        mint::authority = carbon_credits,
+        mint::freeze_authority = carbon_credits,
+    )]
+    pub collection_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = collection_mint,
+        associated_token::authority = carbon_credits,
+    )]
+    pub collection_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: created via CPI to the token metadata program
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: created via CPI to the token metadata program
+    #[account(mut)]
+    pub collection_master_edition: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> InitializePlatformCollection<'info> {
+    pub fn handler(&mut self, uri: String) -> Result<()> {
+        let signer_seeds: &[&[&[u8]]] = &[&[b"carbon_credits", &[self.carbon_credits.bump]]];
+
+        // 1) mint the single collection token to the carbon_credits PDA
+        mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                MintTo {
+                    mint: self.collection_mint.to_account_info(),
+                    to: self.collection_token_account.to_account_info(),
+                    authority: self.carbon_credits.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+
+        // 2) metadata, marked as a sized collection
+        create_metadata_accounts_v3(
+            CpiContext::new_with_signer(
+                self.token_metadata_program.to_account_info(),
+                CreateMetadataAccountsV3 {
+                    metadata: self.collection_metadata.to_account_info(),
+                    mint: self.collection_mint.to_account_info(),
+                    mint_authority: self.carbon_credits.to_account_info(),
+                    payer: self.authority.to_account_info(),
+                    update_authority: self.carbon_credits.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                    rent: self.rent.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            DataV2 {
+                name: "CarbonPay".to_string(),
+                symbol: "CRBN".to_string(),
+                uri,
+                seller_fee_basis_points: 0,
+                creators: Some(vec![Creator {
+                    address: self.carbon_credits.key(),
+                    verified: true,
+                    share: 100,
+                }]),
+                collection: None,
+                uses: None,
+            },
+            true,
+            true,
+            Some(CollectionDetails::V1 { size: 0 }),
+        )?;
+
+        // 3) master edition
+        create_master_edition_v3(
+            CpiContext::new_with_signer(
+                self.token_metadata_program.to_account_info(),
+                CreateMasterEditionV3 {
+                    edition: self.collection_master_edition.to_account_info(),
+                    mint: self.collection_mint.to_account_info(),
+                    update_authority: self.carbon_credits.to_account_info(),
+                    mint_authority: self.carbon_credits.to_account_info(),
+                    metadata: self.collection_metadata.to_account_info(),
+                    payer: self.authority.to_account_info(),
+                    token_program: self.token_program.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                    rent: self.rent.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            Some(0),
+        )?;
+
+        self.carbon_credits.collection_mint = self.collection_mint.key();
+        Ok(())
+    }
+}
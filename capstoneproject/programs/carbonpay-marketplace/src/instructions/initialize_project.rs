@@ -1,16 +1,29 @@
-use crate::state::{CarbonCredits, Project};
+use crate::ed25519::verify_ed25519_signature;
+use crate::errors::ContractError;
+use crate::events::TokenizationAttested;
+use crate::migration::ACCOUNT_VERSION;
+use crate::state::{CarbonCredits, PriceCurrency, Project, ProjectStatus, RegistryOracle, RevenueShare, TokenizationAttestation};
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
 use anchor_spl::{
     associated_token::AssociatedToken,
     metadata::{
-        create_master_edition_v3, create_metadata_accounts_v3,
-        mpl_token_metadata::types::{Creator, DataV2},
-        CreateMasterEditionV3, CreateMetadataAccountsV3, Metadata,
+        create_master_edition_v3, create_metadata_accounts_v3, verify_sized_collection_item,
+        mpl_token_metadata::types::{Collection, CollectionDetails, Creator, DataV2},
+        CreateMasterEditionV3, CreateMetadataAccountsV3, Metadata, VerifySizedCollectionItem,
     },
     token::{mint_to, set_authority, Mint, MintTo, SetAuthority, Token, TokenAccount},
 };
 
-/// ATAs for `project_owner_nft_account` and `vault` must exist before the call
+/// The owner creates `nft_mint` and an unminted `token_mint`; the owner's NFT ATA and the
+/// `carbon_credits` vault ATA are created here.
+/// The project starts in `PendingReview` and opens for sales once a verifier
+/// approves it against `document_hash`. An empty `beneficiaries` list pays all
+/// proceeds to the project owner.
+/// The project NFT is verified into the platform collection and is itself the
+/// collection its purchase NFTs are verified into; `carbon_credits` is its update authority.
+/// `amount` must be backed by an `attestation` signed by a registered registry oracle,
+/// verified by an Ed25519 program instruction placed right before this one.
 #[derive(Accounts)]
 #[instruction(
     amount: u64,
@@ -19,6 +32,10 @@ use anchor_spl::{
     uri: String,
     name: String,
     symbol: String,
+    document_hash: [u8; 32],
+    price_currency: PriceCurrency,
+    beneficiaries: Vec<RevenueShare>,
+    attestation: TokenizationAttestation,
 )]
 pub struct InitializeProject<'info> {
    
@@ -44,27 +61,31 @@ pub struct InitializeProject<'info> {
     )]
     pub nft_mint: Box<Account<'info, Mint>>,
 
-    /// The token mint - will be used for fungible tokens
+    /// The token mint - will be used for fungible tokens, nothing minted yet
     #[account(
         mut,
         mint::decimals = 0,
         mint::authority = project_owner,
         mint::freeze_authority = project_owner,
+        constraint = token_mint.supply == 0 @ ContractError::InvalidProjectMint,
     )]
     pub token_mint: Box<Account<'info, Mint>>,
 
-    /// Owner's ATA for the NFT (must exist before; create with `spl-token create-account`)
+    /// Owner's ATA for the NFT
     #[account(
-        mut,
-        constraint = project_owner_nft_account.owner == project_owner.key(),
-        constraint = project_owner_nft_account.mint  == nft_mint.key(),
+        init,
+        payer = project_owner,
+        associated_token::mint = nft_mint,
+        associated_token::authority = project_owner,
     )]
     pub project_owner_nft_account: Box<Account<'info, TokenAccount>>,
 
-    /// ATA of the `carbon_credits` PDA for fungible tokens (create off-chain)
+    /// ATA of the `carbon_credits` PDA for fungible tokens
     #[account(
-        mut,
-        token::mint = token_mint,
+        init,
+        payer = project_owner,
+        associated_token::mint = token_mint,
+        associated_token::authority = carbon_credits,
     )]
     pub vault: Box<Account<'info, TokenAccount>>,
 
@@ -86,6 +107,33 @@ pub struct InitializeProject<'info> {
     #[account(mut)] 
     pub master_edition: UncheckedAccount<'info>,
 
+    /// Platform collection NFT mint
+    #[account(
+        constraint = platform_collection_mint.key() == carbon_credits.collection_mint @ ContractError::InvalidCollection
+    )]
+    pub platform_collection_mint: Box<Account<'info, Mint>>,
+
+    /// Platform collection metadata, its size is incremented on verification
+    /// CHECK: checked by the token metadata program
+    #[account(mut)]
+    pub platform_collection_metadata: UncheckedAccount<'info>,
+
+    /// Platform collection master edition
+    /// CHECK: checked by the token metadata program
+    pub platform_collection_master_edition: UncheckedAccount<'info>,
+
+    /// Registry oracle that signed the attestation
+    #[account(
+        mut,
+        seeds = [b"registry_oracle", registry_oracle.key.as_ref()],
+        bump = registry_oracle.bump,
+    )]
+    pub registry_oracle: Box<Account<'info, RegistryOracle>>,
+
+    /// CHECK: instructions sysvar, read to find the Ed25519 verification instruction
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
     pub token_program: Program<'info, Token>,
     pub token_metadata_program: Program<'info, Metadata>,
     pub system_program: Program<'info, System>,
@@ -102,8 +150,32 @@ impl<'info> InitializeProject<'info> {
         uri: String,
         name: String,
         symbol: String,
+        document_hash: [u8; 32],
+        price_currency: PriceCurrency,
+        beneficiaries: Vec<RevenueShare>,
+        attestation: TokenizationAttestation,
         bumps: &InitializeProjectBumps,
     ) -> Result<()> {
+        // 0. The amount must be attested by a registry oracle
+        attestation.validate(amount, &self.project_owner.key(), &self.token_mint.key())?;
+        verify_ed25519_signature(
+            &self.instructions,
+            &self.registry_oracle.key,
+            &attestation.message()?,
+        )?;
+        let attestation_hash = attestation.hash()?;
+        self.registry_oracle.record_attestation()?;
+
+        let beneficiaries = if beneficiaries.is_empty() {
+            vec![RevenueShare {
+                recipient: self.project_owner.key(),
+                share_bps: Project::TOTAL_SHARE_BPS,
+            }]
+        } else {
+            beneficiaries
+        };
+        Project::validate_beneficiaries(&beneficiaries)?;
+
         // 1. Initialize on-chain project state and update totals
         self.project.set_inner(Project {
             owner: self.project_owner.key(),
@@ -117,7 +189,24 @@ impl<'info> InitializeProject<'info> {
             carbon_pay_fee,
             carbon_pay_authority: self.carbon_credits.key(),
             project_bump: bumps.project,
-            is_active: true,
+            status: ProjectStatus::PendingReview,
+            document_hash,
+            reviewer: None,
+            reviewed_at: 0,
+            status_reason: 0,
+            price_currency,
+            beneficiaries,
+            pending_offsets: 0,
+            presale: None,
+            sale_expiry: None,
+            expired_amount: 0,
+            registry_oracle: self.registry_oracle.key,
+            attestation_hash,
+            purchase_count: 0,
+            version: ACCOUNT_VERSION,
+            creator: self.project_owner.key(),
+            open_vintages: 0,
+            reserved: [0; Project::RESERVED_SPACE],
         });
         self.carbon_credits.add_project_credits(amount)?;
 
@@ -158,6 +247,21 @@ impl<'info> InitializeProject<'info> {
             Some(self.carbon_credits.key()),
         )?;
 
+        // and drop the freeze authority, so the owner cannot freeze buyers' credits
+        let cpi_clear_freeze_authority = CpiContext::new(
+            self.token_program.to_account_info(),
+            SetAuthority {
+                account_or_mint: self.token_mint.to_account_info(),
+                current_authority: self.project_owner.to_account_info(),
+            },
+        );
+
+        set_authority(
+            cpi_clear_freeze_authority,
+            anchor_spl::token::spl_token::instruction::AuthorityType::FreezeAccount,
+            None,
+        )?;
+
         // 5. Metadata + master edition for the NFT
         let data = DataV2 {
             name,
@@ -168,36 +272,41 @@ impl<'info> InitializeProject<'info> {
                 Creator { address: self.project_owner.key(), verified: true, share: 95 },
                 Creator { address: self.carbon_credits.key(), verified: false, share: 5 },
             ]),
-            collection: None,
+            collection: Some(Collection {
+                verified: false,
+                key: self.platform_collection_mint.key(),
+            }),
             uses: None,
         };
-        // Metadata
+        let signer_seeds: &[&[&[u8]]] = &[&[b"carbon_credits", &[self.carbon_credits.bump]]];
+        // Metadata (a sized collection for the project's purchase NFTs)
         create_metadata_accounts_v3(
-            CpiContext::new(
+            CpiContext::new_with_signer(
                 self.token_metadata_program.to_account_info(),
                 CreateMetadataAccountsV3 {
                     metadata:        self.metadata.to_account_info(),
                     mint:            self.nft_mint.to_account_info(),
                     mint_authority:  self.project_owner.to_account_info(),
                     payer:           self.project_owner.to_account_info(),
-                    update_authority:self.project_owner.to_account_info(),
+                    update_authority:self.carbon_credits.to_account_info(),
                     system_program:  self.system_program.to_account_info(),
                     rent:            self.rent.to_account_info(),
                 },
+                signer_seeds,
             ),
             data,
             true,
             true,
-            None,
+            Some(CollectionDetails::V1 { size: 0 }),
         )?;
         // Master edition
         create_master_edition_v3(
-            CpiContext::new(
+            CpiContext::new_with_signer(
                 self.token_metadata_program.to_account_info(),
                 CreateMasterEditionV3 {
                     edition:         self.master_edition.to_account_info(),
                     mint:            self.nft_mint.to_account_info(),
-                    update_authority:self.project_owner.to_account_info(),
+                    update_authority:self.carbon_credits.to_account_info(),
                     mint_authority:  self.project_owner.to_account_info(),
                     metadata:        self.metadata.to_account_info(),
                     payer:           self.project_owner.to_account_info(),
@@ -205,10 +314,38 @@ impl<'info> InitializeProject<'info> {
                     system_program:  self.system_program.to_account_info(),
                     rent:            self.rent.to_account_info(),
                 },
+                signer_seeds,
             ),
             Some(0), // Max supply of 0 means there will be no prints (editions) of this NFT
         )?;
 
+        // 6. Verify the project NFT into the platform collection
+        verify_sized_collection_item(
+            CpiContext::new_with_signer(
+                self.token_metadata_program.to_account_info(),
+                VerifySizedCollectionItem {
+                    payer:                     self.project_owner.to_account_info(),
+                    metadata:                  self.metadata.to_account_info(),
+                    collection_authority:      self.carbon_credits.to_account_info(),
+                    collection_mint:           self.platform_collection_mint.to_account_info(),
+                    collection_metadata:       self.platform_collection_metadata.to_account_info(),
+                    collection_master_edition: self.platform_collection_master_edition.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            None,
+        )?;
+
+        emit!(TokenizationAttested {
+            project: self.project.key(),
+            registry_oracle: self.registry_oracle.key,
+            registry_project_id: attestation.registry_project_id,
+            serial_start: attestation.serial_start,
+            serial_end: attestation.serial_end,
+            amount,
+            vintage_year: attestation.vintage_year,
+            attestation_hash,
+        });
         Ok(())
     }
 }
@@ -0,0 +1,42 @@
+use crate::errors::ContractError;
+use crate::state::{CarbonCredits, VerifierRegistry};
+use anchor_lang::prelude::*;
+
+/// Creates the verifier registry with the platform authority as its only verifier
+/// and a threshold of 1
+#[derive(Accounts)]
+pub struct InitializeVerifierRegistry<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == carbon_credits.authority @ ContractError::UnauthorizedAdmin
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Account<'info, CarbonCredits>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = VerifierRegistry::DISCRIMINATOR_SIZE + VerifierRegistry::INIT_SPACE,
+        seeds = [b"verifier_registry"],
+        bump
+    )]
+    pub verifier_registry: Account<'info, VerifierRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeVerifierRegistry<'info> {
+    pub fn handler(&mut self, bumps: &InitializeVerifierRegistryBumps) -> Result<()> {
+        self.verifier_registry.set_inner(VerifierRegistry {
+            verifiers: vec![self.authority.key()],
+            threshold: 1,
+            bump: bumps.verifier_registry,
+        });
+        Ok(())
+    }
+}
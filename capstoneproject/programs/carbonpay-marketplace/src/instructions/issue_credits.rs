@@ -0,0 +1,140 @@
+use crate::ed25519::verify_ed25519_signature;
+use crate::errors::ContractError;
+use crate::events::CreditsIssued;
+use crate::state::{CarbonCredits, IssuanceRecord, Project, RegistryOracle, TokenizationAttestation};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use anchor_spl::token::{mint_to, Mint, MintTo, Token, TokenAccount};
+
+/// Mints newly verified credits for an existing project into its vault.
+/// Like at tokenization, `amount` must be backed by an `attestation` signed by a
+/// registered registry oracle, verified by an Ed25519 program instruction placed
+/// right before this one. The `issuance_record` keeps each serial range from
+/// being issued twice, and the tokenization attestation cannot be reused.
+#[derive(Accounts)]
+#[instruction(amount: u64, document_hash: [u8; 32], attestation: TokenizationAttestation)]
+pub struct IssueCredits<'info> {
+    #[account(
+        mut,
+        constraint = project_owner.key() == project.owner @ ContractError::InvalidProjectOwner
+    )]
+    pub project_owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"project", project.seed_owner().as_ref(), project.mint.as_ref()],
+        bump = project.project_bump,
+        constraint = project.is_active() @ ContractError::ProjectInactive,
+    )]
+    pub project: Box<Account<'info, Project>>,
+
+    #[account(
+        mut,
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Box<Account<'info, CarbonCredits>>,
+
+    /// The project's fungible token mint, mint authority is the carbon_credits PDA
+    #[account(
+        mut,
+        constraint = token_mint.key() == project.token_mint @ ContractError::InvalidProjectMint,
+    )]
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    /// The project's vault
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = carbon_credits,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    /// Registry oracle that signed the attestation
+    #[account(
+        mut,
+        seeds = [b"registry_oracle", registry_oracle.key.as_ref()],
+        bump = registry_oracle.bump,
+    )]
+    pub registry_oracle: Box<Account<'info, RegistryOracle>>,
+
+    /// Record of the attested serial range
+    #[account(
+        init,
+        payer = project_owner,
+        space = IssuanceRecord::DISCRIMINATOR_SIZE + IssuanceRecord::INIT_SPACE,
+        seeds = [b"issuance", project.key().as_ref(), &attestation.serial_start.to_le_bytes()],
+        bump
+    )]
+    pub issuance_record: Box<Account<'info, IssuanceRecord>>,
+
+    /// CHECK: instructions sysvar, read to find the Ed25519 verification instruction
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> IssueCredits<'info> {
+    pub fn handler(
+        &mut self,
+        amount: u64,
+        document_hash: [u8; 32],
+        attestation: TokenizationAttestation,
+        bumps: &IssueCreditsBumps,
+    ) -> Result<()> {
+        require!(amount > 0, ContractError::InvalidAmount);
+
+        // 0) the new credits must be attested by a registry oracle
+        attestation.validate(amount, &self.project.owner, &self.token_mint.key())?;
+        verify_ed25519_signature(
+            &self.instructions,
+            &self.registry_oracle.key,
+            &attestation.message()?,
+        )?;
+        let attestation_hash = attestation.hash()?;
+        require!(
+            attestation_hash != self.project.attestation_hash,
+            ContractError::AttestationAlreadyUsed
+        );
+        self.registry_oracle.record_attestation()?;
+        self.issuance_record.set_inner(IssuanceRecord {
+            project: self.project.key(),
+            registry_oracle: self.registry_oracle.key,
+            serial_start: attestation.serial_start,
+            serial_end: attestation.serial_end,
+            attestation_hash,
+            issued_at: Clock::get()?.unix_timestamp,
+            bump: bumps.issuance_record,
+        });
+
+        // 1) mint the new credits into the vault
+        mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                MintTo {
+                    mint: self.token_mint.to_account_info(),
+                    to: self.vault.to_account_info(),
+                    authority: self.carbon_credits.to_account_info(),
+                },
+                &[&[b"carbon_credits", &[self.carbon_credits.bump]]],
+            ),
+            amount,
+        )?;
+
+        // 2) update project and platform totals
+        self.project.record_issuance(amount)?;
+        self.carbon_credits.add_issued_credits(amount)?;
+
+        emit!(CreditsIssued {
+            project: self.project.key(),
+            amount,
+            registry_oracle: self.registry_oracle.key,
+            attestation_hash,
+            document_hash,
+            total_amount: self.project.amount,
+        });
+        Ok(())
+    }
+}
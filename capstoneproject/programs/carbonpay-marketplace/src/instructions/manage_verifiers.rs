@@ -0,0 +1,39 @@
+use crate::errors::ContractError;
+use crate::state::{CarbonCredits, VerifierRegistry};
+use anchor_lang::prelude::*;
+
+/// Platform authority adds or removes verifiers and sets the quorum threshold
+#[derive(Accounts)]
+pub struct ManageVerifiers<'info> {
+    #[account(
+        constraint = authority.key() == carbon_credits.authority @ ContractError::UnauthorizedAdmin
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Account<'info, CarbonCredits>,
+
+    #[account(
+        mut,
+        seeds = [b"verifier_registry"],
+        bump = verifier_registry.bump,
+    )]
+    pub verifier_registry: Account<'info, VerifierRegistry>,
+}
+
+impl<'info> ManageVerifiers<'info> {
+    pub fn add_verifier(&mut self, verifier: Pubkey) -> Result<()> {
+        self.verifier_registry.add_verifier(verifier)
+    }
+
+    pub fn remove_verifier(&mut self, verifier: Pubkey) -> Result<()> {
+        self.verifier_registry.remove_verifier(verifier)
+    }
+
+    pub fn set_threshold(&mut self, threshold: u8) -> Result<()> {
+        self.verifier_registry.set_threshold(threshold)
+    }
+}
@@ -0,0 +1,248 @@
+use crate::errors::ContractError;
+use crate::events::AccountMigrated;
+use crate::migration::{migrate_account, Versioned, ACCOUNT_VERSION};
+use crate::state::{CarbonCredits, OffsetRequest, Project, ProjectVintage, Purchase, RequestStatus};
+use anchor_lang::prelude::*;
+use anchor_spl::metadata::{
+    set_collection_size, update_metadata_accounts_v2, Metadata, SetCollectionSize,
+    UpdateMetadataAccountsV2,
+};
+
+/// Platform authority migrates the `carbon_credits` account to the current layout.
+/// This must happen before any project, purchase or offset request is migrated.
+#[derive(Accounts)]
+pub struct MigrateCarbonCredits<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: decoded from any known layout by the handler
+    #[account(
+        mut,
+        seeds = [b"carbon_credits"],
+        bump,
+    )]
+    pub carbon_credits: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateCarbonCredits<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        let authority = self.authority.key();
+        let from_version = migrate_account::<CarbonCredits>(
+            &self.carbon_credits,
+            &self.authority,
+            &self.system_program,
+            |carbon_credits, _| {
+                require_keys_eq!(authority, carbon_credits.authority, ContractError::UnauthorizedAdmin);
+                Ok(())
+            },
+        )?;
+        emit!(AccountMigrated {
+            account: self.carbon_credits.key(),
+            from_version,
+            to_version: ACCOUNT_VERSION,
+        });
+        Ok(())
+    }
+}
+
+/// The owner of a project, purchase or offset request, or the platform authority,
+/// migrates it to the current layout and pays for the extra rent.
+/// A project migrated from before version 2 passes every vintage it still has as
+/// remaining accounts, so they are counted as open. A request still pending from
+/// version 0 passes its migrated project, whose pending offsets did not count it.
+/// Version 0 project NFTs were created with the owner as update authority and
+/// not as a sized collection, so purchase NFTs could not be verified into them:
+/// the owner migrates those projects, passing the project NFT mint and metadata,
+/// and hands the metadata to `carbon_credits` as a sized collection.
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: a program-owned `Project`, `Purchase` or `OffsetRequest`, decoded from
+    /// any known layout by the handler
+    #[account(mut)]
+    pub account: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+        constraint = carbon_credits.version == ACCOUNT_VERSION @ ContractError::AccountNotMigrated,
+    )]
+    pub carbon_credits: Account<'info, CarbonCredits>,
+
+    /// CHECK: project NFT mint, required to migrate a version 0 project
+    pub project_nft_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: project NFT metadata, required to migrate a version 0 project;
+    /// checked by the token metadata program
+    #[account(mut)]
+    pub project_nft_metadata: Option<UncheckedAccount<'info>>,
+
+    pub token_metadata_program: Option<Program<'info, Metadata>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateAccount<'info> {
+    pub fn migrate_project(&mut self, vintages: &[AccountInfo]) -> Result<()> {
+        let project_key = self.account.key();
+        let from_version = self.migrate::<Project>(
+            |project| project.owner,
+            |project, from_version| {
+                if from_version >= 2 {
+                    return Ok(());
+                }
+                let mut counted: Vec<Pubkey> = Vec::with_capacity(vintages.len());
+                for info in vintages {
+                    require_keys_eq!(*info.owner, crate::ID, ContractError::InvalidVintage);
+                    let vintage = ProjectVintage::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+                    require_keys_eq!(vintage.project, project_key, ContractError::InvalidVintage);
+                    require!(!counted.contains(&info.key()), ContractError::InvalidVintage);
+                    counted.push(info.key());
+                }
+                // vintages added since the upgrade are counted already
+                require!(
+                    counted.len() >= project.open_vintages as usize,
+                    ContractError::InvalidVintage
+                );
+                project.open_vintages =
+                    u16::try_from(counted.len()).map_err(|_| ContractError::ArithmeticOverflow)?;
+                Ok(())
+            },
+        )?;
+        if from_version == 0 {
+            self.adopt_project_metadata()?;
+        }
+        Ok(())
+    }
+
+    pub fn migrate_purchase(&mut self) -> Result<()> {
+        self.migrate::<Purchase>(|purchase| purchase.buyer, |_, _| Ok(()))?;
+        Ok(())
+    }
+
+    /// Requests still pending review are counted on the accounts that only started
+    /// counting them later: the project since version 1 and the purchase since
+    /// version 3. Both are passed as remaining accounts; a purchase the buyer has
+    /// since closed is skipped.
+    pub fn migrate_offset_request(&mut self, counted_on: &[AccountInfo]) -> Result<()> {
+        self.migrate::<OffsetRequest>(
+            |request| request.offset_requester,
+            |request, from_version| {
+                if request.status != RequestStatus::Pending {
+                    return Ok(());
+                }
+                if from_version == 0 {
+                    let info = counted_on
+                        .iter()
+                        .find(|info| info.key() == request.project)
+                        .ok_or(ContractError::InvalidProject)?;
+                    require_keys_eq!(*info.owner, crate::ID, ContractError::InvalidProject);
+                    let mut project = Project::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+                    require!(project.version == ACCOUNT_VERSION, ContractError::AccountNotMigrated);
+                    project.pending_offsets = project
+                        .pending_offsets
+                        .checked_add(1)
+                        .ok_or(ContractError::ArithmeticOverflow)?;
+                    project.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+                }
+                if request.purchase == Pubkey::default() {
+                    return Ok(());
+                }
+                let info = counted_on
+                    .iter()
+                    .find(|info| info.key() == request.purchase)
+                    .ok_or(ContractError::InvalidPurchase)?;
+                if info.data_is_empty() {
+                    return Ok(());
+                }
+                require_keys_eq!(*info.owner, crate::ID, ContractError::InvalidPurchase);
+                require!(
+                    info.data_len() == Purchase::DISCRIMINATOR_SIZE + Purchase::INIT_SPACE,
+                    ContractError::AccountNotMigrated
+                );
+                let mut purchase = Purchase::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+                purchase.pending_requests = purchase
+                    .pending_requests
+                    .checked_add(1)
+                    .ok_or(ContractError::ArithmeticOverflow)?;
+                purchase.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Make `carbon_credits` the update authority of a version 0 project NFT and
+    /// turn it into a sized collection, as `initialize_project` creates them.
+    /// Purchase NFTs of version 0 were never verified into it, so it starts empty.
+    fn adopt_project_metadata(&self) -> Result<()> {
+        let project = Project::try_deserialize(&mut &self.account.try_borrow_data()?[..])?;
+        require_keys_eq!(self.authority.key(), project.owner, ContractError::InvalidProjectOwner);
+        let (Some(nft_mint), Some(nft_metadata), Some(token_metadata_program)) = (
+            self.project_nft_mint.as_ref(),
+            self.project_nft_metadata.as_ref(),
+            self.token_metadata_program.as_ref(),
+        ) else {
+            return err!(ContractError::InvalidCollection);
+        };
+        require_keys_eq!(nft_mint.key(), project.mint, ContractError::InvalidCollection);
+
+        update_metadata_accounts_v2(
+            CpiContext::new(
+                token_metadata_program.to_account_info(),
+                UpdateMetadataAccountsV2 {
+                    metadata: nft_metadata.to_account_info(),
+                    update_authority: self.authority.to_account_info(),
+                },
+            ),
+            Some(self.carbon_credits.key()),
+            None,
+            None,
+            None,
+        )?;
+        set_collection_size(
+            CpiContext::new_with_signer(
+                token_metadata_program.to_account_info(),
+                SetCollectionSize {
+                    metadata: nft_metadata.to_account_info(),
+                    mint: nft_mint.to_account_info(),
+                    update_authority: self.carbon_credits.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                },
+                &[&[b"carbon_credits", &[self.carbon_credits.bump]]],
+            ),
+            None,
+            0,
+        )
+    }
+
+    fn migrate<T: Versioned>(
+        &mut self,
+        owner: impl FnOnce(&T) -> Pubkey,
+        complete: impl FnOnce(&mut T, u8) -> Result<()>,
+    ) -> Result<u8> {
+        let authority = self.authority.key();
+        let platform_authority = self.carbon_credits.authority;
+        let from_version = migrate_account::<T>(
+            &self.account,
+            &self.authority,
+            &self.system_program,
+            |account, from_version| {
+                require!(
+                    authority == owner(account) || authority == platform_authority,
+                    ContractError::Unauthorized
+                );
+                complete(account, from_version)
+            },
+        )?;
+        emit!(AccountMigrated {
+            account: self.account.key(),
+            from_version,
+            to_version: ACCOUNT_VERSION,
+        });
+        Ok(from_version)
+    }
+}
@@ -1,9 +1,91 @@
+pub mod add_project_vintage;
+pub mod burn_expired_credits;
+pub mod cancel_subscription;
+pub mod claim_referral_fees;
+pub mod close_offset_pool;
+pub mod close_offset_request;
+pub mod close_project;
+pub mod close_project_vintage;
+pub mod close_purchase;
+pub mod create_offset_pool;
+pub mod create_retirement_fund;
+pub mod create_subscription;
+pub mod deposit_to_fund;
+pub mod execute_subscription;
+pub mod expire_offset_request;
+pub mod fund_offset_pool;
+pub mod fund_subscription;
 pub mod initialize_carbon_credits;
+pub mod initialize_platform_collection;
 pub mod initialize_project;
+pub mod initialize_verifier_registry;
+pub mod issue_credits;
+pub mod manage_verifiers;
+pub mod migrate_accounts;
+pub mod purchase_and_retire;
 pub mod purchase_carbon_credits;
+pub mod refresh_purchase_metadata;
+pub mod register_referrer;
+pub mod register_registry_oracle;
+pub mod remove_registry_oracle;
 pub mod request_offset;
+pub mod retire_from_fund;
+pub mod retire_from_pool;
+pub mod review_offset_request;
+pub mod review_project;
+pub mod set_metadata_base_uri;
+pub mod set_offset_review_policy;
+pub mod set_presale;
+pub mod set_price_oracle;
+pub mod set_project_beneficiaries;
+pub mod set_referrer_share;
+pub mod set_sale_expiry;
+pub mod suspend_project;
+pub mod transfer_project_ownership;
+pub mod update_project_metadata;
 
+pub use add_project_vintage::*;
+pub use burn_expired_credits::*;
+pub use cancel_subscription::*;
+pub use claim_referral_fees::*;
+pub use close_offset_pool::*;
+pub use close_offset_request::*;
+pub use close_project::*;
+pub use close_project_vintage::*;
+pub use close_purchase::*;
+pub use create_offset_pool::*;
+pub use create_retirement_fund::*;
+pub use create_subscription::*;
+pub use deposit_to_fund::*;
+pub use execute_subscription::*;
+pub use expire_offset_request::*;
+pub use fund_offset_pool::*;
+pub use fund_subscription::*;
 pub use initialize_carbon_credits::*;
+pub use initialize_platform_collection::*;
 pub use initialize_project::*;
+pub use initialize_verifier_registry::*;
+pub use issue_credits::*;
+pub use manage_verifiers::*;
+pub use migrate_accounts::*;
+pub use purchase_and_retire::*;
 pub use purchase_carbon_credits::*;
+pub use refresh_purchase_metadata::*;
+pub use register_referrer::*;
+pub use register_registry_oracle::*;
+pub use remove_registry_oracle::*;
 pub use request_offset::*;
+pub use retire_from_fund::*;
+pub use retire_from_pool::*;
+pub use review_offset_request::*;
+pub use review_project::*;
+pub use set_metadata_base_uri::*;
+pub use set_offset_review_policy::*;
+pub use set_presale::*;
+pub use set_price_oracle::*;
+pub use set_project_beneficiaries::*;
+pub use set_referrer_share::*;
+pub use set_sale_expiry::*;
+pub use suspend_project::*;
+pub use transfer_project_ownership::*;
+pub use update_project_metadata::*;
@@ -0,0 +1,149 @@
+use crate::errors::ContractError;
+use crate::state::{CarbonCredits, OffsetRequest, Project, RetirementDetails, SalesStats};
+use crate::vault_retirement::{Payer, VaultRetirement};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+/// Checkout flow: the buyer pays for `amount` credits of the project's own tranche
+/// and they are burned straight from the vault, without a purchase NFT or tokens
+/// ever reaching the buyer. The retirement is recorded as an offset request with
+/// no purchase (`["offset_request", buyer, Pubkey::default(), request_id]`) and is
+/// reviewed like any other. Not available during a presale. The project's
+/// beneficiaries must be passed as writable remaining accounts, in order.
+#[derive(Accounts)]
+#[instruction(amount: u64, max_total_lamports: u64, expected_fee_bps: u64, request_id: String)]
+pub struct PurchaseAndRetire<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"project", project.seed_owner().as_ref(), project.mint.as_ref()],
+        bump = project.project_bump,
+        constraint = project.is_active() @ ContractError::ProjectInactive,
+    )]
+    pub project: Box<Account<'info, Project>>,
+
+    /// The project's fungible token mint
+    #[account(
+        mut,
+        constraint = token_mint.key() == project.token_mint @ ContractError::InvalidProjectMint,
+    )]
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    /// The project's vault, the retired credits are burned from it
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = carbon_credits,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+        constraint = carbon_credits.key() == project.carbon_pay_authority @ ContractError::InvalidCarbonPayAuthority
+    )]
+    pub carbon_credits: Box<Account<'info, CarbonCredits>>,
+
+    /// SOL/USD price account, required when the project is priced in USD cents
+    /// CHECK: must be the feed configured on carbon_credits; decoded in the handler
+    #[account(
+        constraint = price_feed.key() == carbon_credits.price_feed @ ContractError::InvalidPriceFeed
+    )]
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// The retirement record
+    #[account(
+        init,
+        payer = buyer,
+        space = OffsetRequest::DISCRIMINATOR_SIZE + OffsetRequest::INIT_SPACE,
+        seeds = [b"offset_request", buyer.key().as_ref(), Pubkey::default().as_ref(), request_id.as_bytes()],
+        bump
+    )]
+    pub offset_request: Box<Account<'info, OffsetRequest>>,
+
+    /// daily figures of the project, created on first use
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = SalesStats::DISCRIMINATOR_SIZE + SalesStats::INIT_SPACE,
+        seeds = [b"project_stats", project.key().as_ref()],
+        bump
+    )]
+    pub project_stats: Box<Account<'info, SalesStats>>,
+
+    /// daily figures of the platform, created on first use
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = SalesStats::DISCRIMINATOR_SIZE + SalesStats::INIT_SPACE,
+        seeds = [b"platform_stats"],
+        bump
+    )]
+    pub platform_stats: Box<Account<'info, SalesStats>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> PurchaseAndRetire<'info> {
+    pub fn handler(
+        &mut self,
+        amount: u64,
+        max_total_lamports: u64,
+        expected_fee_bps: u64,
+        request_id: String,
+        retirement: RetirementDetails,
+        beneficiary_accounts: &[AccountInfo<'info>],
+        bumps: &PurchaseAndRetireBumps,
+    ) -> Result<()> {
+        // 1) validate
+        OffsetRequest::validate_request_id(&request_id)?;
+        retirement.validate()?;
+        let now = Clock::get()?.unix_timestamp;
+
+        let buyer_info = self.buyer.to_account_info();
+        let system_program_info = self.system_program.to_account_info();
+        let mut vault_retirement = VaultRetirement {
+            project: &mut self.project,
+            token_mint: &self.token_mint,
+            vault: &self.vault,
+            carbon_credits: &mut self.carbon_credits,
+            price_feed: self.price_feed.as_ref().map(|feed| feed.as_ref()),
+            project_stats: &mut self.project_stats,
+            project_stats_bump: bumps.project_stats,
+            platform_stats: &mut self.platform_stats,
+            platform_stats_bump: bumps.platform_stats,
+            token_program: &self.token_program,
+        };
+
+        // 2) price, with the same guards as purchase_carbon_credits
+        let (total, fee) = vault_retirement.quote(amount, now)?;
+        require!(total <= max_total_lamports, ContractError::SlippageExceeded);
+        require!(
+            vault_retirement.project.carbon_pay_fee <= expected_fee_bps,
+            ContractError::FeeAboveExpected
+        );
+
+        // 3) pay and retire the credits straight from the vault, pending review
+        let payer = Payer::Wallet {
+            account: &buyer_info,
+            system_program: &system_program_info,
+        };
+        vault_retirement.settle(payer, amount, total, fee, beneficiary_accounts, now)?;
+
+        // 4) record the retirement for review
+        vault_retirement.record_request(
+            &mut self.offset_request,
+            self.buyer.key(),
+            retirement,
+            request_id,
+            bumps.offset_request,
+            amount,
+            now,
+        )?;
+        Ok(())
+    }
+}
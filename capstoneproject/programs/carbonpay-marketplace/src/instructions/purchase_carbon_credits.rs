@@ -1,35 +1,55 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    metadata::{create_metadata_accounts_v3, mpl_token_metadata::types::{Creator, DataV2}, CreateMetadataAccountsV3, Metadata}, token::{self, Mint, MintTo, Token, TokenAccount}
+    associated_token::AssociatedToken,
+    metadata::{
+        create_metadata_accounts_v3, verify_sized_collection_item,
+        mpl_token_metadata::types::{Collection, Creator, DataV2},
+        CreateMetadataAccountsV3, Metadata, VerifySizedCollectionItem,
+    },
+    token::{self, Mint, MintTo, Token, TokenAccount},
 };
-use crate::state::{Project, Purchase, CarbonCredits};
+use crate::state::{BuyerAllocation, Project, ProjectVintage, Purchase, CarbonCredits, Referrer, SalesStats};
 use crate::errors::ContractError;
+use crate::migration::ACCOUNT_VERSION;
+use crate::events::ReferralCredited;
+use crate::oracle::quote_in_lamports;
+use crate::nft_metadata::purchase_nft_uri;
 
+/// The project's beneficiaries must be passed as writable remaining accounts,
+/// in the same order as `project.beneficiaries`.
+/// Pass `vintage` to buy from one of the project's vintage tranches, or omit it to
+/// buy from the project's own tranche.
+/// While the project's presale is running, `allowlist_proof` must prove the buyer
+/// is on the allowlist and `buyer_allocation` must be passed to track the per-wallet cap.
+/// An optional registered `referrer` receives its share of the platform fee.
+/// The purchase NFT mint is the `["purchase_nft", project, project.purchase_count]` PDA;
+/// it and the buyer's token accounts are created here, so one transaction is enough.
 #[derive(Accounts)]
 #[instruction(amount: u64)]
 pub struct PurchaseCarbonCredits<'info> {
    
     #[account(
         mut,
-        constraint = project.is_active @ ContractError::ProjectInactive,
-        constraint = project.remaining_amount >= amount @ ContractError::InsufficientTokens,
-        seeds = [b"project", project.owner.as_ref(), project.mint.as_ref()],
+        constraint = project.is_active() @ ContractError::ProjectInactive,
+        seeds = [b"project", project.seed_owner().as_ref(), project.mint.as_ref()],
         bump = project.project_bump,
     )]
-    pub project: Account<'info, Project>,
-    
-    /// CHECK: project owner is the project owner
-    /// who receives the payment
+    pub project: Box<Account<'info, Project>>,
+
+    /// vintage tranche being sold, if any
     #[account(
         mut,
-        constraint = project_owner.key() == project.owner @ ContractError::InvalidProjectOwner
+        constraint = vintage.project == project.key() @ ContractError::InvalidVintage,
+        constraint = vintage.remaining_amount >= amount @ ContractError::InsufficientTokens,
+        seeds = [b"vintage", project.key().as_ref(), &vintage.vintage_year.to_le_bytes()],
+        bump = vintage.bump,
     )]
-    pub project_owner: UncheckedAccount<'info>,
-
-    /// project's fungible token mint (separate from NFT mint)
+    pub vintage: Option<Box<Account<'info, ProjectVintage>>>,
+    
+    /// fungible token mint of the project or of the vintage (separate from NFT mint)
     #[account(
         mut,
-        constraint = project_mint.key() == project.token_mint @ ContractError::InvalidProjectMint
+        constraint = project_mint.key() == vintage.as_ref().map_or(project.token_mint, |v| v.token_mint) @ ContractError::InvalidProjectMint
     )]
     pub project_mint: Account<'info, Mint>,
 
@@ -41,39 +61,47 @@ pub struct PurchaseCarbonCredits<'info> {
     )]
     pub carbon_credits: Account<'info, CarbonCredits>,
 
-    /// project's vault ATA (already created off-chain)
+    /// vault ATA of the tranche being sold (already created off-chain). It holds
+    /// the tranche's unsold credits; `project.remaining_amount` also counts the
+    /// other tranches.
     #[account(
         mut,
         token::mint = project_mint,
         token::authority = carbon_credits,
-        owner = token::ID
+        owner = token::ID,
+        constraint = project_token_account.amount >= amount @ ContractError::InsufficientTokens,
     )]
     pub project_token_account: Account<'info, TokenAccount>,
 
-    /// purchase NFT mint (create off-chain)
+    /// purchase NFT mint, a PDA numbered by the project's purchase count
     #[account(
-        mut,
-        constraint = purchase_nft_mint.mint_authority.unwrap() == buyer.key() @ ContractError::Unauthorized
+        init,
+        payer = buyer,
+        seeds = [b"purchase_nft", project.key().as_ref(), &project.purchase_count.to_le_bytes()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = carbon_credits,
+        mint::freeze_authority = carbon_credits,
     )]
-    pub purchase_nft_mint: Account<'info, Mint>,
+    pub purchase_nft_mint: Box<Account<'info, Mint>>,
 
-    /// buyer's ATA for the purchase NFT (create off-chain)
+    /// buyer's ATA for the purchase NFT
     #[account(
-        mut,
-        token::mint = purchase_nft_mint,
-        token::authority = buyer,
-        owner = token::ID
+        init,
+        payer = buyer,
+        associated_token::mint = purchase_nft_mint,
+        associated_token::authority = buyer,
     )]
-    pub buyer_nft_account: Account<'info, TokenAccount>,
+    pub buyer_nft_account: Box<Account<'info, TokenAccount>>,
 
-    /// buyer's ATA for the fungible tokens (create off-chain)
+    /// buyer's ATA for the fungible tokens, created on the first purchase
     #[account(
-        mut,
-        token::mint = project_mint,
-        token::authority = buyer,
-        owner = token::ID
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = project_mint,
+        associated_token::authority = buyer,
     )]
-    pub buyer_token_account: Account<'info, TokenAccount>,
+    pub buyer_token_account: Box<Account<'info, TokenAccount>>,
 
     /// on-chain purchase record
     #[account(
@@ -85,39 +113,140 @@ pub struct PurchaseCarbonCredits<'info> {
     )]
     pub purchase: Box<Account<'info, Purchase>>,
 
+    /// presale purchases of this buyer, required while the presale is running
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = BuyerAllocation::DISCRIMINATOR_SIZE + BuyerAllocation::INIT_SPACE,
+        seeds = [b"buyer_allocation", project.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub buyer_allocation: Option<Box<Account<'info, BuyerAllocation>>>,
+
+    /// registered sales partner that referred the buyer, if any
+    #[account(
+        mut,
+        seeds = [b"referrer", referrer.wallet.as_ref()],
+        bump = referrer.bump,
+        constraint = referrer.wallet != buyer.key() @ ContractError::SelfReferral,
+    )]
+    pub referrer: Option<Box<Account<'info, Referrer>>>,
+
+    /// SOL/USD price account, required when the project is priced in USD cents
+    /// CHECK: must be the feed configured on carbon_credits; decoded in the handler
+    #[account(
+        constraint = price_feed.key() == carbon_credits.price_feed @ ContractError::InvalidPriceFeed
+    )]
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
     /// purchase NFT metadata account (CPI will create)
     /// CHECK: This account will be initialized by the Token Metadata program via CPI. Safe because we're just passing it to the authorized CPI call.
     #[account(mut)]
     pub purchase_metadata: UncheckedAccount<'info>,
 
+    /// project NFT mint, the collection purchase NFTs are verified into
+    #[account(
+        constraint = project_collection_mint.key() == project.mint @ ContractError::InvalidCollection
+    )]
+    pub project_collection_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: project NFT metadata, checked by the token metadata program
+    #[account(mut)]
+    pub project_collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: project NFT master edition, checked by the token metadata program
+    pub project_collection_master_edition: UncheckedAccount<'info>,
+
     #[account(mut)]
     pub buyer: Signer<'info>,
 
+    /// daily figures of the project, created on first use
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = SalesStats::DISCRIMINATOR_SIZE + SalesStats::INIT_SPACE,
+        seeds = [b"project_stats", project.key().as_ref()],
+        bump
+    )]
+    pub project_stats: Box<Account<'info, SalesStats>>,
+
+    /// daily figures of the platform, created on first use
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = SalesStats::DISCRIMINATOR_SIZE + SalesStats::INIT_SPACE,
+        seeds = [b"platform_stats"],
+        bump
+    )]
+    pub platform_stats: Box<Account<'info, SalesStats>>,
+
     pub token_program: Program<'info, Token>,
     pub token_metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 impl<'info> PurchaseCarbonCredits<'info> {
-    pub fn purchase_carbon_credits(&mut self, amount: u64, bumps: &PurchaseCarbonCreditsBumps) -> Result<()> {
+    pub fn purchase_carbon_credits(
+        &mut self,
+        amount: u64,
+        max_total_lamports: u64,
+        expected_fee_bps: u64,
+        allowlist_proof: Vec<[u8; 32]>,
+        beneficiary_accounts: &[AccountInfo<'info>],
+        bumps: &PurchaseCarbonCreditsBumps,
+    ) -> Result<()> {
+        // 0) credits past their sale expiry can't be sold; presale: allowlist and caps
+        let now = Clock::get()?.unix_timestamp;
+        let expired = match self.vintage.as_ref() {
+            Some(vintage) => vintage.is_sale_expired(now),
+            None => self.project.is_sale_expired(now),
+        };
+        require!(!expired, ContractError::CreditsExpired);
+        self.check_presale(amount, &allowlist_proof, bumps)?;
+
         // 1) payments
-        let total = amount.checked_mul(self.project.price_per_token).ok_or(ContractError::ArithmeticOverflow)?;
-        let fee  = total.checked_mul(self.project.carbon_pay_fee as u64).ok_or(ContractError::ArithmeticOverflow)?
+        let (total, sol_usd_price, sol_usd_expo) = self.total_price_in_lamports(amount)?;
+        let fee  = total.checked_mul(self.project.carbon_pay_fee).ok_or(ContractError::ArithmeticOverflow)?
                         .checked_div(10_000).ok_or(ContractError::ArithmeticOverflow)?;
         let to_owner = total.checked_sub(fee).ok_or(ContractError::ArithmeticOverflow)?;
 
-        // 2) transfer SOL
-        anchor_lang::system_program::transfer(
-            CpiContext::new(
-                self.system_program.to_account_info(),
-                anchor_lang::system_program::Transfer {
-                    from: self.buyer.to_account_info(),
-                    to:   self.project_owner.to_account_info(),
-                },
-            ),
-            to_owner,
-        )?;
+        // guard against the project's terms moving after the buyer's UI quoted them
+        require!(total <= max_total_lamports, ContractError::SlippageExceeded);
+        require!(self.project.carbon_pay_fee <= expected_fee_bps, ContractError::FeeAboveExpected);
+
+        // 2) transfer SOL, splitting the owner proceeds among the project's beneficiaries
+        require!(
+            beneficiary_accounts.len() == self.project.beneficiaries.len(),
+            ContractError::BeneficiaryAccountMismatch
+        );
+        let parts = self.project.split_proceeds(to_owner)?;
+        for ((share, account), part) in self.project.beneficiaries.iter().zip(beneficiary_accounts).zip(parts) {
+            require!(
+                account.key() == share.recipient && account.is_writable,
+                ContractError::BeneficiaryAccountMismatch
+            );
+            if part == 0 {
+                continue;
+            }
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: self.buyer.to_account_info(),
+                        to:   account.clone(),
+                    },
+                ),
+                part,
+            )?;
+        }
+        // the referrer's cut comes out of the platform fee
+        let referral_fee = match self.referrer.as_ref() {
+            Some(referrer) => referrer.referral_fee(fee)?,
+            None => 0,
+        };
+        let platform_fee = fee.checked_sub(referral_fee).ok_or(ContractError::ArithmeticOverflow)?;
         anchor_lang::system_program::transfer(
             CpiContext::new(
                 self.system_program.to_account_info(),
@@ -126,47 +255,74 @@ impl<'info> PurchaseCarbonCredits<'info> {
                     to:   self.carbon_credits.to_account_info(),
                 },
             ),
-            fee,
+            platform_fee,
         )?;
+        self.carbon_credits.add_fees(platform_fee)?;
+        if let Some(referrer) = self.referrer.as_mut() {
+            if referral_fee > 0 {
+                anchor_lang::system_program::transfer(
+                    CpiContext::new(
+                        self.system_program.to_account_info(),
+                        anchor_lang::system_program::Transfer {
+                            from: self.buyer.to_account_info(),
+                            to:   referrer.to_account_info(),
+                        },
+                    ),
+                    referral_fee,
+                )?;
+            }
+            referrer.record_referral(total, amount, referral_fee)?;
+            emit!(ReferralCredited {
+                referrer: referrer.key(),
+                purchase: self.purchase.key(),
+                volume: total,
+                referral_fee,
+            });
+        }
 
         // 3) mint the purchase NFT
         token::mint_to(
-            CpiContext::new(
+            CpiContext::new_with_signer(
                 self.token_program.to_account_info(),
                 MintTo {
                     mint:      self.purchase_nft_mint.to_account_info(),
                     to:        self.buyer_nft_account.to_account_info(),
-                    authority: self.buyer.to_account_info(),
+                    authority: self.carbon_credits.to_account_info(),
                 },
+                &[&[b"carbon_credits", &[self.carbon_credits.bump]]],
             ),
             1,
         )?;
 
-        // 4) create NFT metadata
+        // 4) create NFT metadata, with the carbon_credits PDA as update authority
         create_metadata_accounts_v3(
-            CpiContext::new(
+            CpiContext::new_with_signer(
                 self.token_metadata_program.to_account_info(),
                 CreateMetadataAccountsV3 {
                     metadata:self.purchase_metadata.to_account_info(),
                     mint: self.purchase_nft_mint.to_account_info(),
-                    mint_authority:self.buyer.to_account_info(),
+                    mint_authority:self.carbon_credits.to_account_info(),
                     payer:self.buyer.to_account_info(),
-                    update_authority:self.buyer.to_account_info(),
+                    update_authority:self.carbon_credits.to_account_info(),
                     system_program:self.system_program.to_account_info(),
                     rent:self.rent.to_account_info(),
                 },
+                &[&[b"carbon_credits", &[self.carbon_credits.bump]]],
             ),
             DataV2 {
                 name: format!("Carbon Credits Purchase - {}", amount),
                 symbol:"CRBN".to_string(),
-                uri: format!("https://carbonpay.com/purchases/{}", self.purchase_nft_mint.key()),
+                uri: purchase_nft_uri(&self.carbon_credits.metadata_base_uri, &self.purchase_nft_mint.key(), false),
                 seller_fee_basis_points: 0,
                 creators: Some(vec![Creator{
                     address:  self.buyer.key(),
                     verified: true,
                     share:    100,
                 }]),
-                collection: None,
+                collection: Some(Collection {
+                    verified: false,
+                    key: self.project_collection_mint.key(),
+                }),
                 uses:       None,
             },
             true,
@@ -174,6 +330,23 @@ impl<'info> PurchaseCarbonCredits<'info> {
             None,
         )?;
 
+        // verify the purchase NFT into the project's collection
+        verify_sized_collection_item(
+            CpiContext::new_with_signer(
+                self.token_metadata_program.to_account_info(),
+                VerifySizedCollectionItem {
+                    payer:                     self.buyer.to_account_info(),
+                    metadata:                  self.purchase_metadata.to_account_info(),
+                    collection_authority:      self.carbon_credits.to_account_info(),
+                    collection_mint:           self.project_collection_mint.to_account_info(),
+                    collection_metadata:       self.project_collection_metadata.to_account_info(),
+                    collection_master_edition: self.project_collection_master_edition.to_account_info(),
+                },
+                &[&[b"carbon_credits", &[self.carbon_credits.bump]]],
+            ),
+            None,
+        )?;
+
         // 5) transfer the fungible tokens from vault to buyer
         token::transfer(
             CpiContext::new_with_signer(
@@ -197,10 +370,82 @@ impl<'info> PurchaseCarbonCredits<'info> {
             purchase_date:Clock::get()?.unix_timestamp,
             purchase_bump:bumps.purchase,
             nft_mint:self.purchase_nft_mint.key(),
+            sol_usd_price,
+            sol_usd_expo,
+            current_nft_mint:self.purchase_nft_mint.key(),
+            vintage:self.vintage.as_ref().map_or(Pubkey::default(), |v| v.key()),
+            offset_count:0,
+            version:ACCOUNT_VERSION,
+            pending_requests:0,
+            reserved:[0; Purchase::RESERVED_SPACE],
         });
-        self.project.remaining_amount = self.project.remaining_amount.checked_sub(amount).ok_or(ContractError::ArithmeticOverflow)?;
+        self.project.record_purchase(amount)?;
+        if let Some(vintage) = self.vintage.as_mut() {
+            vintage.record_purchase(amount)?;
+        }
+
+        // 7) daily figures
+        self.project_stats.init_if_new(self.project.key(), bumps.project_stats);
+        self.project_stats.record_sale(now, amount, total, fee)?;
+        self.platform_stats.init_if_new(self.carbon_credits.key(), bumps.platform_stats);
+        self.platform_stats.record_sale(now, amount, total, fee)?;
 
-    
         Ok(())
     }
+
+    /// Enforce the project's presale, if one is configured and has not ended yet
+    fn check_presale(
+        &mut self,
+        amount: u64,
+        allowlist_proof: &[[u8; 32]],
+        bumps: &PurchaseCarbonCreditsBumps,
+    ) -> Result<()> {
+        let Some(presale) = self.project.presale else {
+            return Ok(());
+        };
+        let now = Clock::get()?.unix_timestamp;
+        if now >= presale.end_time {
+            return Ok(());
+        }
+        require!(now >= presale.start_time, ContractError::PresaleNotStarted);
+        require!(
+            presale.is_allowed(&self.buyer.key(), allowlist_proof),
+            ContractError::NotOnAllowlist
+        );
+        require!(amount <= presale.max_per_tx, ContractError::PresaleTxCapExceeded);
+
+        let project = self.project.key();
+        let buyer = self.buyer.key();
+        let allocation = self
+            .buyer_allocation
+            .as_mut()
+            .ok_or(ContractError::BuyerAllocationRequired)?;
+        if allocation.buyer == Pubkey::default() {
+            allocation.buyer = buyer;
+            allocation.project = project;
+            allocation.bump = bumps.buyer_allocation.ok_or(ContractError::BuyerAllocationRequired)?;
+        }
+        allocation.purchased = allocation
+            .purchased
+            .checked_add(amount)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        require!(
+            allocation.purchased <= presale.max_per_wallet,
+            ContractError::PresaleWalletCapExceeded
+        );
+        Ok(())
+    }
+
+    /// Total price of `amount` tokens in lamports, plus the SOL/USD price and exponent
+    /// used for the conversion (zero for lamport-priced projects)
+    fn total_price_in_lamports(&self, amount: u64) -> Result<(u64, i64, i32)> {
+        let price_per_token = self.vintage.as_ref().map_or(self.project.price_per_token, |v| v.price_per_token);
+        let total = amount.checked_mul(price_per_token).ok_or(ContractError::ArithmeticOverflow)?;
+        quote_in_lamports(
+            &self.carbon_credits,
+            self.project.price_currency,
+            total,
+            self.price_feed.as_ref().map(|feed| feed.as_ref()),
+        )
+    }
 }
@@ -0,0 +1,64 @@
+use crate::errors::ContractError;
+use crate::nft_metadata::{purchase_nft_uri, updated_data};
+use crate::state::{CarbonCredits, Purchase};
+use anchor_lang::prelude::*;
+use anchor_spl::metadata::{
+    update_metadata_accounts_v2, Metadata, MetadataAccount, UpdateMetadataAccountsV2,
+};
+
+/// Platform authority re-points a purchase NFT (or its remaining-balance NFT)
+/// at the current metadata base URI
+#[derive(Accounts)]
+pub struct RefreshPurchaseMetadata<'info> {
+    #[account(
+        constraint = authority.key() == carbon_credits.authority @ ContractError::UnauthorizedAdmin
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Box<Account<'info, CarbonCredits>>,
+
+    #[account(
+        seeds = [b"purchase", purchase.buyer.as_ref(), purchase.project.as_ref(), purchase.nft_mint.as_ref()],
+        bump = purchase.purchase_bump,
+    )]
+    pub purchase: Box<Account<'info, Purchase>>,
+
+    #[account(
+        mut,
+        constraint = metadata.mint == purchase.current_nft_mint @ ContractError::InvalidNFTMint,
+    )]
+    pub metadata: Box<Account<'info, MetadataAccount>>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+impl<'info> RefreshPurchaseMetadata<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        let remaining = self.purchase.current_nft_mint != self.purchase.nft_mint;
+        let uri = purchase_nft_uri(
+            &self.carbon_credits.metadata_base_uri,
+            &self.purchase.current_nft_mint,
+            remaining,
+        );
+        let data = updated_data(&self.metadata, None, uri);
+
+        update_metadata_accounts_v2(
+            CpiContext::new_with_signer(
+                self.token_metadata_program.to_account_info(),
+                UpdateMetadataAccountsV2 {
+                    metadata: self.metadata.to_account_info(),
+                    update_authority: self.carbon_credits.to_account_info(),
+                },
+                &[&[b"carbon_credits", &[self.carbon_credits.bump]]],
+            ),
+            None,
+            Some(data),
+            None,
+            None,
+        )
+    }
+}
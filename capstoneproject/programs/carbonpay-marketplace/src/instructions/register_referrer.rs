@@ -0,0 +1,48 @@
+use crate::errors::ContractError;
+use crate::state::{CarbonCredits, Referrer};
+use anchor_lang::prelude::*;
+
+/// Platform authority registers a sales partner and its share of the platform fee
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct RegisterReferrer<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == carbon_credits.authority @ ContractError::UnauthorizedAdmin
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Account<'info, CarbonCredits>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Referrer::DISCRIMINATOR_SIZE + Referrer::INIT_SPACE,
+        seeds = [b"referrer", wallet.as_ref()],
+        bump
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RegisterReferrer<'info> {
+    pub fn handler(&mut self, wallet: Pubkey, share_bps: u16, bumps: &RegisterReferrerBumps) -> Result<()> {
+        require!(share_bps <= Referrer::MAX_SHARE_BPS, ContractError::InvalidReferralShare);
+        self.referrer.set_inner(Referrer {
+            wallet,
+            share_bps,
+            accrued_lamports: 0,
+            total_earned: 0,
+            referred_volume: 0,
+            referred_tokens: 0,
+            referred_purchases: 0,
+            bump: bumps.referrer,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use crate::errors::ContractError;
+use crate::state::{CarbonCredits, RegistryOracle};
+use anchor_lang::prelude::*;
+
+/// Platform authority registers a carbon registry's attestation signing key
+#[derive(Accounts)]
+#[instruction(key: Pubkey)]
+pub struct RegisterRegistryOracle<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == carbon_credits.authority @ ContractError::UnauthorizedAdmin
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Account<'info, CarbonCredits>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = RegistryOracle::DISCRIMINATOR_SIZE + RegistryOracle::INIT_SPACE,
+        seeds = [b"registry_oracle", key.as_ref()],
+        bump
+    )]
+    pub registry_oracle: Account<'info, RegistryOracle>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RegisterRegistryOracle<'info> {
+    pub fn handler(&mut self, key: Pubkey, name: String, bumps: &RegisterRegistryOracleBumps) -> Result<()> {
+        require!(
+            name.len() <= RegistryOracle::MAX_NAME_LEN,
+            ContractError::RegistryNameTooLong
+        );
+        self.registry_oracle.set_inner(RegistryOracle {
+            key,
+            name,
+            attestations: 0,
+            bump: bumps.registry_oracle,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use crate::errors::ContractError;
+use crate::state::{CarbonCredits, RegistryOracle};
+use anchor_lang::prelude::*;
+
+/// Platform authority revokes a registry oracle key; projects it already
+/// attested keep their attestation hash
+#[derive(Accounts)]
+pub struct RemoveRegistryOracle<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == carbon_credits.authority @ ContractError::UnauthorizedAdmin
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Account<'info, CarbonCredits>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"registry_oracle", registry_oracle.key.as_ref()],
+        bump = registry_oracle.bump,
+    )]
+    pub registry_oracle: Account<'info, RegistryOracle>,
+}
+
+impl<'info> RemoveRegistryOracle<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -1,17 +1,21 @@
-use crate::state::{CarbonCredits, OffsetRequest, Project, Purchase, RequestStatus};
+use crate::state::{CarbonCredits, OffsetRequest, Project, ProjectVintage, Purchase, RequestStatus, RetirementDetails, SalesStats};
 use crate::errors::ContractError;
+use crate::events::OffsetRequested;
+use crate::migration::{ACCOUNT_VERSION, RESERVED_SPACE};
+use crate::nft_metadata::purchase_nft_uri;
 use anchor_lang::prelude::*;
 use anchor_spl::{
+    associated_token::AssociatedToken,
     token::{self, Mint, Token, TokenAccount, MintTo, Burn},
     metadata::{
-        create_metadata_accounts_v3,
-        mpl_token_metadata::types::{Creator, DataV2},
-        CreateMetadataAccountsV3, Metadata,
+        create_metadata_accounts_v3, verify_sized_collection_item,
+        mpl_token_metadata::types::{Collection, Creator, DataV2},
+        CreateMetadataAccountsV3, Metadata, VerifySizedCollectionItem,
     },
 };
 
 #[derive(Accounts)]
-#[instruction(amount: u64, request_id: String)]
+#[instruction(amount: u64, request_id: String, retirement: RetirementDetails)]
 pub struct RequestOffset<'info> {
     /// who is asking for the offset
     #[account(mut)]
@@ -30,13 +34,21 @@ pub struct RequestOffset<'info> {
     /// the Project
     #[account(
         mut,
-        seeds = [b"project", project.owner.as_ref(), project.mint.as_ref()],
+        seeds = [b"project", project.seed_owner().as_ref(), project.mint.as_ref()],
         bump = project.project_bump,
     )]
     pub project: Box<Account<'info, Project>>,
 
-    /// the original NFT mint & token account - will be burned
-    #[account(mut, constraint = original_nft_mint.key() == purchase.nft_mint @ ContractError::InvalidNFTMint)]
+    /// the vintage the purchase was made from, required when `purchase.vintage` is set
+    #[account(
+        mut,
+        constraint = vintage.key() == purchase.vintage @ ContractError::InvalidVintage,
+        constraint = vintage.project == project.key() @ ContractError::InvalidVintage,
+    )]
+    pub vintage: Option<Box<Account<'info, ProjectVintage>>>,
+
+    /// the NFT currently holding the purchase balance & its token account - will be burned
+    #[account(mut, constraint = original_nft_mint.key() == purchase.current_nft_mint @ ContractError::InvalidNFTMint)]
     pub original_nft_mint: Box<Account<'info, Mint>>,
     #[account(
         mut,
@@ -46,25 +58,48 @@ pub struct RequestOffset<'info> {
     )]
     pub original_nft_account: Box<Account<'info, TokenAccount>>,
 
-    /// NEW NFT mint & ATA must be created client-side beforehand (for partial offsets)
-    #[account(mut)]
-    pub new_nft_mint: Box<Account<'info, Mint>>,
+    /// NFT for the remaining balance, required for partial offsets only. A PDA
+    /// numbered by the purchase's offset count, created here with the requester's ATA.
     #[account(
-        mut,
-        token::mint = new_nft_mint,
-        token::authority = offset_requester,
+        init,
+        payer = offset_requester,
+        seeds = [b"offset_nft", purchase.key().as_ref(), &purchase.offset_count.to_le_bytes()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = carbon_credits,
+        mint::freeze_authority = carbon_credits,
     )]
-    pub new_nft_account: Box<Account<'info, TokenAccount>>,
+    pub new_nft_mint: Option<Box<Account<'info, Mint>>>,
+    #[account(
+        init,
+        payer = offset_requester,
+        associated_token::mint = new_nft_mint,
+        associated_token::authority = offset_requester,
+    )]
+    pub new_nft_account: Option<Box<Account<'info, TokenAccount>>>,
 
     /// Metadata account for the new NFT
     /// CHECK: will be initialized by CPI
     #[account(mut)]
-    pub new_nft_metadata: UncheckedAccount<'info>,
+    pub new_nft_metadata: Option<UncheckedAccount<'info>>,
 
-    /// The project's fungible token mint
+    /// project NFT mint, the collection the remaining-balance NFT is verified into
+    #[account(
+        constraint = project_collection_mint.key() == project.mint @ ContractError::InvalidCollection
+    )]
+    pub project_collection_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: project NFT metadata, checked by the token metadata program
+    #[account(mut)]
+    pub project_collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: project NFT master edition, checked by the token metadata program
+    pub project_collection_master_edition: UncheckedAccount<'info>,
+
+    /// The fungible token mint of the project or of the purchased vintage
     #[account(
         mut,
-        constraint = token_mint.key() == project.token_mint @ ContractError::InvalidProjectMint,
+        constraint = token_mint.key() == vintage.as_ref().map_or(project.token_mint, |v| v.token_mint) @ ContractError::InvalidProjectMint,
     )]
     pub token_mint: Box<Account<'info, Mint>>,
 
@@ -95,8 +130,29 @@ pub struct RequestOffset<'info> {
     )]
     pub offset_request: Box<Account<'info, OffsetRequest>>,
 
+    /// daily figures of the project, created on first use
+    #[account(
+        init_if_needed,
+        payer = offset_requester,
+        space = SalesStats::DISCRIMINATOR_SIZE + SalesStats::INIT_SPACE,
+        seeds = [b"project_stats", project.key().as_ref()],
+        bump
+    )]
+    pub project_stats: Box<Account<'info, SalesStats>>,
+
+    /// daily figures of the platform, created on first use
+    #[account(
+        init_if_needed,
+        payer = offset_requester,
+        space = SalesStats::DISCRIMINATOR_SIZE + SalesStats::INIT_SPACE,
+        seeds = [b"platform_stats"],
+        bump
+    )]
+    pub platform_stats: Box<Account<'info, SalesStats>>,
+
     pub token_program: Program<'info, Token>,
     pub token_metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -106,10 +162,17 @@ impl<'info> RequestOffset<'info> {
         &mut self,
         amount: u64,
         request_id: String,
+        retirement: RetirementDetails,
         bumps: &RequestOffsetBumps,
     ) -> Result<()> {
         // 1) validate
         require!(amount > 0, ContractError::InvalidAmount);
+        OffsetRequest::validate_request_id(&request_id)?;
+        retirement.validate()?;
+        require!(
+            self.vintage.as_ref().map_or(Pubkey::default(), |v| v.key()) == self.purchase.vintage,
+            ContractError::InvalidVintage
+        );
         require!(
             amount <= self.purchase.remaining_amount,
             ContractError::InsufficientRemainingTokens
@@ -153,16 +216,27 @@ impl<'info> RequestOffset<'info> {
         )?;
 
         // 5) if partial, mint new NFT representing remaining balance
+        //    (full offsets need no new NFT)
+        let mut new_nft_mint_key = None;
         if remaining > 0 {
+            let (Some(new_nft_mint), Some(new_nft_account), Some(new_nft_metadata)) = (
+                self.new_nft_mint.as_ref(),
+                self.new_nft_account.as_ref(),
+                self.new_nft_metadata.as_ref(),
+            ) else {
+                return err!(ContractError::MissingRemainderNft);
+            };
+
             // mint exactly 1 new token
             token::mint_to(
-                CpiContext::new(
+                CpiContext::new_with_signer(
                     self.token_program.to_account_info(),
                     MintTo {
-                        mint: self.new_nft_mint.to_account_info(),
-                        to: self.new_nft_account.to_account_info(),
-                        authority: self.offset_requester.to_account_info(),
+                        mint: new_nft_mint.to_account_info(),
+                        to: new_nft_account.to_account_info(),
+                        authority: self.carbon_credits.to_account_info(),
                     },
+                    &[&[b"carbon_credits", &[self.carbon_credits.bump]]],
                 ),
                 1,
             )?;
@@ -171,68 +245,139 @@ impl<'info> RequestOffset<'info> {
             let data = DataV2 {
                 name: format!("Carbon Credits - Remaining: {}", remaining),
                 symbol: "CRBN".to_string(),
-                uri: format!("https://carbonpay.com/purchases/{}/remaining", self.new_nft_mint.key()),
+                uri: purchase_nft_uri(&self.carbon_credits.metadata_base_uri, &new_nft_mint.key(), true),
                 seller_fee_basis_points: 0,
-                creators: Some(vec![Creator {
-                    address: self.offset_requester.key(),
-                    verified: true,
-                    share: 100,
-                }]),
-                collection: None,
+                creators: Some(remaining_nft_creators(
+                    self.offset_requester.key(),
+                    retirement.beneficiary,
+                )),
+                collection: Some(Collection {
+                    verified: false,
+                    key: self.project_collection_mint.key(),
+                }),
                 uses: None,
             };
 
-            // CPI to create metadata
+            // CPI to create metadata, with the carbon_credits PDA as update authority
             create_metadata_accounts_v3(
-                CpiContext::new(
+                CpiContext::new_with_signer(
                     self.token_metadata_program.to_account_info(),
                     CreateMetadataAccountsV3 {
-                        metadata: self.new_nft_metadata.to_account_info(),
-                        mint: self.new_nft_mint.to_account_info(),
-                        mint_authority: self.offset_requester.to_account_info(),
+                        metadata: new_nft_metadata.to_account_info(),
+                        mint: new_nft_mint.to_account_info(),
+                        mint_authority: self.carbon_credits.to_account_info(),
                         payer: self.offset_requester.to_account_info(),
-                        update_authority: self.offset_requester.to_account_info(),
+                        update_authority: self.carbon_credits.to_account_info(),
                         system_program: self.system_program.to_account_info(),
                         rent: self.rent.to_account_info(),
                     },
+                    &[&[b"carbon_credits", &[self.carbon_credits.bump]]],
                 ),
                 data,
                 true,
                 true,
                 None,
             )?;
+
+            // verify it into the project's collection
+            verify_sized_collection_item(
+                CpiContext::new_with_signer(
+                    self.token_metadata_program.to_account_info(),
+                    VerifySizedCollectionItem {
+                        payer: self.offset_requester.to_account_info(),
+                        metadata: new_nft_metadata.to_account_info(),
+                        collection_authority: self.carbon_credits.to_account_info(),
+                        collection_mint: self.project_collection_mint.to_account_info(),
+                        collection_metadata: self.project_collection_metadata.to_account_info(),
+                        collection_master_edition: self.project_collection_master_edition.to_account_info(),
+                    },
+                    &[&[b"carbon_credits", &[self.carbon_credits.bump]]],
+                ),
+                None,
+            )?;
+
+            new_nft_mint_key = Some(new_nft_mint.key());
         }
 
         // 6) update on-chain state
-        self.purchase.remaining_amount = remaining;
-        self.carbon_credits.offset_credits = self
-            .carbon_credits
-            .offset_credits
-            .checked_add(amount)
-            .ok_or(ContractError::ArithmeticOverflow)?;
-        
-        // Update project's offset_amount
-        self.project.offset_amount = self
-            .project
-            .offset_amount
-            .checked_add(amount)
-            .ok_or(ContractError::ArithmeticOverflow)?;
+        self.purchase.record_offset(remaining)?;
+        if let Some(new_nft_mint_key) = new_nft_mint_key {
+            self.purchase.current_nft_mint = new_nft_mint_key;
+        }
+        self.carbon_credits.record_offset(amount)?;
 
-        // 7) record the Request
+        // Update the offset amount of the project and of the vintage, if any
+        self.project.record_offset(amount)?;
+        if let Some(vintage) = self.vintage.as_mut() {
+            vintage.record_offset(amount)?;
+        }
+
+        // 7) record the Request, with a review deadline if the platform sets one
+        let request_date = Clock::get()?.unix_timestamp;
+        let review_deadline = match self.carbon_credits.offset_review_period {
+            0 => 0,
+            period => request_date
+                .checked_add(period)
+                .ok_or(ContractError::ArithmeticOverflow)?,
+        };
         self.offset_request.set_inner(OffsetRequest {
             offset_requester: self.offset_requester.key(),
+            retirement: retirement.clone(),
             purchase: self.purchase.key(),
             project: self.project.key(),
             amount,
-            request_id,
+            request_id: request_id.clone(),
             status: RequestStatus::Pending,
-            request_date: Clock::get()?.unix_timestamp,
+            request_date,
             processed_date: 0,
             request_bump: bumps.offset_request,
-            processor: None,
+            processors: Vec::new(),
+            vintage: self.purchase.vintage,
+            review_deadline,
+            expiry_action: None,
+            version: ACCOUNT_VERSION,
+            reserved: [0; RESERVED_SPACE],
+        });
+        self.project_stats.init_if_new(self.project.key(), bumps.project_stats);
+        self.project_stats.record_offset(request_date, amount)?;
+        self.platform_stats.init_if_new(self.carbon_credits.key(), bumps.platform_stats);
+        self.platform_stats.record_offset(request_date, amount)?;
+
+        emit!(OffsetRequested {
+            offset_request: self.offset_request.key(),
+            offset_requester: self.offset_requester.key(),
+            beneficiary: retirement.beneficiary,
+            beneficiary_name: retirement.beneficiary_name,
+            reason: retirement.reason,
+            reporting_period_start: retirement.reporting_period_start,
+            reporting_period_end: retirement.reporting_period_end,
+            purchase: self.purchase.key(),
+            project: self.project.key(),
+            vintage: self.purchase.vintage,
+            amount,
+            request_id,
+            request_date,
         });
 
         msg!("Offset request for {} tokens, {} remaining", amount, remaining);
         Ok(())
     }
 }
+
+/// Creators of a remaining-balance NFT: the requester, plus the beneficiary of the
+/// offset (unverified, no share) when retiring on behalf of someone else
+fn remaining_nft_creators(requester: Pubkey, beneficiary: Pubkey) -> Vec<Creator> {
+    let mut creators = vec![Creator {
+        address: requester,
+        verified: true,
+        share: 100,
+    }];
+    if beneficiary != requester {
+        creators.push(Creator {
+            address: beneficiary,
+            verified: false,
+            share: 0,
+        });
+    }
+    creators
+}
@@ -0,0 +1,193 @@
+use crate::errors::ContractError;
+use crate::events::FundRetired;
+use crate::state::{CarbonCredits, FundEpoch, OffsetRequest, Project, RetirementDetails, RetirementFund, SalesStats};
+use crate::vault_retirement::{spendable_lamports, Payer, VaultRetirement};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+/// Buys `amount` credits from one of the fund's projects with the fund's balance
+/// and retires them straight from the vault, for all depositors pro rata. The
+/// manager may call it at any time, anyone else once the period has elapsed.
+/// The retirement is recorded for review like a checkout retirement, as an offset
+/// request of the manager for the fund, with id `fund-<fund_id>-<retirement>`; its
+/// rent is spent from the fund like the credits, and reclaimed by the manager
+/// when closing it. The first retirement of an epoch also spends the rent of the
+/// epoch's record.
+/// The project's beneficiaries must be passed as writable remaining accounts,
+/// in the same order as `project.beneficiaries`.
+#[derive(Accounts)]
+pub struct RetireFromFund<'info> {
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"retirement_fund", retirement_fund.manager.as_ref(), &retirement_fund.fund_id.to_le_bytes()],
+        bump = retirement_fund.bump,
+        constraint = retirement_fund.projects.contains(&project.key()) @ ContractError::ProjectNotInFund,
+    )]
+    pub retirement_fund: Box<Account<'info, RetirementFund>>,
+
+    #[account(
+        mut,
+        seeds = [b"project", project.seed_owner().as_ref(), project.mint.as_ref()],
+        bump = project.project_bump,
+        constraint = project.is_active() @ ContractError::ProjectInactive,
+    )]
+    pub project: Box<Account<'info, Project>>,
+
+    /// The project's fungible token mint
+    #[account(
+        mut,
+        constraint = token_mint.key() == project.token_mint @ ContractError::InvalidProjectMint,
+    )]
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    /// The project's vault, the retired credits are burned from it
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = carbon_credits,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Box<Account<'info, CarbonCredits>>,
+
+    /// SOL/USD price account, required when the project is priced in USD cents
+    /// CHECK: must be the feed configured on carbon_credits; decoded in the handler
+    #[account(
+        constraint = price_feed.key() == carbon_credits.price_feed @ ContractError::InvalidPriceFeed
+    )]
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// The retirement record, its rent is refunded to the caller by the fund
+    #[account(
+        init,
+        payer = caller,
+        space = OffsetRequest::DISCRIMINATOR_SIZE + OffsetRequest::INIT_SPACE,
+        seeds = [
+            b"offset_request",
+            retirement_fund.manager.as_ref(),
+            Pubkey::default().as_ref(),
+            retirement_fund.next_request_id().as_bytes(),
+        ],
+        bump
+    )]
+    pub offset_request: Box<Account<'info, OffsetRequest>>,
+
+    /// record of the fund's current epoch, created by its first retirement and
+    /// refunded to the caller by the fund
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = FundEpoch::DISCRIMINATOR_SIZE + FundEpoch::INIT_SPACE,
+        seeds = [b"fund_epoch", retirement_fund.key().as_ref(), &retirement_fund.epoch.to_le_bytes()],
+        bump
+    )]
+    pub fund_epoch: Box<Account<'info, FundEpoch>>,
+
+    /// daily figures of the project, created on first use
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = SalesStats::DISCRIMINATOR_SIZE + SalesStats::INIT_SPACE,
+        seeds = [b"project_stats", project.key().as_ref()],
+        bump
+    )]
+    pub project_stats: Box<Account<'info, SalesStats>>,
+
+    /// daily figures of the platform, created on first use
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = SalesStats::DISCRIMINATOR_SIZE + SalesStats::INIT_SPACE,
+        seeds = [b"platform_stats"],
+        bump
+    )]
+    pub platform_stats: Box<Account<'info, SalesStats>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RetireFromFund<'info> {
+    pub fn handler(
+        &mut self,
+        amount: u64,
+        beneficiary_accounts: &[AccountInfo<'info>],
+        bumps: &RetireFromFundBumps,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        if self.caller.key() != self.retirement_fund.manager {
+            require!(now >= self.retirement_fund.next_retirement, ContractError::FundRetirementNotDue);
+        }
+
+        let fund_info = self.retirement_fund.to_account_info();
+        let mut retirement = VaultRetirement {
+            project: &mut self.project,
+            token_mint: &self.token_mint,
+            vault: &self.vault,
+            carbon_credits: &mut self.carbon_credits,
+            price_feed: self.price_feed.as_ref().map(|feed| feed.as_ref()),
+            project_stats: &mut self.project_stats,
+            project_stats_bump: bumps.project_stats,
+            platform_stats: &mut self.platform_stats,
+            platform_stats_bump: bumps.platform_stats,
+            token_program: &self.token_program,
+        };
+
+        // 1) price the credits
+        let (total, fee) = retirement.quote(amount, now)?;
+        let max_total = amount
+            .checked_mul(self.retirement_fund.max_price_per_token)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        require!(total <= max_total, ContractError::FundPriceTooHigh);
+
+        // 2) the fund must stay rent exempt after paying for the credits and the records
+        let mut rent = self.offset_request.get_lamports();
+        if self.fund_epoch.fund == Pubkey::default() {
+            self.fund_epoch.fund = self.retirement_fund.key();
+            self.fund_epoch.epoch = self.retirement_fund.epoch;
+            self.fund_epoch.bump = bumps.fund_epoch;
+            rent = rent
+                .checked_add(self.fund_epoch.get_lamports())
+                .ok_or(ContractError::ArithmeticOverflow)?;
+        }
+        let spent = total.checked_add(rent).ok_or(ContractError::ArithmeticOverflow)?;
+        require!(spent <= spendable_lamports(&fund_info)?, ContractError::InsufficientFundBalance);
+
+        // 3) pay, retire the credits straight from the vault and record them for review
+        retirement.settle(Payer::Balance(&fund_info), amount, total, fee, beneficiary_accounts, now)?;
+        retirement.record_request(
+            &mut self.offset_request,
+            self.retirement_fund.manager,
+            RetirementDetails::for_beneficiary(self.retirement_fund.key(), now),
+            self.retirement_fund.next_request_id(),
+            bumps.offset_request,
+            amount,
+            now,
+        )?;
+        Payer::Balance(&fund_info).pay(&self.caller.to_account_info(), rent)?;
+        self.retirement_fund.record_retirement(&mut self.fund_epoch, amount, spent)?;
+        self.retirement_fund.next_retirement = now
+            .checked_add(self.retirement_fund.period_seconds)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+
+        emit!(FundRetired {
+            retirement_fund: self.retirement_fund.key(),
+            project: self.project.key(),
+            offset_request: self.offset_request.key(),
+            amount,
+            total_lamports: total,
+            caller: self.caller.key(),
+            retirement: self.retirement_fund.retirements,
+            timestamp: now,
+        });
+        Ok(())
+    }
+}
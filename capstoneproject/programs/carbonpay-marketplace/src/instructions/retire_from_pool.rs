@@ -0,0 +1,169 @@
+use crate::errors::ContractError;
+use crate::events::PoolRetirement;
+use crate::state::{CarbonCredits, OffsetPool, OffsetRequest, Project, RetirementDetails, SalesStats};
+use crate::vault_retirement::{spendable_lamports, Payer, VaultRetirement};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+/// Stable CPI entry point: the pool's authority buys `amount` credits of a project
+/// with the pool's balance and retires them straight from the vault, on its own
+/// behalf. Meant to be called by other programs (`carbonpay_marketplace::cpi::retire_from_pool`
+/// with the `cpi` feature) signing with the PDA that owns the pool; `payer` only
+/// covers the rent of the stats accounts on first use. The retirement is recorded
+/// for review like a checkout retirement, as an offset request of the authority
+/// with id `pool-<retirement>`; the pool covers its rent, which the authority gets
+/// back when closing it. Not available during a presale. The project's beneficiaries must be passed as writable remaining
+/// accounts, in the same order as `project.beneficiaries`.
+#[derive(Accounts)]
+pub struct RetireFromPool<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"offset_pool", authority.key().as_ref()],
+        bump = offset_pool.bump,
+    )]
+    pub offset_pool: Box<Account<'info, OffsetPool>>,
+
+    #[account(
+        mut,
+        seeds = [b"project", project.seed_owner().as_ref(), project.mint.as_ref()],
+        bump = project.project_bump,
+        constraint = project.is_active() @ ContractError::ProjectInactive,
+    )]
+    pub project: Box<Account<'info, Project>>,
+
+    /// The project's fungible token mint
+    #[account(
+        mut,
+        constraint = token_mint.key() == project.token_mint @ ContractError::InvalidProjectMint,
+    )]
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    /// The project's vault, the retired credits are burned from it
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = carbon_credits,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Box<Account<'info, CarbonCredits>>,
+
+    /// SOL/USD price account, required when the project is priced in USD cents
+    /// CHECK: must be the feed configured on carbon_credits; decoded in the handler
+    #[account(
+        constraint = price_feed.key() == carbon_credits.price_feed @ ContractError::InvalidPriceFeed
+    )]
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// The retirement record, its rent is refunded to `payer` by the pool
+    #[account(
+        init,
+        payer = payer,
+        space = OffsetRequest::DISCRIMINATOR_SIZE + OffsetRequest::INIT_SPACE,
+        seeds = [
+            b"offset_request",
+            authority.key().as_ref(),
+            Pubkey::default().as_ref(),
+            offset_pool.next_request_id().as_bytes(),
+        ],
+        bump
+    )]
+    pub offset_request: Box<Account<'info, OffsetRequest>>,
+
+    /// daily figures of the project, created on first use
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = SalesStats::DISCRIMINATOR_SIZE + SalesStats::INIT_SPACE,
+        seeds = [b"project_stats", project.key().as_ref()],
+        bump
+    )]
+    pub project_stats: Box<Account<'info, SalesStats>>,
+
+    /// daily figures of the platform, created on first use
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = SalesStats::DISCRIMINATOR_SIZE + SalesStats::INIT_SPACE,
+        seeds = [b"platform_stats"],
+        bump
+    )]
+    pub platform_stats: Box<Account<'info, SalesStats>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RetireFromPool<'info> {
+    pub fn handler(
+        &mut self,
+        amount: u64,
+        max_total_lamports: u64,
+        beneficiary_accounts: &[AccountInfo<'info>],
+        bumps: &RetireFromPoolBumps,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let pool_info = self.offset_pool.to_account_info();
+        let mut retirement = VaultRetirement {
+            project: &mut self.project,
+            token_mint: &self.token_mint,
+            vault: &self.vault,
+            carbon_credits: &mut self.carbon_credits,
+            price_feed: self.price_feed.as_ref().map(|feed| feed.as_ref()),
+            project_stats: &mut self.project_stats,
+            project_stats_bump: bumps.project_stats,
+            platform_stats: &mut self.platform_stats,
+            platform_stats_bump: bumps.platform_stats,
+            token_program: &self.token_program,
+        };
+
+        // 1) price the credits
+        let (total, fee) = retirement.quote(amount, now)?;
+        require!(total <= max_total_lamports, ContractError::SlippageExceeded);
+
+        // 2) the pool must stay rent exempt after paying
+        let record_rent = self.offset_request.get_lamports();
+        require!(
+            total.checked_add(record_rent).ok_or(ContractError::ArithmeticOverflow)?
+                <= spendable_lamports(&pool_info)?,
+            ContractError::InsufficientPoolFunds
+        );
+
+        // 3) pay, retire the credits straight from the vault and record them for review
+        retirement.settle(Payer::Balance(&pool_info), amount, total, fee, beneficiary_accounts, now)?;
+        let authority = self.authority.key();
+        retirement.record_request(
+            &mut self.offset_request,
+            authority,
+            RetirementDetails::for_beneficiary(authority, now),
+            self.offset_pool.next_request_id(),
+            bumps.offset_request,
+            amount,
+            now,
+        )?;
+        Payer::Balance(&pool_info).pay(&self.payer.to_account_info(), record_rent)?;
+        self.offset_pool.record_retirement(amount, total)?;
+
+        emit!(PoolRetirement {
+            offset_pool: self.offset_pool.key(),
+            authority: self.authority.key(),
+            project: self.project.key(),
+            offset_request: self.offset_request.key(),
+            amount,
+            total_lamports: total,
+            retirement: self.offset_pool.retirements,
+            timestamp: now,
+        });
+        Ok(())
+    }
+}
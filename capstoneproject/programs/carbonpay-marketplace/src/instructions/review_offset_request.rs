@@ -0,0 +1,161 @@
+use crate::errors::ContractError;
+use crate::events::{OffsetRequestProcessed, VerifierVoted};
+use crate::offset_refund::OffsetRefund;
+use crate::state::{CarbonCredits, OffsetRequest, Project, ProjectVintage, RequestStatus, VerifierRegistry, VoteRecord};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+/// A registered verifier votes on a pending offset request.
+/// The request is approved or rejected once the registry threshold is reached.
+/// The deciding vote passes the request's `purchase`, whose pending count drops.
+/// A rejection reverses the offset the same way as an AutoRefund expiry, so it
+/// also passes `token_mint` and `requester_token_account`, plus the `vintage`,
+/// `balance_nft_mint` and `requester_nft_account` the credits came from.
+#[derive(Accounts)]
+pub struct ReviewOffsetRequest<'info> {
+    #[account(
+        mut,
+        constraint = verifier_registry.is_verifier(&verifier.key()) @ ContractError::UnauthorizedVerifier
+    )]
+    pub verifier: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = offset_request.status == RequestStatus::Pending @ ContractError::RequestAlreadyProcessed,
+    )]
+    pub offset_request: Box<Account<'info, OffsetRequest>>,
+
+    /// the project the request is for, its pending count drops once decided
+    #[account(
+        mut,
+        constraint = project.key() == offset_request.project @ ContractError::InvalidProject,
+    )]
+    pub project: Box<Account<'info, Project>>,
+
+    /// the vintage the credits came from, required for rejections when `offset_request.vintage` is set
+    #[account(
+        mut,
+        constraint = vintage.key() == offset_request.vintage @ ContractError::InvalidVintage,
+    )]
+    pub vintage: Option<Box<Account<'info, ProjectVintage>>>,
+
+    /// the purchase the credits came from, its pending count drops once decided
+    /// CHECK: must be the request's purchase; decoded in the handler unless the buyer closed it
+    #[account(
+        mut,
+        address = offset_request.purchase @ ContractError::InvalidPurchase,
+    )]
+    pub purchase: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Box<Account<'info, CarbonCredits>>,
+
+    /// The fungible token mint of the project or of the vintage, for rejections
+    #[account(mut)]
+    pub token_mint: Option<Box<Account<'info, Mint>>>,
+
+    /// Requester's token account the rejected credits are minted back to
+    #[account(
+        mut,
+        constraint = requester_token_account.owner == offset_request.offset_requester @ ContractError::Unauthorized,
+    )]
+    pub requester_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// The purchase's current balance NFT mint, for rejected requests that used it up
+    #[account(mut)]
+    pub balance_nft_mint: Option<Box<Account<'info, Mint>>>,
+
+    /// Requester's token account the balance NFT is minted back to
+    #[account(
+        mut,
+        constraint = requester_nft_account.owner == offset_request.offset_requester @ ContractError::Unauthorized,
+    )]
+    pub requester_nft_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(
+        seeds = [b"verifier_registry"],
+        bump = verifier_registry.bump,
+    )]
+    pub verifier_registry: Box<Account<'info, VerifierRegistry>>,
+
+    #[account(
+        init_if_needed,
+        payer = verifier,
+        space = VoteRecord::DISCRIMINATOR_SIZE + VoteRecord::INIT_SPACE,
+        seeds = [b"vote_record", offset_request.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Box<Account<'info, VoteRecord>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ReviewOffsetRequest<'info> {
+    pub fn handler(&mut self, approve: bool, bumps: &ReviewOffsetRequestBumps) -> Result<()> {
+        if self.vote_record.target == Pubkey::default() {
+            self.vote_record.target = self.offset_request.key();
+            self.vote_record.bump = bumps.vote_record;
+        }
+        let outcome = self
+            .vote_record
+            .cast(&self.verifier_registry, self.verifier.key(), approve)?;
+
+        emit!(VerifierVoted {
+            target: self.offset_request.key(),
+            verifier: self.verifier.key(),
+            approve,
+            approvals: self.vote_record.approvals.len() as u8,
+            rejections: self.vote_record.rejections.len() as u8,
+            threshold: self.verifier_registry.threshold,
+        });
+
+        if let Some(approved) = outcome {
+            let amount = self.offset_request.amount;
+            let mut refund = self.offset_refund();
+            refund.release_purchase()?;
+            if !approved {
+                refund.refund(amount)?;
+                self.carbon_credits.revert_offset(amount)?;
+            }
+            let offset_request = &mut self.offset_request;
+            offset_request.status = if approved {
+                RequestStatus::Approved
+            } else {
+                RequestStatus::Rejected
+            };
+            offset_request.processed_date = Clock::get()?.unix_timestamp;
+            offset_request.processors = self
+                .vote_record
+                .deciding_voters(&self.verifier_registry, approved);
+            self.project.record_offset_processed()?;
+
+            emit!(OffsetRequestProcessed {
+                offset_request: offset_request.key(),
+                approved,
+                processors: offset_request.processors.clone(),
+                processed_date: offset_request.processed_date,
+            });
+        }
+        Ok(())
+    }
+
+    fn offset_refund(&mut self) -> OffsetRefund<'_, 'info> {
+        OffsetRefund {
+            offset_request: &self.offset_request,
+            project: &mut self.project,
+            vintage: self.vintage.as_deref_mut(),
+            purchase: self.purchase.as_ref().map(|purchase| purchase.as_ref()),
+            carbon_credits: &self.carbon_credits,
+            token_mint: self.token_mint.as_deref(),
+            requester_token_account: self.requester_token_account.as_deref(),
+            balance_nft_mint: self.balance_nft_mint.as_deref(),
+            requester_nft_account: self.requester_nft_account.as_deref(),
+            token_program: &self.token_program,
+        }
+    }
+}
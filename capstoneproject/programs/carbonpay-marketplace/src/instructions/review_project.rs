@@ -0,0 +1,101 @@
+use crate::errors::ContractError;
+use crate::events::{ProjectStatusChanged, VerifierVoted};
+use crate::state::{Project, ProjectStatus, VerifierRegistry, VoteRecord};
+use anchor_lang::prelude::*;
+
+/// A registered verifier votes on a project that is waiting for approval.
+/// The project status changes once the registry threshold is reached.
+#[derive(Accounts)]
+pub struct ReviewProject<'info> {
+    #[account(
+        mut,
+        constraint = verifier_registry.is_verifier(&verifier.key()) @ ContractError::UnauthorizedVerifier
+    )]
+    pub verifier: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"project", project.seed_owner().as_ref(), project.mint.as_ref()],
+        bump = project.project_bump,
+        constraint = project.status == ProjectStatus::PendingReview @ ContractError::ProjectNotPendingReview,
+    )]
+    pub project: Account<'info, Project>,
+
+    #[account(
+        seeds = [b"verifier_registry"],
+        bump = verifier_registry.bump,
+    )]
+    pub verifier_registry: Account<'info, VerifierRegistry>,
+
+    #[account(
+        init_if_needed,
+        payer = verifier,
+        space = VoteRecord::DISCRIMINATOR_SIZE + VoteRecord::INIT_SPACE,
+        seeds = [b"vote_record", project.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ReviewProject<'info> {
+    /// Vote to approve the project. The verifier passes the hash of the documents
+    /// they reviewed, which must match what the owner submitted.
+    pub fn approve(&mut self, document_hash: [u8; 32], bumps: &ReviewProjectBumps) -> Result<()> {
+        require!(
+            self.project.document_hash == document_hash,
+            ContractError::DocumentHashMismatch
+        );
+
+        if self.vote(true, bumps)? == Some(true) {
+            self.project
+                .set_status(ProjectStatus::Active, self.verifier.key(), 0)?;
+            self.emit_status_change();
+        }
+        Ok(())
+    }
+
+    /// Vote to reject the project with a reason code. The reason code of the vote
+    /// that reaches the quorum is recorded on the project.
+    pub fn reject(&mut self, reason_code: u16, bumps: &ReviewProjectBumps) -> Result<()> {
+        require!(reason_code > 0, ContractError::InvalidReasonCode);
+
+        if self.vote(false, bumps)? == Some(false) {
+            self.project
+                .set_status(ProjectStatus::Rejected, self.verifier.key(), reason_code)?;
+            self.emit_status_change();
+        }
+        Ok(())
+    }
+
+    fn vote(&mut self, approve: bool, bumps: &ReviewProjectBumps) -> Result<Option<bool>> {
+        if self.vote_record.target == Pubkey::default() {
+            self.vote_record.target = self.project.key();
+            self.vote_record.bump = bumps.vote_record;
+        }
+        let outcome = self
+            .vote_record
+            .cast(&self.verifier_registry, self.verifier.key(), approve)?;
+
+        emit!(VerifierVoted {
+            target: self.project.key(),
+            verifier: self.verifier.key(),
+            approve,
+            approvals: self.vote_record.approvals.len() as u8,
+            rejections: self.vote_record.rejections.len() as u8,
+            threshold: self.verifier_registry.threshold,
+        });
+        Ok(outcome)
+    }
+
+    fn emit_status_change(&self) {
+        emit!(ProjectStatusChanged {
+            project: self.project.key(),
+            status: self.project.status,
+            reviewer: self.verifier.key(),
+            reason_code: self.project.status_reason,
+            timestamp: self.project.reviewed_at,
+        });
+    }
+}
@@ -0,0 +1,30 @@
+use crate::errors::ContractError;
+use crate::state::CarbonCredits;
+use anchor_lang::prelude::*;
+
+/// Platform authority changes the base URI used for purchase NFT metadata
+#[derive(Accounts)]
+pub struct SetMetadataBaseUri<'info> {
+    #[account(
+        constraint = authority.key() == carbon_credits.authority @ ContractError::UnauthorizedAdmin
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Account<'info, CarbonCredits>,
+}
+
+impl<'info> SetMetadataBaseUri<'info> {
+    pub fn handler(&mut self, metadata_base_uri: String) -> Result<()> {
+        require!(
+            metadata_base_uri.len() <= CarbonCredits::MAX_METADATA_BASE_URI_LEN,
+            ContractError::MetadataUriTooLong
+        );
+        self.carbon_credits.metadata_base_uri = metadata_base_uri;
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+use crate::errors::ContractError;
+use crate::state::{CarbonCredits, ExpiryAction};
+use anchor_lang::prelude::*;
+
+/// Platform authority sets how long verifiers have to decide an offset request and
+/// what happens to requests left undecided. Only applies to new requests.
+#[derive(Accounts)]
+pub struct SetOffsetReviewPolicy<'info> {
+    #[account(
+        constraint = authority.key() == carbon_credits.authority @ ContractError::UnauthorizedAdmin
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Account<'info, CarbonCredits>,
+}
+
+impl<'info> SetOffsetReviewPolicy<'info> {
+    pub fn handler(&mut self, offset_review_period: i64, expiry_action: ExpiryAction) -> Result<()> {
+        require!(offset_review_period >= 0, ContractError::InvalidReviewPeriod);
+        self.carbon_credits.offset_review_period = offset_review_period;
+        self.carbon_credits.expiry_action = expiry_action;
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use crate::errors::ContractError;
+use crate::state::{PresaleConfig, Project};
+use anchor_lang::prelude::*;
+
+/// Project owner configures (or with `None`, removes) the allowlisted presale
+#[derive(Accounts)]
+pub struct SetPresale<'info> {
+    #[account(
+        constraint = project_owner.key() == project.owner @ ContractError::InvalidProjectOwner
+    )]
+    pub project_owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"project", project.seed_owner().as_ref(), project.mint.as_ref()],
+        bump = project.project_bump,
+    )]
+    pub project: Box<Account<'info, Project>>,
+}
+
+impl<'info> SetPresale<'info> {
+    pub fn handler(&mut self, presale: Option<PresaleConfig>) -> Result<()> {
+        if let Some(config) = &presale {
+            require!(
+                config.start_time < config.end_time
+                    && config.max_per_tx > 0
+                    && config.max_per_wallet > 0,
+                ContractError::InvalidPresaleConfig
+            );
+        }
+        self.project.presale = presale;
+        Ok(())
+    }
+}
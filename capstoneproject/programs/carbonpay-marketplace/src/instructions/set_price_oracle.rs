@@ -0,0 +1,34 @@
+use crate::errors::ContractError;
+use crate::oracle::PythPrice;
+use crate::state::CarbonCredits;
+use anchor_lang::prelude::*;
+
+/// Platform authority configures the SOL/USD price account used for USD-priced projects
+#[derive(Accounts)]
+pub struct SetPriceOracle<'info> {
+    #[account(
+        constraint = authority.key() == carbon_credits.authority @ ContractError::UnauthorizedAdmin
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Account<'info, CarbonCredits>,
+
+    /// CHECK: decoded and validated as a Pyth price account in the handler
+    pub price_feed: UncheckedAccount<'info>,
+}
+
+impl<'info> SetPriceOracle<'info> {
+    pub fn handler(&mut self, max_price_age: i64, max_price_confidence_bps: u16) -> Result<()> {
+        PythPrice::load(&self.price_feed.try_borrow_data()?)?;
+
+        self.carbon_credits.price_feed = self.price_feed.key();
+        self.carbon_credits.max_price_age = max_price_age;
+        self.carbon_credits.max_price_confidence_bps = max_price_confidence_bps;
+        Ok(())
+    }
+}
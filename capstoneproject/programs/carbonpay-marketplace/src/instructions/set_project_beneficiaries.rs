@@ -0,0 +1,39 @@
+use crate::errors::ContractError;
+use crate::state::{CarbonCredits, Project, RevenueShare};
+use anchor_lang::prelude::*;
+
+/// Changes a project's revenue split. Requires both the project owner and the
+/// platform authority to sign, so neither side can redirect proceeds alone.
+#[derive(Accounts)]
+pub struct SetProjectBeneficiaries<'info> {
+    #[account(
+        constraint = project_owner.key() == project.owner @ ContractError::InvalidProjectOwner
+    )]
+    pub project_owner: Signer<'info>,
+
+    #[account(
+        constraint = authority.key() == carbon_credits.authority @ ContractError::UnauthorizedAdmin
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"project", project.seed_owner().as_ref(), project.mint.as_ref()],
+        bump = project.project_bump,
+    )]
+    pub project: Account<'info, Project>,
+
+    #[account(
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Account<'info, CarbonCredits>,
+}
+
+impl<'info> SetProjectBeneficiaries<'info> {
+    pub fn handler(&mut self, beneficiaries: Vec<RevenueShare>) -> Result<()> {
+        Project::validate_beneficiaries(&beneficiaries)?;
+        self.project.beneficiaries = beneficiaries;
+        Ok(())
+    }
+}
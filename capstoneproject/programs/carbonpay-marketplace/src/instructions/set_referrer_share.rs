@@ -0,0 +1,34 @@
+use crate::errors::ContractError;
+use crate::state::{CarbonCredits, Referrer};
+use anchor_lang::prelude::*;
+
+/// Platform authority changes a referrer's share of the platform fee
+/// (0 stops paying the referrer without losing its history)
+#[derive(Accounts)]
+pub struct SetReferrerShare<'info> {
+    #[account(
+        constraint = authority.key() == carbon_credits.authority @ ContractError::UnauthorizedAdmin
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Account<'info, CarbonCredits>,
+
+    #[account(
+        mut,
+        seeds = [b"referrer", referrer.wallet.as_ref()],
+        bump = referrer.bump,
+    )]
+    pub referrer: Account<'info, Referrer>,
+}
+
+impl<'info> SetReferrerShare<'info> {
+    pub fn handler(&mut self, share_bps: u16) -> Result<()> {
+        require!(share_bps <= Referrer::MAX_SHARE_BPS, ContractError::InvalidReferralShare);
+        self.referrer.share_bps = share_bps;
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use crate::errors::ContractError;
+use crate::state::{Project, ProjectVintage};
+use anchor_lang::prelude::*;
+
+/// Project owner sets (or with `None`, removes) the time after which credits can no
+/// longer be sold. Applies to `vintage` when given, otherwise to the project's own tranche.
+/// Credits that were already bought can still be offset.
+#[derive(Accounts)]
+pub struct SetSaleExpiry<'info> {
+    #[account(
+        constraint = project_owner.key() == project.owner @ ContractError::InvalidProjectOwner
+    )]
+    pub project_owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"project", project.seed_owner().as_ref(), project.mint.as_ref()],
+        bump = project.project_bump,
+    )]
+    pub project: Box<Account<'info, Project>>,
+
+    /// vintage tranche to set the expiry of, if any
+    #[account(
+        mut,
+        constraint = vintage.project == project.key() @ ContractError::InvalidVintage,
+    )]
+    pub vintage: Option<Box<Account<'info, ProjectVintage>>>,
+}
+
+impl<'info> SetSaleExpiry<'info> {
+    pub fn handler(&mut self, sale_expiry: Option<i64>) -> Result<()> {
+        match self.vintage.as_mut() {
+            Some(vintage) => vintage.sale_expiry = sale_expiry,
+            None => self.project.sale_expiry = sale_expiry,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use crate::errors::ContractError;
+use crate::events::ProjectStatusChanged;
+use crate::state::{CarbonCredits, Project, ProjectStatus};
+use anchor_lang::prelude::*;
+
+/// Platform authority takes a live project off sale
+#[derive(Accounts)]
+pub struct SuspendProject<'info> {
+    #[account(
+        constraint = authority.key() == carbon_credits.authority @ ContractError::UnauthorizedAdmin
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"project", project.seed_owner().as_ref(), project.mint.as_ref()],
+        bump = project.project_bump,
+        constraint = project.is_active() @ ContractError::ProjectInactive,
+    )]
+    pub project: Account<'info, Project>,
+
+    #[account(
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Account<'info, CarbonCredits>,
+}
+
+impl<'info> SuspendProject<'info> {
+    pub fn handler(&mut self, reason_code: u16) -> Result<()> {
+        require!(reason_code > 0, ContractError::InvalidReasonCode);
+
+        self.project
+            .set_status(ProjectStatus::Suspended, self.authority.key(), reason_code)?;
+
+        emit!(ProjectStatusChanged {
+            project: self.project.key(),
+            status: self.project.status,
+            reviewer: self.authority.key(),
+            reason_code,
+            timestamp: self.project.reviewed_at,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use crate::errors::ContractError;
+use crate::events::ProjectOwnershipTransferred;
+use crate::state::Project;
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+/// The holder of the project NFT claims the project: the NFT is its title deed.
+/// Sending the NFT and claiming can be done in one transaction for an atomic
+/// sale. The previous owner's revenue share moves to the new owner; the project
+/// address does not change.
+#[derive(Accounts)]
+pub struct TransferProjectOwnership<'info> {
+    pub new_owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"project", project.seed_owner().as_ref(), project.mint.as_ref()],
+        bump = project.project_bump,
+    )]
+    pub project: Box<Account<'info, Project>>,
+
+    /// the new owner's token account holding the project NFT
+    #[account(
+        constraint = project_nft_account.mint == project.mint @ ContractError::InvalidProjectMint,
+        constraint = project_nft_account.owner == new_owner.key() @ ContractError::NotProjectNftHolder,
+        constraint = project_nft_account.amount == 1 @ ContractError::NotProjectNftHolder,
+    )]
+    pub project_nft_account: Box<Account<'info, TokenAccount>>,
+}
+
+impl<'info> TransferProjectOwnership<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        let previous_owner = self.project.owner;
+        require!(
+            self.new_owner.key() != previous_owner,
+            ContractError::AlreadyProjectOwner
+        );
+
+        self.project.transfer_ownership(self.new_owner.key())?;
+
+        emit!(ProjectOwnershipTransferred {
+            project: self.project.key(),
+            previous_owner,
+            new_owner: self.new_owner.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use crate::errors::ContractError;
+use crate::nft_metadata::updated_data;
+use crate::state::{CarbonCredits, Project};
+use anchor_lang::prelude::*;
+use anchor_spl::metadata::{
+    update_metadata_accounts_v2, Metadata, MetadataAccount, UpdateMetadataAccountsV2,
+};
+
+/// Project owner renames the project NFT or points it at a new URI.
+/// The `carbon_credits` PDA is the update authority and signs the CPI.
+#[derive(Accounts)]
+pub struct UpdateProjectMetadata<'info> {
+    #[account(
+        constraint = project_owner.key() == project.owner @ ContractError::InvalidProjectOwner
+    )]
+    pub project_owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"project", project.seed_owner().as_ref(), project.mint.as_ref()],
+        bump = project.project_bump,
+    )]
+    pub project: Box<Account<'info, Project>>,
+
+    #[account(
+        seeds = [b"carbon_credits"],
+        bump = carbon_credits.bump,
+    )]
+    pub carbon_credits: Box<Account<'info, CarbonCredits>>,
+
+    #[account(
+        mut,
+        constraint = metadata.mint == project.mint @ ContractError::InvalidNFTMint,
+    )]
+    pub metadata: Box<Account<'info, MetadataAccount>>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+impl<'info> UpdateProjectMetadata<'info> {
+    pub fn handler(&mut self, name: String, uri: String) -> Result<()> {
+        let data = updated_data(&self.metadata, Some(name), uri);
+
+        update_metadata_accounts_v2(
+            CpiContext::new_with_signer(
+                self.token_metadata_program.to_account_info(),
+                UpdateMetadataAccountsV2 {
+                    metadata: self.metadata.to_account_info(),
+                    update_authority: self.carbon_credits.to_account_info(),
+                },
+                &[&[b"carbon_credits", &[self.carbon_credits.bump]]],
+            ),
+            None,
+            Some(data),
+            None,
+            None,
+        )
+    }
+}
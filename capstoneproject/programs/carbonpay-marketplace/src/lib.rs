@@ -1,10 +1,21 @@
+// the CPI clients generated for the `cpi` feature mirror every instruction's arguments
+#![allow(clippy::too_many_arguments)]
+
 use anchor_lang::prelude::*;
 
 mod instructions;
 mod state;
 mod errors;
+mod events;
+mod oracle;
+mod ed25519;
+mod nft_metadata;
+mod migration;
+mod offset_refund;
+mod vault_retirement;
 
 use instructions::*;
+use state::{ExpiryAction, PresaleConfig, PriceCurrency, RetirementDetails, RevenueShare, TokenizationAttestation};
 
 declare_id!("b6Yz3TrG29otpSnLzJTNCB1vxxcwJCTuPHdCfR9Njqs");
 
@@ -18,6 +29,13 @@ pub mod carbon_pay {
         ctx.accounts.initialize_carbon_credits_handler(&ctx.bumps)
     }
 
+    pub fn initialize_platform_collection(
+        ctx: Context<InitializePlatformCollection>,
+        uri: String,
+    ) -> Result<()> {
+        ctx.accounts.handler(uri)
+    }
+
     pub fn initialize_project(
         ctx: Context<InitializeProject>,
         amount: u64,
@@ -26,6 +44,10 @@ pub mod carbon_pay {
         uri: String,
         name: String,
         symbol: String,
+        document_hash: [u8; 32],
+        price_currency: PriceCurrency,
+        beneficiaries: Vec<RevenueShare>,
+        attestation: TokenizationAttestation,
     ) -> Result<()> {
         ctx.accounts.handler(
             amount,
@@ -34,22 +56,307 @@ pub mod carbon_pay {
             uri,
             name,
             symbol,
+            document_hash,
+            price_currency,
+            beneficiaries,
+            attestation,
             &ctx.bumps,
         )
     }
 
+    pub fn register_registry_oracle(
+        ctx: Context<RegisterRegistryOracle>,
+        key: Pubkey,
+        name: String,
+    ) -> Result<()> {
+        ctx.accounts.handler(key, name, &ctx.bumps)
+    }
+
+    pub fn remove_registry_oracle(ctx: Context<RemoveRegistryOracle>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn initialize_verifier_registry(ctx: Context<InitializeVerifierRegistry>) -> Result<()> {
+        ctx.accounts.handler(&ctx.bumps)
+    }
+
+    pub fn add_verifier(ctx: Context<ManageVerifiers>, verifier: Pubkey) -> Result<()> {
+        ctx.accounts.add_verifier(verifier)
+    }
+
+    pub fn remove_verifier(ctx: Context<ManageVerifiers>, verifier: Pubkey) -> Result<()> {
+        ctx.accounts.remove_verifier(verifier)
+    }
+
+    pub fn set_verifier_threshold(ctx: Context<ManageVerifiers>, threshold: u8) -> Result<()> {
+        ctx.accounts.set_threshold(threshold)
+    }
+
+    pub fn set_project_beneficiaries(
+        ctx: Context<SetProjectBeneficiaries>,
+        beneficiaries: Vec<RevenueShare>,
+    ) -> Result<()> {
+        ctx.accounts.handler(beneficiaries)
+    }
+
+    pub fn issue_credits(
+        ctx: Context<IssueCredits>,
+        amount: u64,
+        document_hash: [u8; 32],
+        attestation: TokenizationAttestation,
+    ) -> Result<()> {
+        ctx.accounts.handler(amount, document_hash, attestation, &ctx.bumps)
+    }
+
+    pub fn set_presale(ctx: Context<SetPresale>, presale: Option<PresaleConfig>) -> Result<()> {
+        ctx.accounts.handler(presale)
+    }
+
+    pub fn add_project_vintage(
+        ctx: Context<AddProjectVintage>,
+        vintage_year: u16,
+        amount: u64,
+        price_per_token: u64,
+        attestation: TokenizationAttestation,
+    ) -> Result<()> {
+        ctx.accounts.handler(vintage_year, amount, price_per_token, attestation, &ctx.bumps)
+    }
+
+    pub fn update_project_metadata(
+        ctx: Context<UpdateProjectMetadata>,
+        name: String,
+        uri: String,
+    ) -> Result<()> {
+        ctx.accounts.handler(name, uri)
+    }
+
+    pub fn set_metadata_base_uri(
+        ctx: Context<SetMetadataBaseUri>,
+        metadata_base_uri: String,
+    ) -> Result<()> {
+        ctx.accounts.handler(metadata_base_uri)
+    }
+
+    pub fn refresh_purchase_metadata(ctx: Context<RefreshPurchaseMetadata>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn set_price_oracle(
+        ctx: Context<SetPriceOracle>,
+        max_price_age: i64,
+        max_price_confidence_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.handler(max_price_age, max_price_confidence_bps)
+    }
+
+    pub fn register_referrer(
+        ctx: Context<RegisterReferrer>,
+        wallet: Pubkey,
+        share_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.handler(wallet, share_bps, &ctx.bumps)
+    }
+
+    pub fn set_referrer_share(ctx: Context<SetReferrerShare>, share_bps: u16) -> Result<()> {
+        ctx.accounts.handler(share_bps)
+    }
+
+    pub fn claim_referral_fees(ctx: Context<ClaimReferralFees>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn approve_project(ctx: Context<ReviewProject>, document_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.approve(document_hash, &ctx.bumps)
+    }
+
+    pub fn reject_project(ctx: Context<ReviewProject>, reason_code: u16) -> Result<()> {
+        ctx.accounts.reject(reason_code, &ctx.bumps)
+    }
+
+    pub fn suspend_project(ctx: Context<SuspendProject>, reason_code: u16) -> Result<()> {
+        ctx.accounts.handler(reason_code)
+    }
+
     pub fn request_offset(
         ctx: Context<RequestOffset>,
         amount: u64,
         request_id: String,
+        retirement: RetirementDetails,
+    ) -> Result<()> {
+        ctx.accounts.handler(amount, request_id, retirement, &ctx.bumps)
+    }
+
+    pub fn review_offset_request(ctx: Context<ReviewOffsetRequest>, approve: bool) -> Result<()> {
+        ctx.accounts.handler(approve, &ctx.bumps)
+    }
+
+    pub fn purchase_carbon_credits<'info>(
+        ctx: Context<'_, '_, '_, 'info, PurchaseCarbonCredits<'info>>,
+        amount: u64,
+        max_total_lamports: u64,
+        expected_fee_bps: u64,
+        allowlist_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        ctx.accounts.purchase_carbon_credits(
+            amount,
+            max_total_lamports,
+            expected_fee_bps,
+            allowlist_proof,
+            ctx.remaining_accounts,
+            &ctx.bumps,
+        )
+    }
+
+    pub fn purchase_and_retire<'info>(
+        ctx: Context<'_, '_, '_, 'info, PurchaseAndRetire<'info>>,
+        amount: u64,
+        max_total_lamports: u64,
+        expected_fee_bps: u64,
+        request_id: String,
+        retirement: RetirementDetails,
+    ) -> Result<()> {
+        ctx.accounts.handler(
+            amount,
+            max_total_lamports,
+            expected_fee_bps,
+            request_id,
+            retirement,
+            ctx.remaining_accounts,
+            &ctx.bumps,
+        )
+    }
+
+    pub fn create_subscription(
+        ctx: Context<CreateSubscription>,
+        subscription_id: u64,
+        projects: Vec<Pubkey>,
+        tokens_per_period: u64,
+        period_seconds: i64,
+        max_price_per_token: u64,
+        crank_tip: u64,
+        deposit: u64,
+    ) -> Result<()> {
+        ctx.accounts.handler(
+            subscription_id,
+            projects,
+            tokens_per_period,
+            period_seconds,
+            max_price_per_token,
+            crank_tip,
+            deposit,
+            &ctx.bumps,
+        )
+    }
+
+    pub fn fund_subscription(ctx: Context<FundSubscription>, amount: u64) -> Result<()> {
+        ctx.accounts.handler(amount)
+    }
+
+    pub fn cancel_subscription(ctx: Context<CancelSubscription>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn create_offset_pool(ctx: Context<CreateOffsetPool>, deposit: u64) -> Result<()> {
+        ctx.accounts.handler(deposit, &ctx.bumps)
+    }
+
+    pub fn fund_offset_pool(ctx: Context<FundOffsetPool>, amount: u64) -> Result<()> {
+        ctx.accounts.handler(amount)
+    }
+
+    pub fn close_offset_pool(ctx: Context<CloseOffsetPool>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn retire_from_pool<'info>(
+        ctx: Context<'_, '_, '_, 'info, RetireFromPool<'info>>,
+        amount: u64,
+        max_total_lamports: u64,
+    ) -> Result<()> {
+        ctx.accounts.handler(amount, max_total_lamports, ctx.remaining_accounts, &ctx.bumps)
+    }
+
+    pub fn create_retirement_fund(
+        ctx: Context<CreateRetirementFund>,
+        fund_id: u64,
+        projects: Vec<Pubkey>,
+        max_price_per_token: u64,
+        period_seconds: i64,
     ) -> Result<()> {
-        ctx.accounts.handler(amount, request_id, &ctx.bumps)
+        ctx.accounts
+            .handler(fund_id, projects, max_price_per_token, period_seconds, &ctx.bumps)
+    }
+
+    pub fn deposit_to_fund(ctx: Context<DepositToFund>, amount: u64) -> Result<()> {
+        ctx.accounts.handler(amount, &ctx.bumps)
     }
 
-    pub fn purchase_carbon_credits(
-        ctx: Context<PurchaseCarbonCredits>,
+    pub fn retire_from_fund<'info>(
+        ctx: Context<'_, '_, '_, 'info, RetireFromFund<'info>>,
         amount: u64,
     ) -> Result<()> {
-        ctx.accounts.purchase_carbon_credits(amount, &ctx.bumps)
+        ctx.accounts.handler(amount, ctx.remaining_accounts, &ctx.bumps)
+    }
+
+    pub fn execute_subscription<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteSubscription<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.handler(ctx.remaining_accounts, &ctx.bumps)
+    }
+
+    pub fn set_offset_review_policy(
+        ctx: Context<SetOffsetReviewPolicy>,
+        offset_review_period: i64,
+        expiry_action: ExpiryAction,
+    ) -> Result<()> {
+        ctx.accounts.handler(offset_review_period, expiry_action)
+    }
+
+    pub fn expire_offset_request(ctx: Context<ExpireOffsetRequest>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn transfer_project_ownership(ctx: Context<TransferProjectOwnership>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn set_sale_expiry(ctx: Context<SetSaleExpiry>, sale_expiry: Option<i64>) -> Result<()> {
+        ctx.accounts.handler(sale_expiry)
+    }
+
+    pub fn burn_expired_credits(ctx: Context<BurnExpiredCredits>, amount: u64) -> Result<()> {
+        ctx.accounts.handler(amount)
+    }
+
+    pub fn close_purchase(ctx: Context<ClosePurchase>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn close_offset_request(ctx: Context<CloseOffsetRequest>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn close_project(ctx: Context<CloseProject>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn close_project_vintage(ctx: Context<CloseProjectVintage>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn migrate_carbon_credits(ctx: Context<MigrateCarbonCredits>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn migrate_project(ctx: Context<MigrateAccount>) -> Result<()> {
+        ctx.accounts.migrate_project(ctx.remaining_accounts)
+    }
+
+    pub fn migrate_purchase(ctx: Context<MigrateAccount>) -> Result<()> {
+        ctx.accounts.migrate_purchase()
+    }
+
+    pub fn migrate_offset_request(ctx: Context<MigrateAccount>) -> Result<()> {
+        ctx.accounts.migrate_offset_request(ctx.remaining_accounts)
     }
 }
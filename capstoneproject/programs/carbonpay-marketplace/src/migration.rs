@@ -0,0 +1,374 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::Discriminator;
+
+use crate::errors::ContractError;
+use crate::state::{CarbonCredits, OffsetRequest, Project, Purchase};
+use legacy::{CarbonCreditsV0, OffsetRequestV0, ProjectV0, PurchaseV0};
+
+pub mod legacy;
+
+/// Layout version written by this program. Version 0 is the layout of the first
+/// deployment, before accounts had the `version` and `reserved` fields; see `legacy`.
+/// Version 1 added those two fields, version 2 carved `Project::creator` and
+/// `Project::open_vintages` out of `reserved`, version 3 `Purchase::pending_requests`.
+pub const ACCOUNT_VERSION: u8 = 3;
+
+/// Zeroed bytes at the end of versioned accounts, room for future fields
+pub const RESERVED_SPACE: usize = 64;
+
+/// Accounts that can be migrated in place to the current layout
+pub trait Versioned: AccountSerialize + AccountDeserialize + Discriminator + From<Self::V0> {
+    /// The account as the first deployment stored it
+    type V0: AnchorDeserialize;
+    /// Space of the current layout, without the discriminator
+    const SPACE: usize;
+    /// Space of the version 0 layout, without the discriminator
+    const V0_SPACE: usize;
+
+    fn version(&self) -> u8;
+
+    /// Fill in the fields added after `from_version` and stamp `ACCOUNT_VERSION`.
+    /// Values the account alone cannot tell are left to the migration instruction.
+    fn upgrade(&mut self, from_version: u8);
+}
+
+/// Accounts whose fields since version 0 are all filled in by the conversion from
+/// version 0, or read as their default when carved out of `reserved`
+macro_rules! versioned {
+    ($($account:ty => $v0:ty),*) => {
+        $(
+            impl Versioned for $account {
+                type V0 = $v0;
+                const SPACE: usize = <$account>::INIT_SPACE;
+                const V0_SPACE: usize = <$v0>::SPACE;
+
+                fn version(&self) -> u8 {
+                    self.version
+                }
+
+                fn upgrade(&mut self, _from_version: u8) {
+                    self.version = ACCOUNT_VERSION;
+                    self.reserved.fill(0);
+                }
+            }
+        )*
+    };
+}
+
+versioned!(
+    CarbonCredits => CarbonCreditsV0,
+    OffsetRequest => OffsetRequestV0,
+    Purchase => PurchaseV0
+);
+
+impl Versioned for Project {
+    type V0 = ProjectV0;
+    const SPACE: usize = Project::INIT_SPACE;
+    const V0_SPACE: usize = ProjectV0::SPACE;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn upgrade(&mut self, from_version: u8) {
+        if from_version < 2 {
+            // projects transferred before their migration already set `creator`;
+            // open vintages are counted by `migrate_project`
+            self.creator = *self.seed_owner();
+        }
+        self.version = ACCOUNT_VERSION;
+        self.reserved.fill(0);
+    }
+}
+
+/// Decode account data written with an older layout, together with the version
+/// it was written with. Version 0 accounts are recognized by their size; their
+/// variable-length fields leave stale bytes at the end, which are ignored.
+pub fn decode_outdated<T: Versioned>(data: &[u8]) -> Result<(T, u8)> {
+    let discriminator_len = T::DISCRIMINATOR.len();
+    require!(
+        data.starts_with(T::DISCRIMINATOR),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+
+    if data.len() == discriminator_len + T::V0_SPACE {
+        let v0 = T::V0::deserialize(&mut &data[discriminator_len..])
+            .map_err(|_| ErrorCode::AccountDidNotDeserialize)?;
+        return Ok((T::from(v0), 0));
+    }
+
+    require!(
+        data.len() == discriminator_len + T::SPACE,
+        ContractError::UnknownAccountLayout
+    );
+    let account = T::try_deserialize(&mut &data[..])?;
+    let version = account.version();
+    require!(version < ACCOUNT_VERSION, ContractError::AccountAlreadyMigrated);
+    Ok((account, version))
+}
+
+/// Migrate `info` in place. Once upgraded, `complete` checks the signer against
+/// the account and fills in what the account alone cannot tell, given the version
+/// it is migrated from. The account is then resized to the current layout, with
+/// `payer` topping up its rent, and rewritten. Returns the version it was migrated from.
+pub fn migrate_account<'info, T: Versioned>(
+    info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    complete: impl FnOnce(&mut T, u8) -> Result<()>,
+) -> Result<u8> {
+    require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    let (mut account, from_version) = decode_outdated::<T>(&info.try_borrow_data()?)?;
+    account.upgrade(from_version);
+    complete(&mut account, from_version)?;
+
+    let space = T::DISCRIMINATOR.len() + T::SPACE;
+    let top_up = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(info.lamports());
+    if top_up > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: info.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    info.realloc(space, true)?;
+
+    let mut data = info.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data;
+    account.try_serialize(&mut writer)?;
+    Ok(from_version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::legacy::RequestStatusV0;
+    use super::*;
+    use crate::state::{ProjectStatus, RequestStatus};
+
+    /// The account as the first deployment stored it: the version 0 struct in an
+    /// account of the version 0 size, whose unused bytes hold stale data
+    fn v0_data<T: Versioned>(v0: &impl AnchorSerialize) -> Vec<u8> {
+        let mut data = T::DISCRIMINATOR.to_vec();
+        v0.serialize(&mut data).unwrap();
+        assert!(data.len() <= T::DISCRIMINATOR.len() + T::V0_SPACE);
+        data.resize(T::DISCRIMINATOR.len() + T::V0_SPACE, 0xab);
+        data
+    }
+
+    fn migrate<T: Versioned>(data: &[u8]) -> T {
+        let (mut account, from_version) = decode_outdated::<T>(data).unwrap();
+        assert_eq!(from_version, 0);
+        account.upgrade(from_version);
+
+        // the result round-trips through the current layout
+        let mut migrated = Vec::new();
+        account.try_serialize(&mut migrated).unwrap();
+        assert!(migrated.len() <= T::DISCRIMINATOR.len() + T::SPACE);
+        T::try_deserialize(&mut migrated.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn migrates_purchase_and_offset_request_of_the_first_deployment() {
+        let v0 = PurchaseV0 {
+            buyer: Pubkey::new_unique(),
+            project: Pubkey::new_unique(),
+            amount: 10,
+            remaining_amount: 4,
+            purchase_date: 1_735_689_600,
+            purchase_bump: 254,
+            nft_mint: Pubkey::new_unique(),
+        };
+        let data = v0_data::<Purchase>(&v0);
+        assert_eq!(data.len(), 8 + 121);
+        let purchase = migrate::<Purchase>(&data);
+        assert_eq!(purchase.buyer, v0.buyer);
+        assert_eq!(purchase.remaining_amount, 4);
+        assert_eq!(purchase.purchase_bump, 254);
+        assert_eq!(purchase.current_nft_mint, v0.nft_mint);
+        assert_eq!(purchase.offset_count, 0);
+        assert_eq!(purchase.pending_requests, 0);
+        assert_eq!(purchase.version, ACCOUNT_VERSION);
+        assert_eq!(purchase.reserved, [0; Purchase::RESERVED_SPACE]);
+
+        let v0 = OffsetRequestV0 {
+            offset_requester: Pubkey::new_unique(),
+            purchase: Pubkey::new_unique(),
+            project: Pubkey::new_unique(),
+            amount: 6,
+            request_id: "REQ-1".to_string(),
+            status: RequestStatusV0::Approved,
+            request_date: 1_735_689_600,
+            processed_date: 1_735_776_000,
+            request_bump: 253,
+            processor: Some(Pubkey::new_unique()),
+        };
+        let data = v0_data::<OffsetRequest>(&v0);
+        assert_eq!(data.len(), 8 + 223);
+        let request = migrate::<OffsetRequest>(&data);
+        assert_eq!(request.request_id, "REQ-1");
+        assert!(request.status == RequestStatus::Approved);
+        assert_eq!(request.retirement.beneficiary, v0.offset_requester);
+        assert_eq!(request.processors, vec![v0.processor.unwrap()]);
+        assert_eq!(request.processed_date, 1_735_776_000);
+        assert_eq!(request.request_bump, 253);
+        assert_eq!(request.expiry_action, None);
+        assert_eq!(request.version, ACCOUNT_VERSION);
+    }
+
+    #[test]
+    fn migrates_project_and_platform_of_the_first_deployment() {
+        let v0 = ProjectV0 {
+            owner: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            token_mint: Pubkey::new_unique(),
+            token_bump: 0,
+            is_active: true,
+            amount: 100,
+            remaining_amount: 40,
+            offset_amount: 20,
+            price_per_token: 1_000_000,
+            carbon_pay_fee: 100,
+            carbon_pay_authority: Pubkey::new_unique(),
+            project_bump: 252,
+        };
+        let data = v0_data::<Project>(&v0);
+        assert_eq!(data.len(), 8 + 171);
+        let project = migrate::<Project>(&data);
+        assert_eq!(project.owner, v0.owner);
+        assert_eq!(project.seed_owner(), &v0.owner);
+        assert_eq!(project.status, ProjectStatus::Active);
+        assert_eq!(project.remaining_amount, 40);
+        assert_eq!(project.offset_amount, 20);
+        assert_eq!(project.project_bump, 252);
+        assert_eq!(project.beneficiaries.len(), 1);
+        assert_eq!(project.split_proceeds(1_000).unwrap(), vec![1_000]);
+        assert_eq!(project.version, ACCOUNT_VERSION);
+        assert_eq!(project.reserved, [0; Project::RESERVED_SPACE]);
+
+        let v0 = CarbonCreditsV0 {
+            authority: Pubkey::new_unique(),
+            total_credits: 100,
+            active_credits: 100,
+            offset_credits: 20,
+            projects_count: 1,
+            total_fees_earned: 5_000,
+            bump: 251,
+        };
+        let data = v0_data::<CarbonCredits>(&v0);
+        assert_eq!(data.len(), 8 + 73);
+        let carbon_credits = migrate::<CarbonCredits>(&data);
+        assert_eq!(carbon_credits.authority, v0.authority);
+        assert_eq!(carbon_credits.active_credits, 80);
+        assert_eq!(carbon_credits.offset_credits, 20);
+        assert_eq!(carbon_credits.total_fees_earned, 5_000);
+        assert_eq!(carbon_credits.metadata_base_uri, CarbonCredits::DEFAULT_METADATA_BASE_URI);
+        assert_eq!(carbon_credits.version, ACCOUNT_VERSION);
+    }
+
+    #[test]
+    fn upgrades_projects_of_version_1() {
+        let v1 = |creator: Pubkey| Project {
+            version: 1,
+            creator,
+            ..Project::from(ProjectV0 {
+                owner: Pubkey::new_unique(),
+                mint: Pubkey::new_unique(),
+                token_mint: Pubkey::new_unique(),
+                token_bump: 0,
+                is_active: true,
+                amount: 10,
+                remaining_amount: 10,
+                offset_amount: 0,
+                price_per_token: 1_000_000,
+                carbon_pay_fee: 100,
+                carbon_pay_authority: Pubkey::new_unique(),
+                project_bump: 252,
+            })
+        };
+
+        // `creator` and `open_vintages` read as zeroed from the version 1 padding
+        let mut data = Vec::new();
+        v1(Pubkey::default()).try_serialize(&mut data).unwrap();
+        data.resize(8 + Project::INIT_SPACE, 0);
+        let (mut project, from_version) = decode_outdated::<Project>(&data).unwrap();
+        assert_eq!(from_version, 1);
+        project.upgrade(from_version);
+        assert_eq!(project.creator, project.owner);
+        assert_eq!(project.version, ACCOUNT_VERSION);
+
+        // a project transferred before its migration keeps the creator of its seeds
+        let creator = Pubkey::new_unique();
+        let mut project = v1(creator);
+        project.upgrade(1);
+        assert_eq!(project.creator, creator);
+        assert_eq!(project.seed_owner(), &creator);
+    }
+
+    #[test]
+    fn upgrades_purchases_of_version_2() {
+        let mut purchase = Purchase {
+            version: 2,
+            ..Purchase::from(PurchaseV0 {
+                buyer: Pubkey::new_unique(),
+                project: Pubkey::new_unique(),
+                amount: 10,
+                remaining_amount: 4,
+                purchase_date: 0,
+                purchase_bump: 255,
+                nft_mint: Pubkey::new_unique(),
+            })
+        };
+        // counted by requests made since the upgrade, before the purchase was migrated
+        purchase.record_offset(2).unwrap();
+
+        let mut data = Vec::new();
+        purchase.try_serialize(&mut data).unwrap();
+        let (mut purchase, from_version) = decode_outdated::<Purchase>(&data).unwrap();
+        assert_eq!(from_version, 2);
+        purchase.upgrade(from_version);
+        assert_eq!(purchase.pending_requests, 1);
+        assert_eq!(purchase.version, ACCOUNT_VERSION);
+    }
+
+    #[test]
+    fn rejects_current_and_unknown_layouts() {
+        let purchase = Purchase {
+            version: ACCOUNT_VERSION,
+            ..Purchase::from(PurchaseV0 {
+                buyer: Pubkey::new_unique(),
+                project: Pubkey::new_unique(),
+                amount: 1,
+                remaining_amount: 1,
+                purchase_date: 0,
+                purchase_bump: 255,
+                nft_mint: Pubkey::new_unique(),
+            })
+        };
+        let mut data = Vec::new();
+        purchase.try_serialize(&mut data).unwrap();
+        assert_eq!(
+            decode_outdated::<Purchase>(&data).err(),
+            Some(ContractError::AccountAlreadyMigrated.into())
+        );
+
+        data.truncate(data.len() - 1);
+        assert_eq!(
+            decode_outdated::<Purchase>(&data).err(),
+            Some(ContractError::UnknownAccountLayout.into())
+        );
+
+        data[0] ^= 0xff;
+        assert_eq!(
+            decode_outdated::<Purchase>(&data).err(),
+            Some(ErrorCode::AccountDiscriminatorMismatch.into())
+        );
+    }
+}
@@ -0,0 +1,214 @@
+//! Account layouts of the first deployment (version 0), before accounts were
+//! versioned, and their conversion to the current layout. Fields added since
+//! start from the values a new account would have.
+
+use anchor_lang::prelude::*;
+
+use super::RESERVED_SPACE;
+use crate::state::{
+    CarbonCredits, ExpiryAction, OffsetRequest, PriceCurrency, Project, ProjectStatus, Purchase,
+    RequestStatus, RetirementDetails, RevenueShare,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct CarbonCreditsV0 {
+    pub authority: Pubkey,
+    pub total_credits: u64,
+    pub active_credits: u64,
+    pub offset_credits: u64,
+    pub projects_count: u64,
+    pub total_fees_earned: u64,
+    pub bump: u8,
+}
+
+impl CarbonCreditsV0 {
+    pub const SPACE: usize = 32 + 8 + 8 + 8 + 8 + 8 + 1;
+}
+
+impl From<CarbonCreditsV0> for CarbonCredits {
+    fn from(v0: CarbonCreditsV0) -> Self {
+        Self {
+            authority: v0.authority,
+            total_credits: v0.total_credits,
+            // version 0 never took offset credits out of the active ones
+            active_credits: v0.total_credits.saturating_sub(v0.offset_credits),
+            offset_credits: v0.offset_credits,
+            projects_count: v0.projects_count,
+            total_fees_earned: v0.total_fees_earned,
+            bump: v0.bump,
+            price_feed: Pubkey::default(),
+            max_price_age: 0,
+            max_price_confidence_bps: 0,
+            collection_mint: Pubkey::default(),
+            metadata_base_uri: Self::DEFAULT_METADATA_BASE_URI.to_string(),
+            offset_review_period: 0,
+            expiry_action: ExpiryAction::AutoApprove,
+            expired_requests: 0,
+            refunded_credits: 0,
+            expired_credits: 0,
+            version: 0,
+            reserved: [0; RESERVED_SPACE],
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ProjectV0 {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub token_mint: Pubkey,
+    pub token_bump: u8,
+    pub is_active: bool,
+    pub amount: u64,
+    pub remaining_amount: u64,
+    pub offset_amount: u64,
+    pub price_per_token: u64,
+    pub carbon_pay_fee: u64,
+    pub carbon_pay_authority: Pubkey,
+    pub project_bump: u8,
+}
+
+impl ProjectV0 {
+    pub const SPACE: usize = 32 + 32 + 32 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 32 + 1;
+}
+
+impl From<ProjectV0> for Project {
+    fn from(v0: ProjectV0) -> Self {
+        Self {
+            owner: v0.owner,
+            mint: v0.mint,
+            token_mint: v0.token_mint,
+            token_bump: v0.token_bump,
+            // version 0 listed projects without review
+            status: if v0.is_active {
+                ProjectStatus::Active
+            } else {
+                ProjectStatus::Suspended
+            },
+            amount: v0.amount,
+            remaining_amount: v0.remaining_amount,
+            offset_amount: v0.offset_amount,
+            price_per_token: v0.price_per_token,
+            carbon_pay_fee: v0.carbon_pay_fee,
+            carbon_pay_authority: v0.carbon_pay_authority,
+            project_bump: v0.project_bump,
+            document_hash: [0; 32],
+            reviewer: None,
+            reviewed_at: 0,
+            status_reason: 0,
+            price_currency: PriceCurrency::Lamports,
+            beneficiaries: vec![RevenueShare {
+                recipient: v0.owner,
+                share_bps: Self::TOTAL_SHARE_BPS,
+            }],
+            pending_offsets: 0,
+            presale: None,
+            sale_expiry: None,
+            expired_amount: 0,
+            registry_oracle: Pubkey::default(),
+            attestation_hash: [0; 32],
+            purchase_count: 0,
+            version: 0,
+            creator: Pubkey::default(),
+            open_vintages: 0,
+            reserved: [0; Project::RESERVED_SPACE],
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct PurchaseV0 {
+    pub buyer: Pubkey,
+    pub project: Pubkey,
+    pub amount: u64,
+    pub remaining_amount: u64,
+    pub purchase_date: i64,
+    pub purchase_bump: u8,
+    pub nft_mint: Pubkey,
+}
+
+impl PurchaseV0 {
+    pub const SPACE: usize = 32 + 32 + 8 + 8 + 8 + 1 + 32;
+}
+
+impl From<PurchaseV0> for Purchase {
+    fn from(v0: PurchaseV0) -> Self {
+        Self {
+            buyer: v0.buyer,
+            project: v0.project,
+            amount: v0.amount,
+            remaining_amount: v0.remaining_amount,
+            purchase_date: v0.purchase_date,
+            purchase_bump: v0.purchase_bump,
+            nft_mint: v0.nft_mint,
+            sol_usd_price: 0,
+            sol_usd_expo: 0,
+            // version 0 only ever offset with the purchase NFT itself
+            current_nft_mint: v0.nft_mint,
+            vintage: Pubkey::default(),
+            offset_count: 0,
+            version: 0,
+            pending_requests: 0,
+            reserved: [0; Purchase::RESERVED_SPACE],
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub enum RequestStatusV0 {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct OffsetRequestV0 {
+    pub offset_requester: Pubkey,
+    pub purchase: Pubkey,
+    pub project: Pubkey,
+    pub amount: u64,
+    pub request_id: String,
+    pub status: RequestStatusV0,
+    pub request_date: i64,
+    pub processed_date: i64,
+    pub request_bump: u8,
+    pub processor: Option<Pubkey>,
+}
+
+impl OffsetRequestV0 {
+    pub const SPACE: usize = 32 + 32 + 32 + 8 + 4 + 64 + 1 + 8 + 8 + 1 + 1 + 32;
+}
+
+impl From<OffsetRequestV0> for OffsetRequest {
+    fn from(v0: OffsetRequestV0) -> Self {
+        Self {
+            offset_requester: v0.offset_requester,
+            // version 0 offsets were claimed by the requester, with no details
+            retirement: RetirementDetails {
+                beneficiary: v0.offset_requester,
+                beneficiary_name: String::new(),
+                reason: String::new(),
+                reporting_period_start: 0,
+                reporting_period_end: 0,
+            },
+            purchase: v0.purchase,
+            project: v0.project,
+            amount: v0.amount,
+            request_id: v0.request_id,
+            status: match v0.status {
+                RequestStatusV0::Pending => RequestStatus::Pending,
+                RequestStatusV0::Approved => RequestStatus::Approved,
+                RequestStatusV0::Rejected => RequestStatus::Rejected,
+            },
+            request_date: v0.request_date,
+            processed_date: v0.processed_date,
+            request_bump: v0.request_bump,
+            processors: v0.processor.into_iter().collect(),
+            vintage: Pubkey::default(),
+            review_deadline: 0,
+            expiry_action: None,
+            version: 0,
+            reserved: [0; RESERVED_SPACE],
+        }
+    }
+}
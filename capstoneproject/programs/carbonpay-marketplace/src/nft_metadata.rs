@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use anchor_spl::metadata::mpl_token_metadata::{accounts::Metadata, types::DataV2};
+
+/// URI of a purchase NFT, or of the NFT holding the remaining balance after a
+/// partial offset, under the platform's metadata base URI
+pub fn purchase_nft_uri(base_uri: &str, nft_mint: &Pubkey, remaining: bool) -> String {
+    let base_uri = base_uri.trim_end_matches('/');
+    if remaining {
+        format!("{}/{}/remaining", base_uri, nft_mint)
+    } else {
+        format!("{}/{}", base_uri, nft_mint)
+    }
+}
+
+/// Current metadata with a new name and URI. Creators, collection and uses are
+/// kept as they are so update_metadata_accounts_v2 does not touch verifications.
+pub fn updated_data(metadata: &Metadata, name: Option<String>, uri: String) -> DataV2 {
+    DataV2 {
+        name: name.unwrap_or_else(|| trim_padding(&metadata.name)),
+        symbol: trim_padding(&metadata.symbol),
+        uri,
+        seller_fee_basis_points: metadata.seller_fee_basis_points,
+        creators: metadata.creators.clone(),
+        collection: metadata.collection.clone(),
+        uses: metadata.uses.clone(),
+    }
+}
+
+/// Token metadata pads name, symbol and uri with NUL bytes
+fn trim_padding(value: &str) -> String {
+    value.trim_end_matches('\0').to_string()
+}
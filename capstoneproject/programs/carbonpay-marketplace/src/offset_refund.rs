@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{mint_to, Mint, MintTo, Token, TokenAccount};
+
+use crate::errors::ContractError;
+use crate::state::{CarbonCredits, OffsetRequest, Project, ProjectVintage, Purchase};
+
+/// The accounts needed to reverse an offset request whose credits did not end up
+/// retired, whether rejected by the verifiers or expired with AutoRefund. The
+/// platform's counters are left to the caller, as they differ between the two.
+pub struct OffsetRefund<'a, 'info> {
+    pub offset_request: &'a Account<'info, OffsetRequest>,
+    pub project: &'a mut Account<'info, Project>,
+    pub vintage: Option<&'a mut Account<'info, ProjectVintage>>,
+    pub purchase: Option<&'a AccountInfo<'info>>,
+    pub carbon_credits: &'a Account<'info, CarbonCredits>,
+    pub token_mint: Option<&'a Account<'info, Mint>>,
+    pub requester_token_account: Option<&'a Account<'info, TokenAccount>>,
+    pub balance_nft_mint: Option<&'a Account<'info, Mint>>,
+    pub requester_nft_account: Option<&'a Account<'info, TokenAccount>>,
+    pub token_program: &'a Program<'info, Token>,
+}
+
+impl<'a, 'info> OffsetRefund<'a, 'info> {
+    /// Whether the request's purchase still holds a balance to refund into.
+    /// Checkout retirements were never held as one, and a purchase closed before
+    /// pending requests were counted may be gone.
+    pub fn has_open_purchase(&self) -> Result<bool> {
+        if self.offset_request.purchase == Pubkey::default() {
+            return Ok(false);
+        }
+        let purchase = self.purchase.ok_or(ContractError::MissingRefundAccounts)?;
+        Ok(!purchase.data_is_empty())
+    }
+
+    /// Take the decided request off its purchase's pending count, so the buyer can
+    /// close it once none is left. Requests made before version 3 are only counted
+    /// once `migrate_offset_request` has run on them.
+    pub fn release_purchase(&self) -> Result<()> {
+        if self.offset_request.version < 3 || !self.has_open_purchase()? {
+            return Ok(());
+        }
+        self.update_purchase(|purchase| purchase.record_request_processed())
+    }
+
+    /// Mint the credits back to the requester and reverse the offset on the
+    /// project and the vintage. When the request came from a purchase that is
+    /// still open, its balance is restored too, with the balance NFT if the
+    /// offset had used it up.
+    pub fn refund(&mut self, amount: u64) -> Result<()> {
+        require!(
+            self.vintage.as_ref().map_or(Pubkey::default(), |v| v.key()) == self.offset_request.vintage,
+            ContractError::InvalidVintage
+        );
+        let token_mint = self.token_mint.ok_or(ContractError::MissingRefundAccounts)?;
+        let requester_token_account = self
+            .requester_token_account
+            .ok_or(ContractError::MissingRefundAccounts)?;
+        require!(
+            token_mint.key() == self.vintage.as_ref().map_or(self.project.token_mint, |v| v.token_mint),
+            ContractError::InvalidProjectMint
+        );
+        require!(
+            requester_token_account.mint == token_mint.key()
+                && requester_token_account.owner == self.offset_request.offset_requester,
+            ContractError::InvalidProjectMint
+        );
+        self.mint_to_requester(token_mint, requester_token_account, amount)?;
+
+        if self.has_open_purchase()? {
+            self.update_purchase(|purchase| {
+                // a used-up purchase had its balance NFT burned, the same mint is reissued
+                if purchase.remaining_amount == 0 {
+                    let balance_nft_mint = self.balance_nft_mint.ok_or(ContractError::MissingRefundAccounts)?;
+                    let requester_nft_account = self
+                        .requester_nft_account
+                        .ok_or(ContractError::MissingRefundAccounts)?;
+                    require!(
+                        balance_nft_mint.key() == purchase.current_nft_mint
+                            && requester_nft_account.mint == purchase.current_nft_mint
+                            && requester_nft_account.owner == self.offset_request.offset_requester,
+                        ContractError::InvalidNFTMint
+                    );
+                    self.mint_to_requester(balance_nft_mint, requester_nft_account, 1)?;
+                }
+                purchase.record_refund(amount)
+            })?;
+        }
+
+        self.project.revert_offset(amount)?;
+        if let Some(vintage) = self.vintage.as_mut() {
+            vintage.revert_offset(amount)?;
+        }
+        Ok(())
+    }
+
+    /// Decode the request's open purchase, apply `update` and write it back
+    fn update_purchase(&self, update: impl FnOnce(&mut Purchase) -> Result<()>) -> Result<()> {
+        let purchase_info = self.purchase.ok_or(ContractError::MissingRefundAccounts)?;
+        require_keys_eq!(purchase_info.key(), self.offset_request.purchase, ContractError::InvalidPurchase);
+        require_keys_eq!(*purchase_info.owner, crate::ID, ContractError::InvalidPurchase);
+        let mut purchase = Purchase::try_deserialize(&mut &purchase_info.try_borrow_data()?[..])?;
+        update(&mut purchase)?;
+        purchase.try_serialize(&mut &mut purchase_info.try_borrow_mut_data()?[..])
+    }
+
+    fn mint_to_requester(&self, mint: &Account<'info, Mint>, to: &Account<'info, TokenAccount>, amount: u64) -> Result<()> {
+        mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                MintTo {
+                    mint: mint.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: self.carbon_credits.to_account_info(),
+                },
+                &[&[b"carbon_credits", &[self.carbon_credits.bump]]],
+            ),
+            amount,
+        )
+    }
+}
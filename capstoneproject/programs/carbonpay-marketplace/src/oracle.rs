@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+
+use crate::errors::ContractError;
+use crate::state::{CarbonCredits, PriceCurrency};
+
+/// Minimal reader for a Pyth (v2, push-oracle legacy) price account.
+/// Only the header and the aggregate price are decoded, which is all the
+/// marketplace needs to convert USD-denominated prices to lamports.
+pub struct PythPrice {
+    pub price: i64,     // Aggregate price, scaled by 10^expo
+    pub conf: u64,      // Aggregate confidence interval, scaled by 10^expo
+    pub expo: i32,      // Price exponent
+    pub timestamp: i64, // Unix time of the aggregate price
+    pub status: u32,    // Aggregate price status
+}
+
+impl PythPrice {
+    pub const MAGIC: u32 = 0xa1b2c3d4;
+    pub const VERSION: u32 = 2;
+    pub const ACCOUNT_TYPE_PRICE: u32 = 3;
+    pub const STATUS_TRADING: u32 = 1;
+
+    const EXPO_OFFSET: usize = 20;
+    const TIMESTAMP_OFFSET: usize = 96;
+    const AGG_PRICE_OFFSET: usize = 208;
+    const AGG_CONF_OFFSET: usize = 216;
+    const AGG_STATUS_OFFSET: usize = 224;
+    pub const MIN_LEN: usize = 240;
+
+    pub fn load(data: &[u8]) -> Result<Self> {
+        require!(data.len() >= Self::MIN_LEN, ContractError::InvalidPriceFeed);
+        require!(
+            read_u32(data, 0) == Self::MAGIC
+                && read_u32(data, 4) == Self::VERSION
+                && read_u32(data, 8) == Self::ACCOUNT_TYPE_PRICE,
+            ContractError::InvalidPriceFeed
+        );
+
+        Ok(Self {
+            price: read_u64(data, Self::AGG_PRICE_OFFSET) as i64,
+            conf: read_u64(data, Self::AGG_CONF_OFFSET),
+            expo: read_u32(data, Self::EXPO_OFFSET) as i32,
+            timestamp: read_u64(data, Self::TIMESTAMP_OFFSET) as i64,
+            status: read_u32(data, Self::AGG_STATUS_OFFSET),
+        })
+    }
+
+    /// Reject prices that are not trading, older than `max_age` seconds, or whose
+    /// confidence interval is wider than `max_confidence_bps` of the price
+    pub fn validate(&self, now: i64, max_age: i64, max_confidence_bps: u16) -> Result<()> {
+        require!(
+            self.status == Self::STATUS_TRADING && self.price > 0,
+            ContractError::PriceNotTrading
+        );
+        require!(
+            now.saturating_sub(self.timestamp) <= max_age,
+            ContractError::StalePrice
+        );
+        let conf_bps = (self.conf as u128)
+            .checked_mul(10_000)
+            .ok_or(ContractError::ArithmeticOverflow)?
+            / self.price as u128;
+        require!(
+            conf_bps <= max_confidence_bps as u128,
+            ContractError::PriceConfidenceTooLow
+        );
+        Ok(())
+    }
+
+    /// Convert a USD amount in cents to lamports at this SOL/USD price
+    pub fn usd_cents_to_lamports(&self, cents: u64) -> Result<u64> {
+        // lamports = cents / 100 * LAMPORTS_PER_SOL / (price * 10^expo)
+        let mut numerator = (cents as u128)
+            .checked_mul(LAMPORTS_PER_SOL as u128)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        let mut denominator = (self.price as u128)
+            .checked_mul(100)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+
+        let scale = 10u128
+            .checked_pow(self.expo.unsigned_abs())
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        if self.expo < 0 {
+            numerator = numerator
+                .checked_mul(scale)
+                .ok_or(ContractError::ArithmeticOverflow)?;
+        } else {
+            denominator = denominator
+                .checked_mul(scale)
+                .ok_or(ContractError::ArithmeticOverflow)?;
+        }
+
+        u64::try_from(numerator / denominator).map_err(|_| ContractError::ArithmeticOverflow.into())
+    }
+}
+
+/// Lamports due for a price `total` quoted in `currency`, plus the SOL/USD price and
+/// exponent used for the conversion (zero for lamport prices). USD prices need
+/// the platform's configured SOL/USD `price_feed`.
+pub fn quote_in_lamports(
+    carbon_credits: &CarbonCredits,
+    currency: PriceCurrency,
+    total: u64,
+    price_feed: Option<&AccountInfo>,
+) -> Result<(u64, i64, i32)> {
+    match currency {
+        PriceCurrency::Lamports => Ok((total, 0, 0)),
+        PriceCurrency::UsdCents => {
+            let price_feed = price_feed.ok_or(ContractError::InvalidPriceFeed)?;
+            let price = PythPrice::load(&price_feed.try_borrow_data()?)?;
+            price.validate(
+                Clock::get()?.unix_timestamp,
+                carbon_credits.max_price_age,
+                carbon_credits.max_price_confidence_bps,
+            )?;
+            Ok((price.usd_cents_to_lamports(total)?, price.price, price.expo))
+        }
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
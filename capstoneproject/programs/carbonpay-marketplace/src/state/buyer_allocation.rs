@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+/// Tokens a wallet has bought from a project during its presale, used to
+/// enforce `PresaleConfig.max_per_wallet`
+#[account]
+pub struct BuyerAllocation {
+    pub buyer: Pubkey,    // Wallet the allocation belongs to
+    pub project: Pubkey,  // Project the allocation is for
+    pub purchased: u64,   // Tokens bought during the presale
+    pub bump: u8,         // Bump for the PDA
+}
+
+impl BuyerAllocation {
+    pub const DISCRIMINATOR_SIZE: usize = 8;
+    pub const INIT_SPACE: usize = 32 + // buyer: Pubkey
+        32 + // project: Pubkey
+        8 +  // purchased: u64
+        1; // bump: u8
+}
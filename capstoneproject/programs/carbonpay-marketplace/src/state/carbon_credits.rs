@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
 
+use super::ExpiryAction;
+use crate::migration::{ACCOUNT_VERSION, RESERVED_SPACE};
+
 /// CarbonCredits tracks the global metrics of all carbon credits across all projects in the platform.
 /// This serves as a central dashboard for platform-wide statistics and does not replace
 /// the individual tracking of credits within each Project.
@@ -13,6 +16,18 @@ pub struct CarbonCredits {
     pub projects_count: u64, // Total number of projects created on the platform
     pub total_fees_earned: u64, // Total fees earned by the platform from all projects
     pub bump: u8,            // The PDA bump
+    pub price_feed: Pubkey,  // Pyth SOL/USD price account used for USD-priced projects
+    pub max_price_age: i64,  // Maximum age of an oracle price, in seconds
+    pub max_price_confidence_bps: u16, // Maximum oracle confidence interval, in bps of the price
+    pub collection_mint: Pubkey, // Platform collection NFT that project NFTs are verified into
+    pub metadata_base_uri: String, // Base URI of purchase NFT metadata, followed by `/{mint}`
+    pub offset_review_period: i64, // Seconds verifiers have to decide an offset request, 0 for no deadline
+    pub expiry_action: ExpiryAction, // What happens to offset requests not decided in time
+    pub expired_requests: u64, // Number of offset requests that expired without a decision
+    pub refunded_credits: u64, // Credits minted back to requesters by expired requests
+    pub expired_credits: u64, // Unsold credits burned after their sale expiry
+    pub version: u8, // Layout version, see `migration::ACCOUNT_VERSION`
+    pub reserved: [u8; 64], // Zeroed, room for future fields
 }
 
 impl CarbonCredits {
@@ -23,7 +38,22 @@ impl CarbonCredits {
         8 +  // offset_credits: u64
         8 +  // projects_count: u64
         8 +  // total_fees_earned: u64
-        1; // bump: u8
+        1 +  // bump: u8
+        32 + // price_feed: Pubkey
+        8 +  // max_price_age: i64
+        2 +  // max_price_confidence_bps: u16
+        32 + // collection_mint: Pubkey
+        4 + Self::MAX_METADATA_BASE_URI_LEN + // metadata_base_uri: String
+        8 +  // offset_review_period: i64
+        1 +  // expiry_action: ExpiryAction
+        8 +  // expired_requests: u64
+        8 +  // refunded_credits: u64
+        8 +  // expired_credits: u64
+        1 +  // version: u8
+        RESERVED_SPACE; // reserved: [u8; 64]
+
+    pub const MAX_METADATA_BASE_URI_LEN: usize = 128;
+    pub const DEFAULT_METADATA_BASE_URI: &str = "https://carbonpay.com/purchases";
 
     /// Initialize the global platform dashboard
     pub fn initialize(&mut self, authority: Pubkey, bump: u8) -> Result<()> {
@@ -34,6 +64,18 @@ impl CarbonCredits {
         self.projects_count = 0;
         self.total_fees_earned = 0;
         self.bump = bump;
+        self.price_feed = Pubkey::default();
+        self.max_price_age = 0;
+        self.max_price_confidence_bps = 0;
+        self.collection_mint = Pubkey::default();
+        self.metadata_base_uri = Self::DEFAULT_METADATA_BASE_URI.to_string();
+        self.offset_review_period = 0;
+        self.expiry_action = ExpiryAction::AutoApprove;
+        self.expired_requests = 0;
+        self.refunded_credits = 0;
+        self.expired_credits = 0;
+        self.version = ACCOUNT_VERSION;
+        self.reserved = [0; RESERVED_SPACE];
         Ok(())
     }
 
@@ -55,6 +97,19 @@ impl CarbonCredits {
         Ok(())
     }
 
+    /// Add credits issued to an existing project to the global tracking
+    pub fn add_issued_credits(&mut self, credits_amount: u64) -> Result<()> {
+        self.total_credits = self
+            .total_credits
+            .checked_add(credits_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.active_credits = self
+            .active_credits
+            .checked_add(credits_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
     /// Record a carbon credit offset in the global tracking
     pub fn record_offset(&mut self, offset_amount: u64) -> Result<()> {
         // Update global counts when credits are offset
@@ -69,6 +124,46 @@ impl CarbonCredits {
         Ok(())
     }
 
+    /// Reverse an offset whose request was rejected or refunded; the credits are active again
+    pub fn revert_offset(&mut self, offset_amount: u64) -> Result<()> {
+        self.offset_credits = self
+            .offset_credits
+            .checked_sub(offset_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.active_credits = self
+            .active_credits
+            .checked_add(offset_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Record an offset request that expired; refunded credits are active again
+    pub fn record_expiry(&mut self, refunded_amount: u64) -> Result<()> {
+        self.expired_requests = self
+            .expired_requests
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.revert_offset(refunded_amount)?;
+        self.refunded_credits = self
+            .refunded_credits
+            .checked_add(refunded_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Record unsold credits burned after their sale expiry; they are no longer active
+    pub fn record_expired_burn(&mut self, burned_amount: u64) -> Result<()> {
+        self.active_credits = self
+            .active_credits
+            .checked_sub(burned_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.expired_credits = self
+            .expired_credits
+            .checked_add(burned_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
     /// Add platform fees to the global tracking
     pub fn add_fees(&mut self, fee_amount: u64) -> Result<()> {
         // Track total fees earned by the platform
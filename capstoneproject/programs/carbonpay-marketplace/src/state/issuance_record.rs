@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+/// Credits issued to an existing project under one registry attestation, at
+/// `["issuance", project, serial_start]`. It can only be created once, so an
+/// attestation cannot be used to issue the same serial range twice.
+#[account]
+pub struct IssuanceRecord {
+    pub project: Pubkey,             // Project the credits were issued to
+    pub registry_oracle: Pubkey,     // Oracle that signed the attestation
+    pub serial_start: u64,           // First serial number issued (inclusive)
+    pub serial_end: u64,             // Last serial number issued (inclusive)
+    pub attestation_hash: [u8; 32],  // sha256 of the signed attestation
+    pub issued_at: i64,              // Unix timestamp of the issuance
+    pub bump: u8,                    // Bump for the PDA
+}
+
+impl IssuanceRecord {
+    pub const DISCRIMINATOR_SIZE: usize = 8;
+    pub const INIT_SPACE: usize = 32 + // project: Pubkey
+        32 + // registry_oracle: Pubkey
+        8 +  // serial_start: u64
+        8 +  // serial_end: u64
+        32 + // attestation_hash: [u8; 32]
+        8 +  // issued_at: i64
+        1; // bump: u8
+}
@@ -1,8 +1,30 @@
+pub mod buyer_allocation;
 pub mod carbon_credits;
+pub mod issuance_record;
 pub mod project;
+pub mod project_vintage;
 pub mod purchase;   
+pub mod referrer;
+pub mod registry_oracle;
+pub mod retirement_fund;
+pub mod sales_stats;
+pub mod offset_pool;
 pub mod offset_request;
+pub mod subscription;
+pub mod verifier_registry;
+pub mod vote_record;
+pub use buyer_allocation::*;
 pub use carbon_credits::*;
+pub use issuance_record::*;
 pub use project::*; 
+pub use project_vintage::*;
 pub use purchase::*;
+pub use referrer::*;
+pub use registry_oracle::*;
+pub use retirement_fund::*;
+pub use sales_stats::*;
+pub use offset_pool::*;
 pub use offset_request::*;
+pub use subscription::*;
+pub use verifier_registry::*;
+pub use vote_record::*;
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+/// Lamports pre-funded for retiring credits on behalf of `authority`, usually a
+/// PDA of another program that retires credits through CPI as part of its own
+/// instructions. Anyone can fund the pool; only `authority` can spend it.
+#[account]
+pub struct OffsetPool {
+    pub authority: Pubkey,          // Signer of retirements and their beneficiary
+    pub retirements: u64,           // Number of retirements so far
+    pub total_retired: u64,         // Tokens retired so far
+    pub total_spent: u64,           // Lamports spent on credits and fees so far
+    pub bump: u8,                   // Bump for the PDA
+}
+
+impl OffsetPool {
+    pub const DISCRIMINATOR_SIZE: usize = 8;
+    pub const INIT_SPACE: usize = 32 + // authority: Pubkey
+        8 +  // retirements: u64
+        8 +  // total_retired: u64
+        8 +  // total_spent: u64
+        1; // bump: u8
+
+    /// Request id of the next retirement's record; an authority has a single pool
+    pub fn next_request_id(&self) -> String {
+        format!("pool-{}", self.retirements.saturating_add(1))
+    }
+
+    /// Record a retirement of `tokens` paid with `spent` lamports
+    pub fn record_retirement(&mut self, tokens: u64, spent: u64) -> Result<()> {
+        self.retirements = self
+            .retirements
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.total_retired = self
+            .total_retired
+            .checked_add(tokens)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.total_spent = self
+            .total_spent
+            .checked_add(spent)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
@@ -1,36 +1,160 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::ContractError;
+use crate::migration::RESERVED_SPACE;
+use crate::state::VerifierRegistry;
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
 pub enum RequestStatus {
     Pending,
     Approved,
     Rejected,
+    Expired,
+}
+
+/// What happens to a pending offset request once its review deadline has passed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ExpiryAction {
+    AutoApprove, // The credits stay retired, as if the request had been approved
+    AutoRefund,  // The offset is reversed and the credits minted back to the requester
+}
+
+/// Who the credits are retired for and why. The payer (offset_requester) can retire
+/// on behalf of a subsidiary, a customer or an event.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct RetirementDetails {
+    pub beneficiary: Pubkey,         // Entity the retirement is claimed for
+    pub beneficiary_name: String,    // Display name of the beneficiary
+    pub reason: String,              // Retirement reason / message
+    pub reporting_period_start: i64, // Start of the reporting period the offset applies to
+    pub reporting_period_end: i64,   // End of the reporting period the offset applies to
+}
+
+impl RetirementDetails {
+    pub const MAX_BENEFICIARY_NAME_LEN: usize = 64;
+    pub const MAX_REASON_LEN: usize = 200;
+    pub const INIT_SPACE: usize = 32 + // beneficiary
+        4 + Self::MAX_BENEFICIARY_NAME_LEN + // beneficiary_name (prefix + max length)
+        4 + Self::MAX_REASON_LEN + // reason (prefix + max length)
+        8 + // reporting_period_start
+        8; // reporting_period_end
+
+    /// Details of credits the program retires for `beneficiary` on a schedule or
+    /// by CPI (subscriptions, offset pools and retirement funds): no name or
+    /// reason, reported at the time of the retirement
+    pub fn for_beneficiary(beneficiary: Pubkey, now: i64) -> Self {
+        Self {
+            beneficiary,
+            beneficiary_name: String::new(),
+            reason: String::new(),
+            reporting_period_start: now,
+            reporting_period_end: now,
+        }
+    }
+
+    /// Check the text lengths and that the reporting period is not reversed
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.beneficiary_name.len() <= Self::MAX_BENEFICIARY_NAME_LEN,
+            ContractError::BeneficiaryNameTooLong
+        );
+        require!(
+            self.reason.len() <= Self::MAX_REASON_LEN,
+            ContractError::RetirementReasonTooLong
+        );
+        require!(
+            self.reporting_period_start <= self.reporting_period_end,
+            ContractError::InvalidReportingPeriod
+        );
+        Ok(())
+    }
 }
 
 #[account]
 pub struct OffsetRequest {
-    pub offset_requester: Pubkey,   // Buyer requesting the offset
-    pub purchase: Pubkey,          // The purchase account this request is for
-    pub project: Pubkey,           // The project this purchase belongs to
-    pub amount: u64,               // Amount of tokens to offset
-    pub request_id: String,        // Unique identifier for this request
-    pub status: RequestStatus,     // Status of the request (pending/approved/rejected)
-    pub request_date: i64,         // When the request was created
-    pub processed_date: i64,       // When the request was processed (approved/rejected)
-    pub request_bump: u8,          // Bump for the PDA
-    pub processor: Option<Pubkey>, // Authority who processed the request
+    pub offset_requester: Pubkey,       // Buyer requesting (and paying for) the offset
+    pub retirement: RetirementDetails,  // Beneficiary, reason and reporting period
+    pub purchase: Pubkey,               // The purchase account this request is for, default if bought and retired at once
+    pub project: Pubkey,                // The project this purchase belongs to
+    pub amount: u64,                    // Amount of tokens to offset
+    pub request_id: String,             // Unique identifier for this request
+    pub status: RequestStatus,          // Status of the request (pending/approved/rejected/expired)
+    pub request_date: i64,              // When the request was created
+    pub processed_date: i64,            // When the request was processed (approved/rejected/expired)
+    pub request_bump: u8,               // Bump for the PDA
+    pub processors: Vec<Pubkey>,        // Verifiers whose votes decided the request
+    pub vintage: Pubkey,                // Vintage of the purchase, default if bought from the project
+    pub review_deadline: i64,           // After this time the request can be expired, 0 if never
+    pub expiry_action: Option<ExpiryAction>, // Action applied when the request expired
+    pub version: u8,                    // Layout version, see `migration::ACCOUNT_VERSION`
+    pub reserved: [u8; 64],             // Zeroed, room for future fields
 }
 
 impl OffsetRequest {
     pub const DISCRIMINATOR_SIZE: usize = 8;
+    /// `request_id` is a seed of the request PDA, and seeds are at most 32 bytes
+    pub const MAX_REQUEST_ID_LEN: usize = 32;
+    /// Byte offset of `retirement.beneficiary`, for `memcmp` filters that look up
+    /// retirements by beneficiary regardless of who paid for them
+    pub const BENEFICIARY_OFFSET: usize = Self::DISCRIMINATOR_SIZE + 32;
     pub const INIT_SPACE: usize = 32 + // offset_requester
+        RetirementDetails::INIT_SPACE + // retirement
         32 + // purchase
         32 + // project
         8 + // amount
-        4 + 64 + // request_id (prefix + max length)
+        4 + 64 + // request_id (prefix + the 64 bytes accounts were sized with before the seed-length cap)
         1 + // status enum
         8 + // request_date
         8 + // processed_date
         1 + // request_bump
-        1 + 32; // processor (Option<Pubkey>)
+        4 + 32 * VerifierRegistry::MAX_VERIFIERS + // processors (Vec<Pubkey>)
+        32 + // vintage
+        8 + // review_deadline
+        1 + 1 + // expiry_action (Option<ExpiryAction>)
+        1 + // version
+        RESERVED_SPACE; // reserved
+
+    /// Check that `request_id` fits in a PDA seed
+    pub fn validate_request_id(request_id: &str) -> Result<()> {
+        require!(
+            request_id.len() <= Self::MAX_REQUEST_ID_LEN,
+            ContractError::RequestIdTooLong
+        );
+        Ok(())
+    }
+
+    /// Whether the review deadline has passed without a verifier decision
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.status == RequestStatus::Pending && self.review_deadline != 0 && now >= self.review_deadline
+    }
+
+    /// Whether the credits of this request ended up retired
+    pub fn is_retired(&self) -> bool {
+        match self.status {
+            RequestStatus::Approved => true,
+            RequestStatus::Expired => self.expiry_action == Some(ExpiryAction::AutoApprove),
+            RequestStatus::Pending | RequestStatus::Rejected => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_ids_are_capped_at_the_seed_length() {
+        let longest = "R".repeat(OffsetRequest::MAX_REQUEST_ID_LEN);
+        assert!(OffsetRequest::validate_request_id(&longest).is_ok());
+        let seeds: &[&[u8]] = &[b"offset_request", &[1; 32], &[2; 32], longest.as_bytes()];
+        assert!(Pubkey::try_find_program_address(seeds, &crate::ID).is_some());
+
+        let too_long = "R".repeat(OffsetRequest::MAX_REQUEST_ID_LEN + 1);
+        assert_eq!(
+            OffsetRequest::validate_request_id(&too_long).err(),
+            Some(ContractError::RequestIdTooLong.into())
+        );
+        let seeds: &[&[u8]] = &[b"offset_request", &[1; 32], &[2; 32], too_long.as_bytes()];
+        assert!(Pubkey::try_find_program_address(seeds, &crate::ID).is_none());
+    }
 }
@@ -1,21 +1,99 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::errors::ContractError;
+use crate::migration::RESERVED_SPACE;
+
+/// Listing lifecycle of a project. Sales are only open while `Active`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ProjectStatus {
+    PendingReview, // Submitted, waiting for a platform verifier
+    Active,        // Approved by a verifier, open for sales
+    Rejected,      // Rejected by a verifier, see `status_reason`
+    Suspended,     // Suspended by the platform authority, see `status_reason`
+}
+
+/// Unit in which `Project.price_per_token` is quoted
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PriceCurrency {
+    Lamports, // Fixed price in lamports
+    UsdCents, // USD cents, converted to lamports at purchase time via the SOL/USD oracle
+}
+
+/// A recipient of purchase proceeds and its share in basis points
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RevenueShare {
+    pub recipient: Pubkey, // Account that receives this share of the proceeds
+    pub share_bps: u16,    // Share of the owner proceeds (10_000 = 100%)
+}
+
+/// Allowlisted early-access sale. Between `start_time` and `end_time` only wallets
+/// in the merkle tree can buy, within the per-transaction and per-wallet caps.
+/// Sales are closed before `start_time` and public after `end_time`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PresaleConfig {
+    pub merkle_root: [u8; 32], // Root of the allowlist, leaves are sha256(wallet)
+    pub start_time: i64,       // When the presale opens
+    pub end_time: i64,         // When the presale ends and the public sale opens
+    pub max_per_wallet: u64,   // Maximum tokens one wallet can buy during the presale
+    pub max_per_tx: u64,       // Maximum tokens per purchase during the presale
+}
+
+impl PresaleConfig {
+    pub const INIT_SPACE: usize = 32 + // merkle_root: [u8; 32]
+        8 + // start_time: i64
+        8 + // end_time: i64
+        8 + // max_per_wallet: u64
+        8; // max_per_tx: u64
+
+    /// Whether `wallet` is in the allowlist. Proof nodes are hashed in sorted
+    /// pair order, so no left/right flags are needed.
+    pub fn is_allowed(&self, wallet: &Pubkey, proof: &[[u8; 32]]) -> bool {
+        let leaf = hashv(&[wallet.as_ref()]).to_bytes();
+        let root = proof.iter().fold(leaf, |node, sibling| {
+            if node <= *sibling {
+                hashv(&[&node, sibling]).to_bytes()
+            } else {
+                hashv(&[sibling, &node]).to_bytes()
+            }
+        });
+        root == self.merkle_root
+    }
+}
 
 /// Project represents a specific carbon credit offering with its own tokens and tracking.
 /// Each project has its own independent accounting of credits, separate from other projects.
 #[account]
 pub struct Project {
-    pub owner: Pubkey, // The user who lists their carbon credits license in the platform
+    pub owner: Pubkey, // Current owner: holder of the project NFT who last claimed the project
     pub mint: Pubkey,  // The NFT mint for the project owner
     pub token_mint: Pubkey, // The token mint for fungible tokens stored in the vault
     pub token_bump: u8, // The token bump
-    pub is_active: bool, // Status of the project
+    pub status: ProjectStatus, // Review / listing status of the project
     pub amount: u64,   // Total amount of tokens minted for this project
     pub remaining_amount: u64, // Amount of tokens not yet sold in this project
     pub offset_amount: u64, // Amount of tokens that have been offset in this project
-    pub price_per_token: u64, // Price per token in lamports
+    pub price_per_token: u64, // Price per token, in the unit given by `price_currency`
     pub carbon_pay_fee: u64, // Fee percentage taken by CarbonPay (e.g. 500 = 5.00%)
     pub carbon_pay_authority: Pubkey, // Authority that can receive fees
     pub project_bump: u8, // Project bump
+    pub document_hash: [u8; 32], // Hash of the documents submitted for review
+    pub reviewer: Option<Pubkey>, // Verifier (or authority) that last changed the status
+    pub reviewed_at: i64, // When the status was last changed by a reviewer
+    pub status_reason: u16, // Reason code for a rejection or suspension (0 = none)
+    pub price_currency: PriceCurrency, // Whether `price_per_token` is in lamports or USD cents
+    pub beneficiaries: Vec<RevenueShare>, // How purchase proceeds (after fees) are split
+    pub pending_offsets: u32, // Offset requests waiting for a verifier decision
+    pub presale: Option<PresaleConfig>, // Allowlisted presale phase, if any
+    pub sale_expiry: Option<i64>, // After this time the project's own tranche can no longer be sold
+    pub expired_amount: u64, // Unsold tokens burned after their sale expiry, across the project and its vintages
+    pub registry_oracle: Pubkey, // Registry oracle key that attested the credits
+    pub attestation_hash: [u8; 32], // sha256 of the signed tokenization attestation
+    pub purchase_count: u64, // Number of purchases, seeds the next purchase NFT mint
+    pub version: u8, // Layout version, see `migration::ACCOUNT_VERSION`
+    pub creator: Pubkey, // Owner the project was created by, part of the PDA seeds (default: `owner`)
+    pub open_vintages: u16, // Vintage tranches not closed yet, the project can only be closed after them
+    pub reserved: [u8; 30], // Zeroed, room for future fields
 }
 
 impl Project {
@@ -24,38 +102,241 @@ impl Project {
         32 +  // mint: Pubkey
         32 +  // token_mint: Pubkey
         1 +   // token_bump: u8
-        1 +   // is_active: bool
+        1 +   // status: ProjectStatus
         8 +   // amount: u64
         8 +   // remaining_amount: u64
         8 +   // offset_amount: u64
         8 +   // price_per_token: u64
         8 +   // carbon_pay_fee: u64
         32 +  // carbon_pay_authority: Pubkey
-        1; // project_bump: u8
+        1 +   // project_bump: u8
+        32 +  // document_hash: [u8; 32]
+        1 + 32 + // reviewer: Option<Pubkey>
+        8 +   // reviewed_at: i64
+        2 +   // status_reason: u16
+        1 +   // price_currency: PriceCurrency
+        4 + Self::MAX_BENEFICIARIES * (32 + 2) + // beneficiaries: Vec<RevenueShare>
+        4 +   // pending_offsets: u32
+        1 + PresaleConfig::INIT_SPACE + // presale: Option<PresaleConfig>
+        1 + 8 + // sale_expiry: Option<i64>
+        8 +     // expired_amount: u64
+        32 +    // registry_oracle: Pubkey
+        32 +    // attestation_hash: [u8; 32]
+        8 +     // purchase_count: u64
+        1 +     // version: u8
+        32 +    // creator: Pubkey
+        2 +     // open_vintages: u16
+        Self::RESERVED_SPACE; // reserved: [u8; 30]
+
+    /// What is left of the versioning padding after `creator` and `open_vintages`
+    pub const RESERVED_SPACE: usize = RESERVED_SPACE - 32 - 2;
+    pub const MAX_BENEFICIARIES: usize = 5;
+    pub const TOTAL_SHARE_BPS: u16 = 10_000;
 
     /// Initialize a new carbon credit project
     pub fn initialize(&mut self) -> Result<()> {
-        self.is_active = true;
+        self.status = ProjectStatus::PendingReview;
         self.remaining_amount = self.amount;
         self.offset_amount = 0;
         Ok(())
     }
 
+    /// Owner key of the PDA seeds, `["project", seed_owner, mint]`. Projects created
+    /// before `creator` existed have it zeroed and were never transferred.
+    pub fn seed_owner(&self) -> &Pubkey {
+        if self.creator == Pubkey::default() {
+            &self.owner
+        } else {
+            &self.creator
+        }
+    }
+
+    /// Hand the project to `new_owner`. The previous owner's revenue share goes
+    /// with it; other beneficiaries keep theirs.
+    pub fn transfer_ownership(&mut self, new_owner: Pubkey) -> Result<()> {
+        let previous_owner = self.owner;
+        self.creator = *self.seed_owner();
+        self.owner = new_owner;
+
+        let Some(i) = self
+            .beneficiaries
+            .iter()
+            .position(|b| b.recipient == previous_owner)
+        else {
+            return Ok(());
+        };
+        match self.beneficiaries.iter().position(|b| b.recipient == new_owner) {
+            // already a beneficiary: the shares are merged
+            Some(j) => {
+                let share_bps = self.beneficiaries.remove(i).share_bps;
+                let j = if j > i { j - 1 } else { j };
+                self.beneficiaries[j].share_bps = self.beneficiaries[j]
+                    .share_bps
+                    .checked_add(share_bps)
+                    .ok_or(ContractError::ArithmeticOverflow)?;
+            }
+            None => self.beneficiaries[i].recipient = new_owner,
+        }
+        Ok(())
+    }
+
+    /// Whether the project is open for sales
+    pub fn is_active(&self) -> bool {
+        self.status == ProjectStatus::Active
+    }
+
+    /// Record a review decision (approval, rejection or suspension)
+    pub fn set_status(&mut self, status: ProjectStatus, reviewer: Pubkey, reason: u16) -> Result<()> {
+        self.status = status;
+        self.reviewer = Some(reviewer);
+        self.reviewed_at = Clock::get()?.unix_timestamp;
+        self.status_reason = reason;
+        Ok(())
+    }
+
+    /// Check a revenue split: 1 to MAX_BENEFICIARIES distinct recipients, each with a
+    /// non-zero share, summing to exactly 10_000 bps
+    pub fn validate_beneficiaries(beneficiaries: &[RevenueShare]) -> Result<()> {
+        require!(
+            !beneficiaries.is_empty() && beneficiaries.len() <= Self::MAX_BENEFICIARIES,
+            ContractError::InvalidBeneficiaryCount
+        );
+        let mut total: u16 = 0;
+        for (i, share) in beneficiaries.iter().enumerate() {
+            require!(share.share_bps > 0, ContractError::InvalidBeneficiaryShares);
+            require!(
+                !beneficiaries[..i].iter().any(|b| b.recipient == share.recipient),
+                ContractError::DuplicateBeneficiary
+            );
+            total = total
+                .checked_add(share.share_bps)
+                .ok_or(ContractError::InvalidBeneficiaryShares)?;
+        }
+        require!(total == Self::TOTAL_SHARE_BPS, ContractError::InvalidBeneficiaryShares);
+        Ok(())
+    }
+
+    /// Split `proceeds` according to the beneficiaries list. Rounding dust goes to the
+    /// first beneficiary so the parts always add up to `proceeds`.
+    pub fn split_proceeds(&self, proceeds: u64) -> Result<Vec<u64>> {
+        let mut parts = self
+            .beneficiaries
+            .iter()
+            .map(|b| {
+                let part = (proceeds as u128) * (b.share_bps as u128) / (Self::TOTAL_SHARE_BPS as u128);
+                u64::try_from(part).map_err(|_| ContractError::ArithmeticOverflow.into())
+            })
+            .collect::<Result<Vec<u64>>>()?;
+        let distributed = parts
+            .iter()
+            .try_fold(0u64, |acc, part| acc.checked_add(*part))
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        if let Some(first) = parts.first_mut() {
+            *first = first
+                .checked_add(proceeds.checked_sub(distributed).ok_or(ContractError::ArithmeticOverflow)?)
+                .ok_or(ContractError::ArithmeticOverflow)?;
+        }
+        Ok(parts)
+    }
+
+    /// Record newly issued credits added to the vault
+    pub fn record_issuance(&mut self, issued_amount: u64) -> Result<()> {
+        self.amount = self
+            .amount
+            .checked_add(issued_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.remaining_amount = self
+            .remaining_amount
+            .checked_add(issued_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
     /// Record a purchase of credits from this project
     pub fn record_purchase(&mut self, purchase_amount: u64) -> Result<()> {
         self.remaining_amount = self
             .remaining_amount
             .checked_sub(purchase_amount)
             .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.purchase_count = self
+            .purchase_count
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
         Ok(())
     }
 
-    /// Record an offset of credits from this project
+    /// Record an offset of credits from this project, pending verifier review
     pub fn record_offset(&mut self, offset_amount: u64) -> Result<()> {
         self.offset_amount = self
             .offset_amount
             .checked_add(offset_amount)
             .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.pending_offsets = self
+            .pending_offsets
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Reverse the offset of a request that was refunded instead of retired
+    pub fn revert_offset(&mut self, offset_amount: u64) -> Result<()> {
+        self.offset_amount = self
+            .offset_amount
+            .checked_sub(offset_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Whether the project's own tranche is past its sale expiry
+    pub fn is_sale_expired(&self, now: i64) -> bool {
+        self.sale_expiry.is_some_and(|expiry| now >= expiry)
+    }
+
+    /// Record unsold tokens burned after their sale expiry
+    pub fn record_expired_burn(&mut self, burned_amount: u64) -> Result<()> {
+        self.remaining_amount = self
+            .remaining_amount
+            .checked_sub(burned_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.expired_amount = self
+            .expired_amount
+            .checked_add(burned_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
         Ok(())
     }
+
+    /// Record that a pending offset request has been approved, rejected or expired
+    pub fn record_offset_processed(&mut self) -> Result<()> {
+        self.pending_offsets = self
+            .pending_offsets
+            .checked_sub(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Record a vintage tranche added to the project
+    pub fn record_vintage_added(&mut self) -> Result<()> {
+        self.open_vintages = self
+            .open_vintages
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Record a finished vintage tranche closed
+    pub fn record_vintage_closed(&mut self) -> Result<()> {
+        self.open_vintages = self
+            .open_vintages
+            .checked_sub(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Whether every credit has been sold and offset (or burned after expiry), with
+    /// no offset request left to review, so the project can be closed
+    pub fn is_finished(&self) -> bool {
+        self.remaining_amount == 0
+            && self.offset_amount.checked_add(self.expired_amount) == Some(self.amount)
+            && self.pending_offsets == 0
+    }
 }
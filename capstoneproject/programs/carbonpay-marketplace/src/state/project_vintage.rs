@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+
+/// A vintage tranche of a project: credits from one vintage year, with their own
+/// mint, vault and price. Its amounts are also rolled up into the parent `Project`.
+#[account]
+pub struct ProjectVintage {
+    pub project: Pubkey,         // Parent project PDA
+    pub vintage_year: u16,       // Year the credits in this tranche were generated
+    pub token_mint: Pubkey,      // Fungible token mint of this vintage
+    pub vault: Pubkey,           // carbon_credits ATA holding the unsold tokens
+    pub amount: u64,             // Total amount of tokens minted for this vintage
+    pub remaining_amount: u64,   // Amount of tokens not yet sold in this vintage
+    pub offset_amount: u64,      // Amount of tokens that have been offset in this vintage
+    pub price_per_token: u64,    // Price per token, in the project's `price_currency`
+    pub bump: u8,                // Bump for the PDA
+    pub sale_expiry: Option<i64>, // After this time the vintage can no longer be sold
+    pub expired_amount: u64,     // Unsold tokens burned after the sale expiry
+}
+
+impl ProjectVintage {
+    pub const DISCRIMINATOR_SIZE: usize = 8;
+    pub const INIT_SPACE: usize = 32 + // project: Pubkey
+        2 +  // vintage_year: u16
+        32 + // token_mint: Pubkey
+        32 + // vault: Pubkey
+        8 +  // amount: u64
+        8 +  // remaining_amount: u64
+        8 +  // offset_amount: u64
+        8 +  // price_per_token: u64
+        1 +  // bump: u8
+        1 + 8 + // sale_expiry: Option<i64>
+        8; // expired_amount: u64
+
+    /// Record a purchase of credits from this vintage
+    pub fn record_purchase(&mut self, purchase_amount: u64) -> Result<()> {
+        self.remaining_amount = self
+            .remaining_amount
+            .checked_sub(purchase_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Whether every credit of the vintage has been sold and offset, or burned after
+    /// its expiry, so it can be closed
+    pub fn is_finished(&self) -> bool {
+        self.remaining_amount == 0
+            && self.offset_amount.checked_add(self.expired_amount) == Some(self.amount)
+    }
+
+    /// Whether the vintage is past its sale expiry
+    pub fn is_sale_expired(&self, now: i64) -> bool {
+        self.sale_expiry.is_some_and(|expiry| now >= expiry)
+    }
+
+    /// Record unsold tokens of this vintage burned after its sale expiry
+    pub fn record_expired_burn(&mut self, burned_amount: u64) -> Result<()> {
+        self.remaining_amount = self
+            .remaining_amount
+            .checked_sub(burned_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.expired_amount = self
+            .expired_amount
+            .checked_add(burned_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Record an offset of credits from this vintage
+    pub fn record_offset(&mut self, offset_amount: u64) -> Result<()> {
+        self.offset_amount = self
+            .offset_amount
+            .checked_add(offset_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Reverse an offset of credits from this vintage
+    pub fn revert_offset(&mut self, offset_amount: u64) -> Result<()> {
+        self.offset_amount = self
+            .offset_amount
+            .checked_sub(offset_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
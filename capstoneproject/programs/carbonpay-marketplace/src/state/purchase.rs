@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::migration::RESERVED_SPACE;
+
 #[account]
 
 pub struct Purchase {
@@ -10,6 +12,14 @@ pub struct Purchase {
     pub purchase_date: i64,    // Timestamp when purchase was made
     pub purchase_bump: u8,     // Bump for the purchase PDA
     pub nft_mint: Pubkey,      // Mint of the NFT representing this purchase
+    pub sol_usd_price: i64,    // SOL/USD oracle price used for USD-priced projects (0 otherwise)
+    pub sol_usd_expo: i32,     // Exponent of `sol_usd_price`
+    pub current_nft_mint: Pubkey, // NFT currently representing the unoffset balance
+    pub vintage: Pubkey,       // ProjectVintage the credits came from (default = the project's own tranche)
+    pub offset_count: u64,     // Number of offset requests, seeds the next remaining-balance NFT mint
+    pub version: u8,           // Layout version, see `migration::ACCOUNT_VERSION`
+    pub pending_requests: u32, // Offset requests of this purchase not yet approved, rejected or expired
+    pub reserved: [u8; 60],    // Zeroed, room for future fields
 }
 
 impl Purchase {
@@ -20,5 +30,48 @@ impl Purchase {
         8 +  // remaining_amount: u64
         8 +  // purchase_date: i64
         1 +  // purchase_bump: u8
-        32; // nft_mint: Pubkey
+        32 + // nft_mint: Pubkey
+        8 +  // sol_usd_price: i64
+        4 +  // sol_usd_expo: i32
+        32 + // current_nft_mint: Pubkey
+        32 + // vintage: Pubkey
+        8 +  // offset_count: u64
+        1 +  // version: u8
+        4 +  // pending_requests: u32
+        Self::RESERVED_SPACE; // reserved: [u8; 60]
+
+    /// What is left of the versioning padding after `pending_requests`
+    pub const RESERVED_SPACE: usize = RESERVED_SPACE - 4;
+
+    /// Record an offset request against this purchase, pending verifier review
+    pub fn record_offset(&mut self, remaining_amount: u64) -> Result<()> {
+        self.remaining_amount = remaining_amount;
+        self.offset_count = self
+            .offset_count
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.pending_requests = self
+            .pending_requests
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Record that one of its offset requests has been approved, rejected or expired
+    pub fn record_request_processed(&mut self) -> Result<()> {
+        self.pending_requests = self
+            .pending_requests
+            .checked_sub(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Put the credits of a refunded offset request back into the unoffset balance
+    pub fn record_refund(&mut self, refunded_amount: u64) -> Result<()> {
+        self.remaining_amount = self
+            .remaining_amount
+            .checked_add(refunded_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
 }
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ContractError;
+
+/// A sales partner registered by the platform. Its cut is taken out of the
+/// platform fee on purchases it refers and accrues on this account until claimed.
+#[account]
+pub struct Referrer {
+    pub wallet: Pubkey,          // Affiliate wallet, receives claimed fees
+    pub share_bps: u16,          // Share of the platform fee paid to the referrer (10_000 = 100%)
+    pub accrued_lamports: u64,   // Referral fees held on this account, not yet claimed
+    pub total_earned: u64,       // Referral fees ever credited
+    pub referred_volume: u64,    // Lamports paid by referred buyers
+    pub referred_tokens: u64,    // Tokens bought by referred buyers
+    pub referred_purchases: u64, // Number of referred purchases
+    pub bump: u8,                // Bump for the PDA
+}
+
+impl Referrer {
+    pub const DISCRIMINATOR_SIZE: usize = 8;
+    pub const INIT_SPACE: usize = 32 + // wallet: Pubkey
+        2 + // share_bps: u16
+        8 + // accrued_lamports: u64
+        8 + // total_earned: u64
+        8 + // referred_volume: u64
+        8 + // referred_tokens: u64
+        8 + // referred_purchases: u64
+        1; // bump: u8
+
+    pub const MAX_SHARE_BPS: u16 = 10_000;
+
+    /// Referrer's cut of a platform fee
+    pub fn referral_fee(&self, platform_fee: u64) -> Result<u64> {
+        let cut = (platform_fee as u128) * (self.share_bps as u128) / (Self::MAX_SHARE_BPS as u128);
+        u64::try_from(cut).map_err(|_| ContractError::ArithmeticOverflow.into())
+    }
+
+    /// Record a referred purchase and the referral fee credited for it
+    pub fn record_referral(&mut self, volume: u64, tokens: u64, referral_fee: u64) -> Result<()> {
+        self.accrued_lamports = self
+            .accrued_lamports
+            .checked_add(referral_fee)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.total_earned = self
+            .total_earned
+            .checked_add(referral_fee)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.referred_volume = self
+            .referred_volume
+            .checked_add(volume)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.referred_tokens = self
+            .referred_tokens
+            .checked_add(tokens)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.referred_purchases = self
+            .referred_purchases
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+
+use crate::errors::ContractError;
+
+/// A carbon registry's signing key, registered by the platform authority.
+/// Projects can only be tokenized with an attestation signed by an oracle.
+#[account]
+pub struct RegistryOracle {
+    pub key: Pubkey,       // Ed25519 key the registry signs attestations with
+    pub name: String,      // Registry name, e.g. "Verra"
+    pub attestations: u64, // Number of attestations credits were issued under
+    pub bump: u8,          // Bump for the PDA
+}
+
+impl RegistryOracle {
+    pub const DISCRIMINATOR_SIZE: usize = 8;
+    pub const MAX_NAME_LEN: usize = 32;
+    pub const INIT_SPACE: usize = 32 + // key: Pubkey
+        4 + Self::MAX_NAME_LEN + // name: String
+        8 +  // attestations: u64
+        1; // bump: u8
+
+    /// Count an attestation credits were issued under
+    pub fn record_attestation(&mut self) -> Result<()> {
+        self.attestations = self
+            .attestations
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+/// What a registry oracle attests before credits are tokenized: the registry's
+/// project and serial range, bound to the owner and mint that will hold them.
+/// The oracle signs the borsh serialization of this struct.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct TokenizationAttestation {
+    pub registry_project_id: String, // Project ID in the registry, e.g. "VCS-1234"
+    pub serial_start: u64,           // First serial number of the credits (inclusive)
+    pub serial_end: u64,             // Last serial number of the credits (inclusive)
+    pub amount: u64,                 // Number of credits, must match the serial range
+    pub vintage_year: u16,           // Year the credits were generated
+    pub project_owner: Pubkey,       // Wallet allowed to tokenize the credits
+    pub token_mint: Pubkey,          // Mint the credits are tokenized into
+}
+
+impl TokenizationAttestation {
+    pub const MAX_REGISTRY_PROJECT_ID_LEN: usize = 32;
+
+    /// Check the attestation covers `amount` credits for this owner and mint
+    pub fn validate(&self, amount: u64, project_owner: &Pubkey, token_mint: &Pubkey) -> Result<()> {
+        require!(
+            self.registry_project_id.len() <= Self::MAX_REGISTRY_PROJECT_ID_LEN,
+            ContractError::InvalidAttestation
+        );
+        let range = self
+            .serial_end
+            .checked_sub(self.serial_start)
+            .and_then(|span| span.checked_add(1))
+            .ok_or(ContractError::InvalidAttestation)?;
+        require!(
+            range == self.amount && self.amount == amount,
+            ContractError::AttestationAmountMismatch
+        );
+        require!(
+            self.project_owner == *project_owner && self.token_mint == *token_mint,
+            ContractError::InvalidAttestation
+        );
+        Ok(())
+    }
+
+    /// The message the oracle signs
+    pub fn message(&self) -> Result<Vec<u8>> {
+        Ok(self.try_to_vec()?)
+    }
+
+    /// sha256 of the signed message, stored on the project for audit
+    pub fn hash(&self) -> Result<[u8; 32]> {
+        Ok(hash(&self.message()?).to_bytes())
+    }
+}
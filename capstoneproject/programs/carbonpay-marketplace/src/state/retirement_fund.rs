@@ -0,0 +1,489 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ContractError;
+
+/// Pooled retirement fund: depositors add lamports, the manager, or anyone once a
+/// period has elapsed, spends the balance on credits of the fund's projects and
+/// retires them in bulk. Each retirement is credited to the depositors pro rata to
+/// the part of their deposits still unspent, and spends that part pro rata too.
+///
+/// `unspent_per_lamport` is what is left of one lamport deposited at the start of
+/// the epoch, and `acc_retired_per_lamport` the grams retired for it; a depositor
+/// settles both on each deposit. An epoch ends when a retirement spends the whole
+/// balance, or leaves less than `1 / EPOCH_SCALE` of a lamport per lamport: the
+/// unspent balance then carries on at `EPOCH_SCALE` times the old figure. Each
+/// epoch's final figures are kept in its `FundEpoch`.
+#[account]
+pub struct RetirementFund {
+    pub manager: Pubkey,                // Picks the projects and retires at any time
+    pub fund_id: u64,                   // Manager-chosen id, part of the PDA seeds
+    pub projects: Vec<Pubkey>,          // Projects the credits may be bought from
+    pub max_price_per_token: u64,       // Highest price per token accepted, in lamports
+    pub period_seconds: i64,            // Time between two retirements by anyone else
+    pub next_retirement: i64,           // Earliest time anyone may retire
+    pub epoch: u64,                     // Current accounting epoch
+    pub total_unspent: u64,             // Lamports deposited and not spent yet
+    pub unspent_per_lamport: u128,      // Unspent part of a lamport deposited at the epoch start, scaled by ACC_PRECISION
+    pub acc_retired_per_lamport: u128,  // Grams retired per lamport deposited at the epoch start, scaled by ACC_PRECISION
+    pub total_deposited: u64,           // Lamports deposited so far
+    pub total_retired: u64,             // Tokens retired so far
+    pub total_spent: u64,               // Lamports spent on credits and fees so far
+    pub retirements: u64,               // Number of retirements so far
+    pub bump: u8,                       // Bump for the PDA
+}
+
+impl RetirementFund {
+    pub const DISCRIMINATOR_SIZE: usize = 8;
+    pub const MAX_PROJECTS: usize = 5;
+    pub const GRAMS_PER_TONNE: u128 = 1_000_000;
+    pub const ACC_PRECISION: u128 = 1_000_000_000_000_000_000;
+    pub const EPOCH_SCALE: u128 = 1_000_000_000;
+    pub const INIT_SPACE: usize = 32 + // manager: Pubkey
+        8 +  // fund_id: u64
+        4 + 32 * Self::MAX_PROJECTS + // projects: Vec<Pubkey>
+        8 +  // max_price_per_token: u64
+        8 +  // period_seconds: i64
+        8 +  // next_retirement: i64
+        8 +  // epoch: u64
+        8 +  // total_unspent: u64
+        16 + // unspent_per_lamport: u128
+        16 + // acc_retired_per_lamport: u128
+        8 +  // total_deposited: u64
+        8 +  // total_retired: u64
+        8 +  // total_spent: u64
+        8 +  // retirements: u64
+        1; // bump: u8
+
+    /// Check a fund's configuration
+    pub fn validate_config(projects: &[Pubkey], period_seconds: i64) -> Result<()> {
+        require!(
+            !projects.is_empty() && projects.len() <= Self::MAX_PROJECTS,
+            ContractError::InvalidRetirementFund
+        );
+        for (i, project) in projects.iter().enumerate() {
+            require!(!projects[..i].contains(project), ContractError::InvalidRetirementFund);
+        }
+        require!(period_seconds > 0, ContractError::InvalidRetirementFund);
+        Ok(())
+    }
+
+    /// Record a deposit of `deposit` lamports
+    pub fn record_deposit(&mut self, deposit: u64) -> Result<()> {
+        require!(deposit > 0, ContractError::InvalidAmount);
+        self.total_unspent = self
+            .total_unspent
+            .checked_add(deposit)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.total_deposited = self
+            .total_deposited
+            .checked_add(deposit)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Request id of the next retirement's record, kept apart from the manager's
+    /// other funds by `fund_id`
+    pub fn next_request_id(&self) -> String {
+        format!("fund-{}-{}", self.fund_id, self.retirements.saturating_add(1))
+    }
+
+    /// Record a retirement of `tokens` paid with `spent` lamports, pro rata to the
+    /// unspent balance. `epoch` is the current epoch's record; it keeps the epoch's
+    /// final figures if the retirement ends it.
+    pub fn record_retirement(&mut self, epoch: &mut FundEpoch, tokens: u64, spent: u64) -> Result<()> {
+        require!(
+            self.total_unspent > 0 && spent <= self.total_unspent,
+            ContractError::InsufficientFundBalance
+        );
+        let unspent = self.total_unspent as u128;
+        let grams = (tokens as u128)
+            .checked_mul(Self::GRAMS_PER_TONNE)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.acc_retired_per_lamport = self
+            .acc_retired_per_lamport
+            .checked_add(mul_div(grams, self.unspent_per_lamport, unspent)?)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        epoch.acc_retired_per_lamport = self.acc_retired_per_lamport;
+
+        self.total_unspent -= spent;
+        let unspent_per_lamport =
+            mul_div(self.unspent_per_lamport, self.total_unspent as u128, unspent)?;
+        if unspent_per_lamport == 0 {
+            // spent down: what is left, if anything, is dust no depositor owns
+            epoch.drained = true;
+            self.total_unspent = 0;
+            self.start_epoch(Self::ACC_PRECISION)?;
+        } else if unspent_per_lamport < Self::ACC_PRECISION / Self::EPOCH_SCALE {
+            self.start_epoch(unspent_per_lamport * Self::EPOCH_SCALE)?;
+        } else {
+            self.unspent_per_lamport = unspent_per_lamport;
+        }
+
+        self.retirements = self
+            .retirements
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.total_retired = self
+            .total_retired
+            .checked_add(tokens)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.total_spent = self
+            .total_spent
+            .checked_add(spent)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    fn start_epoch(&mut self, unspent_per_lamport: u128) -> Result<()> {
+        self.epoch = self
+            .epoch
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.unspent_per_lamport = unspent_per_lamport;
+        self.acc_retired_per_lamport = 0;
+        Ok(())
+    }
+}
+
+/// Figures of one epoch of a fund, `["fund_epoch", fund, epoch (u64 LE)]`, created
+/// by the epoch's first retirement and updated by the others. A depositor whose
+/// last deposit was in an ended epoch is settled with it, and with the next one
+/// when the epoch ended with part of the balance unspent.
+#[account]
+pub struct FundEpoch {
+    pub fund: Pubkey,                   // The retirement fund
+    pub epoch: u64,                     // Epoch number
+    pub acc_retired_per_lamport: u128,  // `fund.acc_retired_per_lamport` after the last retirement
+    pub drained: bool,                  // The epoch ended with the whole balance spent
+    pub bump: u8,                       // Bump for the PDA
+}
+
+impl FundEpoch {
+    pub const DISCRIMINATOR_SIZE: usize = 8;
+    pub const INIT_SPACE: usize = 32 + // fund: Pubkey
+        8 +  // epoch: u64
+        16 + // acc_retired_per_lamport: u128
+        1 +  // drained: bool
+        1; // bump: u8
+}
+
+/// A depositor's position in a retirement fund, `["fund_share", fund, depositor]`,
+/// settled on each deposit: `unspent` lamports and `retired_grams` as of the last
+/// deposit, and the fund's figures at that time to work out what changed since.
+#[account]
+pub struct FundShare {
+    pub fund: Pubkey,                   // The retirement fund
+    pub depositor: Pubkey,              // Owner of the position
+    pub epoch: u64,                     // Fund epoch of the last deposit
+    pub unspent: u64,                   // Lamports unspent right after the last deposit
+    pub unspent_per_lamport: u128,      // `fund.unspent_per_lamport` at the last deposit
+    pub retired_debt: u128,             // `fund.acc_retired_per_lamport` at the last deposit
+    pub deposited: u64,                 // Lamports deposited so far
+    pub retired_grams: u64,             // Grams retired for this depositor, as of the last deposit
+    pub bump: u8,                       // Bump for the PDA
+}
+
+impl FundShare {
+    pub const DISCRIMINATOR_SIZE: usize = 8;
+    pub const INIT_SPACE: usize = 32 + // fund: Pubkey
+        32 + // depositor: Pubkey
+        8 +  // epoch: u64
+        8 +  // unspent: u64
+        16 + // unspent_per_lamport: u128
+        16 + // retired_debt: u128
+        8 +  // deposited: u64
+        8 +  // retired_grams: u64
+        1; // bump: u8
+
+    /// Lamports still unspent and grams retired for this depositor up to now.
+    /// `ended` and `next` are the records of the epoch of the last deposit and of
+    /// the one after it, needed once the fund has moved past that epoch.
+    pub fn position(
+        &self,
+        fund: &RetirementFund,
+        ended: Option<&FundEpoch>,
+        next: Option<&FundEpoch>,
+    ) -> Result<(u64, u64)> {
+        let deposit = self.unspent as u128;
+        let (mut unspent, mut grams) = (0, 0);
+        if deposit > 0 && self.epoch == fund.epoch {
+            unspent = mul_div(deposit, fund.unspent_per_lamport, self.unspent_per_lamport)?;
+            grams = mul_div(
+                deposit,
+                fund.acc_retired_per_lamport - self.retired_debt,
+                self.unspent_per_lamport,
+            )?;
+        } else if deposit > 0 {
+            let ended = ended
+                .filter(|record| record.epoch == self.epoch)
+                .ok_or(ContractError::MissingFundEpoch)?;
+            grams = mul_div(
+                deposit,
+                ended.acc_retired_per_lamport - self.retired_debt,
+                self.unspent_per_lamport,
+            )?;
+            if !ended.drained {
+                // carried over at EPOCH_SCALE times the old figure
+                let carried = self
+                    .unspent_per_lamport
+                    .checked_mul(RetirementFund::EPOCH_SCALE)
+                    .ok_or(ContractError::ArithmeticOverflow)?;
+                let acc_retired_per_lamport = if self.epoch + 1 == fund.epoch {
+                    unspent = mul_div(deposit, fund.unspent_per_lamport, carried)?;
+                    fund.acc_retired_per_lamport
+                } else {
+                    // past a second epoch less than a billionth of the deposit is left
+                    next.filter(|record| record.epoch == self.epoch + 1)
+                        .ok_or(ContractError::MissingFundEpoch)?
+                        .acc_retired_per_lamport
+                };
+                grams += mul_div(deposit, acc_retired_per_lamport, carried)?;
+            }
+        }
+
+        let retired_grams = (self.retired_grams as u128)
+            .checked_add(grams)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        Ok((
+            u64::try_from(unspent).map_err(|_| ContractError::ArithmeticOverflow)?,
+            u64::try_from(retired_grams).map_err(|_| ContractError::ArithmeticOverflow)?,
+        ))
+    }
+
+    /// Settle the position so far, then add `deposit` lamports to it
+    pub fn record_deposit(
+        &mut self,
+        fund: &RetirementFund,
+        ended: Option<&FundEpoch>,
+        next: Option<&FundEpoch>,
+        deposit: u64,
+    ) -> Result<()> {
+        let (unspent, retired_grams) = self.position(fund, ended, next)?;
+        self.unspent = unspent
+            .checked_add(deposit)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.retired_grams = retired_grams;
+        self.epoch = fund.epoch;
+        self.unspent_per_lamport = fund.unspent_per_lamport;
+        self.retired_debt = fund.acc_retired_per_lamport;
+        self.deposited = self
+            .deposited
+            .checked_add(deposit)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+/// `a * b / c`, rounded down
+fn mul_div(a: u128, b: u128, c: u128) -> Result<u128> {
+    Ok(a.checked_mul(b).ok_or(ContractError::ArithmeticOverflow)? / c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fund() -> RetirementFund {
+        RetirementFund {
+            manager: Pubkey::new_unique(),
+            fund_id: 1,
+            projects: vec![Pubkey::new_unique()],
+            max_price_per_token: 10,
+            period_seconds: 86_400,
+            next_retirement: 0,
+            epoch: 0,
+            total_unspent: 0,
+            unspent_per_lamport: RetirementFund::ACC_PRECISION,
+            acc_retired_per_lamport: 0,
+            total_deposited: 0,
+            total_retired: 0,
+            total_spent: 0,
+            retirements: 0,
+            bump: 255,
+        }
+    }
+
+    fn share() -> FundShare {
+        FundShare {
+            fund: Pubkey::new_unique(),
+            depositor: Pubkey::new_unique(),
+            epoch: 0,
+            unspent: 0,
+            unspent_per_lamport: 0,
+            retired_debt: 0,
+            deposited: 0,
+            retired_grams: 0,
+            bump: 255,
+        }
+    }
+
+    /// The fund with its epoch records, indexed by epoch
+    struct Pool {
+        fund: RetirementFund,
+        epochs: Vec<FundEpoch>,
+    }
+
+    impl Pool {
+        fn new() -> Self {
+            Pool { fund: fund(), epochs: Vec::new() }
+        }
+
+        fn position(&self, position: &FundShare) -> Result<(u64, u64)> {
+            let epoch = position.epoch as usize;
+            position.position(&self.fund, self.epochs.get(epoch), self.epochs.get(epoch + 1))
+        }
+
+        fn unspent(&self, position: &FundShare) -> u64 {
+            self.position(position).unwrap().0
+        }
+
+        fn retired(&self, position: &FundShare) -> u64 {
+            self.position(position).unwrap().1
+        }
+
+        fn deposit(&mut self, position: &mut FundShare, amount: u64) {
+            let epoch = position.epoch as usize;
+            self.fund.record_deposit(amount).unwrap();
+            position
+                .record_deposit(&self.fund, self.epochs.get(epoch), self.epochs.get(epoch + 1), amount)
+                .unwrap();
+        }
+
+        fn retire(&mut self, tokens: u64, spent: u64) -> Result<()> {
+            if self.epochs.len() as u64 == self.fund.epoch {
+                self.epochs.push(FundEpoch {
+                    fund: Pubkey::default(),
+                    epoch: self.fund.epoch,
+                    acc_retired_per_lamport: 0,
+                    drained: false,
+                    bump: 255,
+                });
+            }
+            let epoch = self.epochs.last_mut().unwrap();
+            self.fund.record_retirement(epoch, tokens, spent)
+        }
+    }
+
+    const SOL: u64 = 1_000_000_000;
+
+    #[test]
+    fn retirements_are_split_by_unspent_balance() {
+        let mut pool = Pool::new();
+        let (mut alice, mut bob, mut carol) = (share(), share(), share());
+
+        pool.deposit(&mut alice, 3 * SOL);
+        pool.deposit(&mut bob, SOL);
+
+        // 4 tonnes for the whole balance: 3 to alice, 1 to bob
+        pool.retire(4, 4 * SOL).unwrap();
+        assert_eq!(pool.retired(&alice), 3_000_000);
+        assert_eq!(pool.retired(&bob), 1_000_000);
+        assert_eq!(pool.unspent(&alice), 0);
+
+        // carol's 4 SOL alone pay for the next 2 tonnes
+        pool.deposit(&mut carol, 4 * SOL);
+        pool.retire(2, 4 * SOL).unwrap();
+        assert_eq!(pool.retired(&carol), 2_000_000);
+        assert_eq!(pool.retired(&alice), 3_000_000);
+        assert_eq!(pool.retired(&bob), 1_000_000);
+        // alice got 3 of the 4 tonnes her lamports were in
+        assert_eq!(pool.fund.epoch, 2);
+        assert!(pool.epochs.iter().all(|epoch| epoch.drained));
+    }
+
+    #[test]
+    fn a_deposit_after_a_partial_spend_weighs_its_unspent_balance() {
+        let mut pool = Pool::new();
+        let (mut alice, mut bob) = (share(), share());
+
+        pool.deposit(&mut alice, SOL);
+        pool.deposit(&mut bob, SOL);
+        // 1 tonne for half the balance, half a tonne and half a SOL spent each
+        pool.retire(1, SOL).unwrap();
+        assert_eq!(pool.unspent(&bob), SOL / 2);
+
+        // alice now has 1.5 SOL unspent to bob's 0.5
+        pool.deposit(&mut alice, SOL);
+        assert_eq!(alice.retired_grams, 500_000);
+        assert_eq!(alice.unspent, 3 * SOL / 2);
+        pool.retire(4, 2 * SOL).unwrap();
+        assert_eq!(pool.retired(&alice), 500_000 + 3_000_000);
+        assert_eq!(pool.retired(&bob), 500_000 + 1_000_000);
+        assert_eq!(alice.deposited, 2 * SOL);
+    }
+
+    #[test]
+    fn stays_exact_over_many_spend_down_cycles() {
+        let mut pool = Pool::new();
+        let (mut alice, mut bob) = (share(), share());
+
+        // each cycle both deposit 1:3 and the whole balance is spent on 4 tonnes
+        for cycle in 1..=50u64 {
+            pool.deposit(&mut alice, SOL);
+            pool.deposit(&mut bob, 3 * SOL);
+            pool.retire(4, 4 * SOL).unwrap();
+
+            assert_eq!(pool.retired(&alice), cycle * 1_000_000);
+            assert_eq!(pool.retired(&bob), cycle * 3_000_000);
+        }
+        assert_eq!(pool.fund.epoch, 50);
+        assert_eq!(pool.fund.total_retired, 200);
+        assert_eq!(pool.fund.total_deposited, 200 * SOL);
+    }
+
+    #[test]
+    fn leftovers_carry_over_into_new_epochs() {
+        let mut pool = Pool::new();
+        let (mut alice, mut bob) = (share(), share());
+        pool.deposit(&mut bob, SOL);
+
+        // bob never comes back; alice deposits each cycle and 95% of the balance
+        // is spent on 1 tonne, so the fund moves through several epochs
+        let (mut bob_unspent, mut bob_grams) = (SOL as f64, 0f64);
+        for _ in 0..40 {
+            pool.deposit(&mut alice, SOL);
+            let unspent = pool.fund.total_unspent;
+            let spent = unspent - unspent / 20;
+            bob_grams += 1_000_000.0 * bob_unspent / unspent as f64;
+            bob_unspent *= (unspent - spent) as f64 / unspent as f64;
+            pool.retire(1, spent).unwrap();
+        }
+        assert!(pool.fund.epoch >= 4);
+        assert!(pool.epochs.iter().all(|epoch| !epoch.drained));
+
+        assert!((pool.retired(&bob) as f64 - bob_grams).abs() <= 1.0);
+        assert_eq!(pool.unspent(&bob), 0);
+        // nothing is lost beyond rounding
+        let total = pool.retired(&alice) + pool.retired(&bob);
+        assert!(40_000_000 - total <= 40);
+        assert!(pool.fund.total_unspent - pool.unspent(&alice) <= 1);
+    }
+
+    #[test]
+    fn stale_positions_need_their_epoch_records() {
+        let mut pool = Pool::new();
+        let mut alice = share();
+        pool.deposit(&mut alice, SOL);
+        pool.retire(1, SOL).unwrap();
+
+        assert_eq!(
+            alice.position(&pool.fund, None, None).err(),
+            Some(ContractError::MissingFundEpoch.into())
+        );
+        assert_eq!(alice.position(&pool.fund, pool.epochs.first(), None).unwrap(), (0, 1_000_000));
+    }
+
+    #[test]
+    fn rejects_empty_deposits_and_overspending() {
+        let mut pool = Pool::new();
+        assert!(pool.retire(1, 10).is_err());
+        assert_eq!(
+            pool.fund.record_deposit(0).err(),
+            Some(ContractError::InvalidAmount.into())
+        );
+        let mut alice = share();
+        pool.deposit(&mut alice, 10);
+        assert!(pool.retire(1, 11).is_err());
+        assert_eq!(alice.unspent, 10);
+    }
+}
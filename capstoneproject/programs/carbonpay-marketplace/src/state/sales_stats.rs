@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ContractError;
+
+/// Sales and offset figures of one UTC day
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct DailyStats {
+    pub day: u32,             // Days since the unix epoch, 0 for a bucket never used
+    pub purchases: u32,       // Number of purchases (subscription executions included)
+    pub tokens_sold: u64,     // Credits sold
+    pub revenue: u64,         // Lamports paid by buyers, platform fee included
+    pub fees: u64,            // Lamports of platform fee, referral cuts included
+    pub offset_requests: u32, // Number of offset requests (subscription executions included)
+    pub tokens_offset: u64,   // Credits retired
+}
+
+impl DailyStats {
+    pub const INIT_SPACE: usize = 4 + // day: u32
+        4 + // purchases: u32
+        8 + // tokens_sold: u64
+        8 + // revenue: u64
+        8 + // fees: u64
+        4 + // offset_requests: u32
+        8; // tokens_offset: u64
+}
+
+/// Ring buffer of the last `DAYS` daily figures, for a project (`["project_stats", project]`)
+/// or for the whole platform (`["platform_stats"]`). The bucket of a day is
+/// `day % DAYS`; a bucket still holding an older day is reset when it is reused.
+#[account]
+pub struct SalesStats {
+    pub scope: Pubkey,               // Project the figures are for, or the carbon_credits PDA
+    pub bump: u8,                    // The PDA bump
+    pub buckets: [DailyStats; 30],   // Daily figures, indexed by `day % DAYS`
+}
+
+impl SalesStats {
+    pub const DISCRIMINATOR_SIZE: usize = 8;
+    pub const DAYS: usize = 30;
+    pub const SECONDS_PER_DAY: i64 = 86_400;
+    pub const INIT_SPACE: usize = 32 + // scope: Pubkey
+        1 + // bump: u8
+        Self::DAYS * DailyStats::INIT_SPACE; // buckets: [DailyStats; DAYS]
+
+    /// Set up an account created by `init_if_needed`; no-op once it has a scope
+    pub fn init_if_new(&mut self, scope: Pubkey, bump: u8) {
+        if self.scope == Pubkey::default() {
+            self.scope = scope;
+            self.bump = bump;
+        }
+    }
+
+    /// Record a purchase of `amount` credits for `revenue` lamports, `fee` of them to the platform
+    pub fn record_sale(&mut self, now: i64, amount: u64, revenue: u64, fee: u64) -> Result<()> {
+        let bucket = self.bucket(now)?;
+        bucket.purchases = bucket
+            .purchases
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        bucket.tokens_sold = bucket
+            .tokens_sold
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        bucket.revenue = bucket
+            .revenue
+            .checked_add(revenue)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        bucket.fees = bucket
+            .fees
+            .checked_add(fee)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Record an offset request retiring `amount` credits
+    pub fn record_offset(&mut self, now: i64, amount: u64) -> Result<()> {
+        let bucket = self.bucket(now)?;
+        bucket.offset_requests = bucket
+            .offset_requests
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        bucket.tokens_offset = bucket
+            .tokens_offset
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// The bucket of the day containing `now`, reset if it still holds an older day
+    fn bucket(&mut self, now: i64) -> Result<&mut DailyStats> {
+        let day = u32::try_from(now.div_euclid(Self::SECONDS_PER_DAY))
+            .map_err(|_| ContractError::ArithmeticOverflow)?;
+        let bucket = &mut self.buckets[day as usize % Self::DAYS];
+        if bucket.day != day {
+            *bucket = DailyStats {
+                day,
+                ..DailyStats::default()
+            };
+        }
+        Ok(bucket)
+    }
+}
@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ContractError;
+
+/// Recurring offset: every `period_seconds`, anyone can crank the subscription to
+/// buy and retire `tokens_per_period` credits from one of the preferred projects,
+/// paid from the lamports pre-funded on this account.
+#[account]
+pub struct Subscription {
+    pub owner: Pubkey,              // Customer the credits are retired for
+    pub subscription_id: u64,       // Owner-chosen id, part of the PDA seeds
+    pub projects: Vec<Pubkey>,      // Projects the credits may be bought from
+    pub tokens_per_period: u64,     // Tokens bought and retired each period
+    pub period_seconds: i64,        // Time between two executions
+    pub max_price_per_token: u64,   // Highest price per token accepted, in lamports
+    pub crank_tip: u64,             // Lamports paid to the cranker per execution
+    pub next_execution: i64,        // Earliest time of the next execution
+    pub executions: u64,            // Number of executions so far
+    pub total_retired: u64,         // Tokens retired so far
+    pub total_spent: u64,           // Lamports spent on credits, fees and tips so far
+    pub bump: u8,                   // Bump for the PDA
+}
+
+impl Subscription {
+    pub const DISCRIMINATOR_SIZE: usize = 8;
+    pub const MAX_PROJECTS: usize = 3;
+    pub const INIT_SPACE: usize = 32 + // owner: Pubkey
+        8 +  // subscription_id: u64
+        4 + 32 * Self::MAX_PROJECTS + // projects: Vec<Pubkey>
+        8 +  // tokens_per_period: u64
+        8 +  // period_seconds: i64
+        8 +  // max_price_per_token: u64
+        8 +  // crank_tip: u64
+        8 +  // next_execution: i64
+        8 +  // executions: u64
+        8 +  // total_retired: u64
+        8 +  // total_spent: u64
+        1; // bump: u8
+
+    /// Check a subscription's configuration
+    pub fn validate_config(projects: &[Pubkey], tokens_per_period: u64, period_seconds: i64) -> Result<()> {
+        require!(
+            !projects.is_empty() && projects.len() <= Self::MAX_PROJECTS,
+            ContractError::InvalidSubscription
+        );
+        for (i, project) in projects.iter().enumerate() {
+            require!(!projects[..i].contains(project), ContractError::InvalidSubscription);
+        }
+        require!(
+            tokens_per_period > 0 && period_seconds > 0,
+            ContractError::InvalidSubscription
+        );
+        Ok(())
+    }
+
+    /// Request id of the next execution's retirement record. `sub-` keeps it apart
+    /// from the owner's other subscriptions and, unless they pick such ids, from
+    /// their checkout requests; it fits in a seed for any id and 10^7 executions.
+    pub fn next_request_id(&self) -> String {
+        format!("sub-{}-{}", self.subscription_id, self.executions.saturating_add(1))
+    }
+
+    /// Record an execution at `now` that retired `tokens` for `spent` lamports. The
+    /// next execution is the first period boundary after `now`: periods missed by a
+    /// late crank are skipped, not caught up.
+    pub fn record_execution(&mut self, now: i64, tokens: u64, spent: u64) -> Result<()> {
+        let elapsed_periods = now
+            .checked_sub(self.next_execution)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / self.period_seconds;
+        self.next_execution = elapsed_periods
+            .checked_add(1)
+            .and_then(|periods| periods.checked_mul(self.period_seconds))
+            .and_then(|delay| self.next_execution.checked_add(delay))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.executions = self
+            .executions
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.total_retired = self
+            .total_retired
+            .checked_add(tokens)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.total_spent = self
+            .total_spent
+            .checked_add(spent)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::OffsetRequest;
+
+    #[test]
+    fn request_ids_fit_in_a_seed() {
+        let subscription = Subscription {
+            owner: Pubkey::new_unique(),
+            subscription_id: u64::MAX,
+            projects: vec![Pubkey::new_unique()],
+            tokens_per_period: 1,
+            period_seconds: 100,
+            max_price_per_token: 1,
+            crank_tip: 0,
+            next_execution: 0,
+            executions: 9_999_998,
+            total_retired: 0,
+            total_spent: 0,
+            bump: 255,
+        };
+        assert_eq!(subscription.next_request_id(), "sub-18446744073709551615-9999999");
+        assert!(OffsetRequest::validate_request_id(&subscription.next_request_id()).is_ok());
+    }
+
+    #[test]
+    fn late_crank_skips_missed_periods() {
+        let mut subscription = Subscription {
+            owner: Pubkey::new_unique(),
+            subscription_id: 1,
+            projects: vec![Pubkey::new_unique()],
+            tokens_per_period: 1,
+            period_seconds: 100,
+            max_price_per_token: 1,
+            crank_tip: 0,
+            next_execution: 1_000,
+            executions: 0,
+            total_retired: 0,
+            total_spent: 0,
+            bump: 255,
+        };
+
+        // on time: the next one is a period later
+        subscription.record_execution(1_000, 1, 10).unwrap();
+        assert_eq!(subscription.next_execution, 1_100);
+
+        // 3.5 periods late: one execution, the schedule keeps its phase
+        subscription.record_execution(1_450, 1, 10).unwrap();
+        assert_eq!(subscription.next_execution, 1_500);
+        assert_eq!(subscription.executions, 2);
+        assert_eq!(subscription.total_retired, 2);
+    }
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ContractError;
+
+/// Set of platform verifiers and the number of matching votes needed to approve
+/// or reject a project listing or an offset request.
+/// Managed by `CarbonCredits.authority`.
+#[account]
+pub struct VerifierRegistry {
+    pub verifiers: Vec<Pubkey>, // Keys allowed to vote on reviews
+    pub threshold: u8,          // Votes required for a decision (M of N)
+    pub bump: u8,               // The PDA bump
+}
+
+impl VerifierRegistry {
+    pub const DISCRIMINATOR_SIZE: usize = 8;
+    pub const MAX_VERIFIERS: usize = 10;
+    pub const INIT_SPACE: usize = 4 + 32 * Self::MAX_VERIFIERS + // verifiers: Vec<Pubkey>
+        1 + // threshold: u8
+        1; // bump: u8
+
+    pub fn is_verifier(&self, key: &Pubkey) -> bool {
+        self.verifiers.contains(key)
+    }
+
+    pub fn add_verifier(&mut self, verifier: Pubkey) -> Result<()> {
+        require!(
+            !self.is_verifier(&verifier),
+            ContractError::VerifierAlreadyRegistered
+        );
+        require!(
+            self.verifiers.len() < Self::MAX_VERIFIERS,
+            ContractError::VerifierRegistryFull
+        );
+        self.verifiers.push(verifier);
+        Ok(())
+    }
+
+    pub fn remove_verifier(&mut self, verifier: Pubkey) -> Result<()> {
+        let index = self
+            .verifiers
+            .iter()
+            .position(|v| *v == verifier)
+            .ok_or(ContractError::VerifierNotRegistered)?;
+        self.verifiers.remove(index);
+        // The quorum must stay reachable with the remaining verifiers
+        require!(
+            self.threshold as usize <= self.verifiers.len(),
+            ContractError::InvalidThreshold
+        );
+        Ok(())
+    }
+
+    pub fn set_threshold(&mut self, threshold: u8) -> Result<()> {
+        require!(
+            threshold > 0 && threshold as usize <= self.verifiers.len(),
+            ContractError::InvalidThreshold
+        );
+        self.threshold = threshold;
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ContractError;
+use crate::state::VerifierRegistry;
+
+/// Individual verifier votes on a single review target (a Project or an OffsetRequest).
+/// The decision takes effect once either side reaches the registry threshold.
+#[account]
+pub struct VoteRecord {
+    pub target: Pubkey,          // The Project or OffsetRequest being reviewed
+    pub approvals: Vec<Pubkey>,  // Verifiers who voted to approve
+    pub rejections: Vec<Pubkey>, // Verifiers who voted to reject
+    pub resolved: bool,          // Whether the quorum has been reached
+    pub bump: u8,                // The PDA bump
+}
+
+impl VoteRecord {
+    pub const DISCRIMINATOR_SIZE: usize = 8;
+    pub const INIT_SPACE: usize = 32 + // target: Pubkey
+        4 + 32 * VerifierRegistry::MAX_VERIFIERS + // approvals: Vec<Pubkey>
+        4 + 32 * VerifierRegistry::MAX_VERIFIERS + // rejections: Vec<Pubkey>
+        1 + // resolved: bool
+        1; // bump: u8
+
+    /// Record a verifier's vote. Returns `Some(true)` once approvals reach the quorum,
+    /// `Some(false)` once rejections do, and `None` while the vote is still open.
+    /// Votes from keys that have since left the registry are dropped, which keeps
+    /// both lists within `MAX_VERIFIERS` however often the registry changes.
+    pub fn cast(
+        &mut self,
+        registry: &VerifierRegistry,
+        voter: Pubkey,
+        approve: bool,
+    ) -> Result<Option<bool>> {
+        require!(!self.resolved, ContractError::VoteAlreadyResolved);
+        self.approvals.retain(|v| registry.is_verifier(v));
+        self.rejections.retain(|v| registry.is_verifier(v));
+        require!(
+            !self.approvals.contains(&voter) && !self.rejections.contains(&voter),
+            ContractError::AlreadyVoted
+        );
+
+        if approve {
+            self.approvals.push(voter);
+        } else {
+            self.rejections.push(voter);
+        }
+
+        let threshold = registry.threshold as usize;
+        let outcome = if self.approvals.len() >= threshold {
+            Some(true)
+        } else if self.rejections.len() >= threshold {
+            Some(false)
+        } else {
+            None
+        };
+        self.resolved = outcome.is_some();
+        Ok(outcome)
+    }
+
+    /// Current registry members who voted on the winning side
+    pub fn deciding_voters(&self, registry: &VerifierRegistry, approved: bool) -> Vec<Pubkey> {
+        let votes = if approved { &self.approvals } else { &self.rejections };
+        votes
+            .iter()
+            .filter(|v| registry.is_verifier(v))
+            .copied()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry(verifiers: &[Pubkey], threshold: u8) -> VerifierRegistry {
+        VerifierRegistry {
+            verifiers: verifiers.to_vec(),
+            threshold,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn votes_of_removed_verifiers_are_dropped() {
+        let mut record = VoteRecord {
+            target: Pubkey::new_unique(),
+            approvals: Vec::new(),
+            rejections: Vec::new(),
+            resolved: false,
+            bump: 255,
+        };
+
+        // the whole registry is replaced many times, each one voting to approve
+        // before leaving, without reaching a 2-of-N quorum
+        for _ in 0..3 * VerifierRegistry::MAX_VERIFIERS {
+            let voter = Pubkey::new_unique();
+            let outcome = record
+                .cast(&registry(&[voter, Pubkey::new_unique()], 2), voter, true)
+                .unwrap();
+            assert_eq!(outcome, None);
+            assert!(record.approvals.len() <= VerifierRegistry::MAX_VERIFIERS);
+        }
+        assert_eq!(record.approvals.len(), 1);
+
+        // current members still reach the quorum
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let current = registry(&[a, b], 2);
+        assert_eq!(record.cast(&current, a, false).unwrap(), None);
+        assert_eq!(record.cast(&current, b, false).unwrap(), Some(false));
+        assert_eq!(record.deciding_voters(&current, false), vec![a, b]);
+    }
+}
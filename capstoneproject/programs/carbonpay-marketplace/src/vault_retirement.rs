@@ -0,0 +1,217 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token::{burn, Burn, Mint, Token, TokenAccount};
+
+use crate::errors::ContractError;
+use crate::events::OffsetRequested;
+use crate::migration::{ACCOUNT_VERSION, RESERVED_SPACE};
+use crate::oracle::quote_in_lamports;
+use crate::state::{CarbonCredits, OffsetRequest, Project, RequestStatus, RetirementDetails, SalesStats};
+
+/// Where the lamports of a direct retirement come from
+pub enum Payer<'a, 'info> {
+    /// A signing wallet, paying by system transfer
+    Wallet {
+        account: &'a AccountInfo<'info>,
+        system_program: &'a AccountInfo<'info>,
+    },
+    /// A program-owned balance (subscription, pool, fund), debited directly
+    Balance(&'a AccountInfo<'info>),
+}
+
+impl<'info> Payer<'_, 'info> {
+    /// Move `lamports` from the payer to `to`
+    pub fn pay(&self, to: &AccountInfo<'info>, lamports: u64) -> Result<()> {
+        match self {
+            Payer::Wallet { account, system_program } => {
+                if lamports == 0 {
+                    return Ok(());
+                }
+                transfer(
+                    CpiContext::new(
+                        (*system_program).clone(),
+                        Transfer {
+                            from: (*account).clone(),
+                            to: to.clone(),
+                        },
+                    ),
+                    lamports,
+                )
+            }
+            Payer::Balance(account) => {
+                account.sub_lamports(lamports)?;
+                to.add_lamports(lamports)?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Lamports a program-owned account can spend while staying rent exempt
+pub fn spendable_lamports(info: &AccountInfo) -> Result<u64> {
+    let reserve = Rent::get()?.minimum_balance(info.data_len());
+    Ok(info.lamports().saturating_sub(reserve))
+}
+
+/// The accounts shared by every instruction that buys credits of a project's own
+/// tranche and burns them straight from the vault: checkout, subscriptions,
+/// offset pools and retirement funds.
+pub struct VaultRetirement<'a, 'info> {
+    pub project: &'a mut Account<'info, Project>,
+    pub token_mint: &'a Account<'info, Mint>,
+    pub vault: &'a Account<'info, TokenAccount>,
+    pub carbon_credits: &'a mut Account<'info, CarbonCredits>,
+    pub price_feed: Option<&'a AccountInfo<'info>>,
+    pub project_stats: &'a mut Account<'info, SalesStats>,
+    pub project_stats_bump: u8,
+    pub platform_stats: &'a mut Account<'info, SalesStats>,
+    pub platform_stats_bump: u8,
+    pub token_program: &'a Program<'info, Token>,
+}
+
+impl<'a, 'info> VaultRetirement<'a, 'info> {
+    /// Lamports due for `amount` credits in the public sale and the platform fee
+    /// out of them. Each caller checks the total against its own price limit.
+    pub fn quote(&self, amount: u64, now: i64) -> Result<(u64, u64)> {
+        require!(amount > 0, ContractError::InvalidAmount);
+        require!(!self.project.is_sale_expired(now), ContractError::CreditsExpired);
+        // direct retirements only buy in the public sale: presales need an allowlist
+        // proof and a per-wallet allocation, use purchase_carbon_credits
+        if let Some(presale) = self.project.presale {
+            require!(now >= presale.start_time, ContractError::PresaleNotStarted);
+            require!(now >= presale.end_time, ContractError::PresaleActive);
+        }
+        require!(
+            self.project.remaining_amount >= amount && self.vault.amount >= amount,
+            ContractError::InsufficientTokens
+        );
+
+        let price = amount
+            .checked_mul(self.project.price_per_token)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        let (total, _, _) = quote_in_lamports(
+            self.carbon_credits,
+            self.project.price_currency,
+            price,
+            self.price_feed,
+        )?;
+        let fee = total
+            .checked_mul(self.project.carbon_pay_fee)
+            .ok_or(ContractError::ArithmeticOverflow)?
+            / 10_000;
+        Ok((total, fee))
+    }
+
+    /// Pay a quoted `total` to the beneficiaries (passed in the same order as
+    /// `project.beneficiaries`) and the platform fee, burn the credits from the
+    /// vault and record the sale and the offset. The offset is recorded on the
+    /// project with its request, see `record_request`.
+    pub fn settle(
+        &mut self,
+        payer: Payer<'_, 'info>,
+        amount: u64,
+        total: u64,
+        fee: u64,
+        beneficiary_accounts: &[AccountInfo<'info>],
+        now: i64,
+    ) -> Result<()> {
+        // 1) pay the beneficiaries and the platform fee
+        require!(
+            beneficiary_accounts.len() == self.project.beneficiaries.len(),
+            ContractError::BeneficiaryAccountMismatch
+        );
+        let to_owner = total.checked_sub(fee).ok_or(ContractError::ArithmeticOverflow)?;
+        let parts = self.project.split_proceeds(to_owner)?;
+        for ((share, account), part) in self.project.beneficiaries.iter().zip(beneficiary_accounts).zip(parts) {
+            require!(
+                account.key() == share.recipient && account.is_writable,
+                ContractError::BeneficiaryAccountMismatch
+            );
+            payer.pay(account, part)?;
+        }
+        payer.pay(&self.carbon_credits.to_account_info(), fee)?;
+
+        // 2) retire the credits straight from the vault
+        burn(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Burn {
+                    mint: self.token_mint.to_account_info(),
+                    from: self.vault.to_account_info(),
+                    authority: self.carbon_credits.to_account_info(),
+                },
+                &[&[b"carbon_credits", &[self.carbon_credits.bump]]],
+            ),
+            amount,
+        )?;
+
+        // 3) update on-chain state and the daily figures
+        self.project.record_purchase(amount)?;
+        self.carbon_credits.record_offset(amount)?;
+        self.carbon_credits.add_fees(fee)?;
+        self.project_stats.init_if_new(self.project.key(), self.project_stats_bump);
+        self.project_stats.record_sale(now, amount, total, fee)?;
+        self.project_stats.record_offset(now, amount)?;
+        self.platform_stats.init_if_new(self.carbon_credits.key(), self.platform_stats_bump);
+        self.platform_stats.record_sale(now, amount, total, fee)?;
+        self.platform_stats.record_offset(now, amount)?;
+        Ok(())
+    }
+
+    /// Record settled credits as a pending offset request with no purchase
+    /// (`["offset_request", offset_requester, Pubkey::default(), request_id]`),
+    /// reviewed, expired and closed like any other, and count it on the project.
+    pub fn record_request(
+        &mut self,
+        offset_request: &mut Account<'info, OffsetRequest>,
+        offset_requester: Pubkey,
+        retirement: RetirementDetails,
+        request_id: String,
+        request_bump: u8,
+        amount: u64,
+        now: i64,
+    ) -> Result<()> {
+        self.project.record_offset(amount)?;
+
+        // reviewed by the verifiers, with a deadline if the platform sets one
+        let review_deadline = match self.carbon_credits.offset_review_period {
+            0 => 0,
+            period => now.checked_add(period).ok_or(ContractError::ArithmeticOverflow)?,
+        };
+        offset_request.set_inner(OffsetRequest {
+            offset_requester,
+            retirement: retirement.clone(),
+            purchase: Pubkey::default(),
+            project: self.project.key(),
+            amount,
+            request_id: request_id.clone(),
+            status: RequestStatus::Pending,
+            request_date: now,
+            processed_date: 0,
+            request_bump,
+            processors: Vec::new(),
+            vintage: Pubkey::default(),
+            review_deadline,
+            expiry_action: None,
+            version: ACCOUNT_VERSION,
+            reserved: [0; RESERVED_SPACE],
+        });
+
+        emit!(OffsetRequested {
+            offset_request: offset_request.key(),
+            offset_requester,
+            beneficiary: retirement.beneficiary,
+            beneficiary_name: retirement.beneficiary_name,
+            reason: retirement.reason,
+            reporting_period_start: retirement.reporting_period_start,
+            reporting_period_end: retirement.reporting_period_end,
+            purchase: Pubkey::default(),
+            project: self.project.key(),
+            vintage: Pubkey::default(),
+            amount,
+            request_id,
+            request_date: now,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+import { BN, Program } from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { CarbonPay } from "../target/types/carbon_pay";
+
+// Read helpers for pooled retirement funds (`RetirementFund`, `FundEpoch` and
+// `FundShare` accounts): a depositor's unspent balance and pro-rata share of the
+// tonnes the fund retired.
+
+/** Factor the unspent figure is scaled up by when an epoch ends part spent (`RetirementFund::EPOCH_SCALE`) */
+const EPOCH_SCALE = new BN(1_000_000_000);
+
+/** Grams per tonne, the unit retired tonnage is tracked in */
+export const GRAMS_PER_TONNE = 1_000_000;
+
+/** Fund account, `["retirement_fund", manager, fundId (u64 LE)]` */
+export const findRetirementFundPda = (
+  programId: PublicKey,
+  manager: PublicKey,
+  fundId: BN
+) =>
+  PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("retirement_fund"),
+      manager.toBuffer(),
+      fundId.toArrayLike(Buffer, "le", 8),
+    ],
+    programId
+  )[0];
+
+/** Figures of one epoch of a fund, `["fund_epoch", fund, epoch (u64 LE)]` */
+export const findFundEpochPda = (
+  programId: PublicKey,
+  fund: PublicKey,
+  epoch: BN
+) =>
+  PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("fund_epoch"),
+      fund.toBuffer(),
+      epoch.toArrayLike(Buffer, "le", 8),
+    ],
+    programId
+  )[0];
+
+/** A depositor's position in a fund, `["fund_share", fund, depositor]` */
+export const findFundSharePda = (
+  programId: PublicKey,
+  fund: PublicKey,
+  depositor: PublicKey
+) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("fund_share"), fund.toBuffer(), depositor.toBuffer()],
+    programId
+  )[0];
+
+export type DepositorRetirement = {
+  deposited: BN; // lamports
+  unspent: BN; // lamports not spent on retirements yet
+  totalUnspent: BN;
+  retiredGrams: BN;
+  retiredTonnes: number;
+};
+
+/**
+ * Tonnes retired by a fund on behalf of one depositor so far and lamports of
+ * theirs still unspent, the same figures as `FundShare::position` on chain. All
+ * zero for someone who never deposited.
+ */
+export async function fetchDepositorRetirement(
+  program: Program<CarbonPay>,
+  fund: PublicKey,
+  depositor: PublicKey
+): Promise<DepositorRetirement> {
+  const fundAcc = await program.account.retirementFund.fetch(fund);
+  const share = await program.account.fundShare.fetchNullable(
+    findFundSharePda(program.programId, fund, depositor)
+  );
+  if (!share) {
+    return {
+      deposited: new BN(0),
+      unspent: new BN(0),
+      totalUnspent: fundAcc.totalUnspent,
+      retiredGrams: new BN(0),
+      retiredTonnes: 0,
+    };
+  }
+
+  // settled at the last deposit, plus what the fund did since
+  const deposit = share.unspent;
+  let unspent = new BN(0);
+  let grams = new BN(0);
+  const fetchEpoch = (epoch: BN) =>
+    program.account.fundEpoch.fetch(
+      findFundEpochPda(program.programId, fund, epoch)
+    );
+  if (deposit.gtn(0) && share.epoch.eq(fundAcc.epoch)) {
+    unspent = deposit
+      .mul(fundAcc.unspentPerLamport)
+      .div(share.unspentPerLamport);
+    grams = deposit
+      .mul(fundAcc.accRetiredPerLamport.sub(share.retiredDebt))
+      .div(share.unspentPerLamport);
+  } else if (deposit.gtn(0)) {
+    const ended = await fetchEpoch(share.epoch);
+    grams = deposit
+      .mul(ended.accRetiredPerLamport.sub(share.retiredDebt))
+      .div(share.unspentPerLamport);
+    if (!ended.drained) {
+      // carried over into the next epoch at EPOCH_SCALE times the old figure
+      const carried = share.unspentPerLamport.mul(EPOCH_SCALE);
+      let acc: BN;
+      if (share.epoch.addn(1).eq(fundAcc.epoch)) {
+        unspent = deposit.mul(fundAcc.unspentPerLamport).div(carried);
+        acc = fundAcc.accRetiredPerLamport;
+      } else {
+        acc = (await fetchEpoch(share.epoch.addn(1))).accRetiredPerLamport;
+      }
+      grams = grams.add(deposit.mul(acc).div(carried));
+    }
+  }
+  const retiredGrams = share.retiredGrams.add(grams);
+  return {
+    deposited: share.deposited,
+    unspent,
+    totalUnspent: fundAcc.totalUnspent,
+    retiredGrams,
+    retiredTonnes: retiredGrams.toNumber() / GRAMS_PER_TONNE,
+  };
+}
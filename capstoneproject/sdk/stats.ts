@@ -0,0 +1,93 @@
+import { BN, Program } from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { CarbonPay } from "../target/types/carbon_pay";
+
+// Read helpers for the on-chain daily sales and offset statistics
+// (`SalesStats` accounts), so dashboards need no indexer.
+
+/** Number of daily buckets kept on chain (`SalesStats::DAYS`) */
+export const STATS_DAYS = 30;
+
+const SECONDS_PER_DAY = 86_400;
+
+export type DailyStats = {
+  day: number; // days since the unix epoch
+  date: Date; // start of the day, UTC
+  purchases: number;
+  tokensSold: BN;
+  revenue: BN; // lamports paid by buyers, platform fee included
+  fees: BN; // lamports of platform fee
+  offsetRequests: number;
+  tokensOffset: BN;
+};
+
+/** Stats account of a project, `["project_stats", project]` */
+export const findProjectStatsPda = (programId: PublicKey, project: PublicKey) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("project_stats"), project.toBuffer()],
+    programId
+  )[0];
+
+/** Platform-wide stats account, `["platform_stats"]` */
+export const findPlatformStatsPda = (programId: PublicKey) =>
+  PublicKey.findProgramAddressSync([Buffer.from("platform_stats")], programId)[0];
+
+const emptyDay = (day: number): DailyStats => ({
+  day,
+  date: new Date(day * SECONDS_PER_DAY * 1000),
+  purchases: 0,
+  tokensSold: new BN(0),
+  revenue: new BN(0),
+  fees: new BN(0),
+  offsetRequests: 0,
+  tokensOffset: new BN(0),
+});
+
+/**
+ * Daily figures of a stats account for the `days` days up to the one containing
+ * `now` (unix seconds, defaults to the local clock), oldest first. Days without
+ * activity are zero, as is everything for a stats account not created yet.
+ */
+export async function fetchDailyStats(
+  program: Program<CarbonPay>,
+  stats: PublicKey,
+  opts: { days?: number; now?: number } = {}
+): Promise<DailyStats[]> {
+  const days = Math.min(opts.days ?? STATS_DAYS, STATS_DAYS);
+  const now = opts.now ?? Math.floor(Date.now() / 1000);
+  const today = Math.floor(now / SECONDS_PER_DAY);
+
+  // A bucket is reused for a newer day, so each day is found at most once
+  const account = await program.account.salesStats.fetchNullable(stats);
+  const byDay = new Map<number, DailyStats>();
+  for (const bucket of account?.buckets ?? []) {
+    byDay.set(bucket.day, { ...bucket, date: emptyDay(bucket.day).date });
+  }
+
+  return Array.from({ length: days }, (_, i) => {
+    const day = today - days + 1 + i;
+    return byDay.get(day) ?? emptyDay(day);
+  });
+}
+
+/** Totals over a range of days, e.g. the last 7 entries of `fetchDailyStats` */
+export function sumDailyStats(days: DailyStats[]) {
+  return days.reduce(
+    (total, day) => ({
+      purchases: total.purchases + day.purchases,
+      tokensSold: total.tokensSold.add(day.tokensSold),
+      revenue: total.revenue.add(day.revenue),
+      fees: total.fees.add(day.fees),
+      offsetRequests: total.offsetRequests + day.offsetRequests,
+      tokensOffset: total.tokensOffset.add(day.tokensOffset),
+    }),
+    {
+      purchases: 0,
+      tokensSold: new BN(0),
+      revenue: new BN(0),
+      fees: new BN(0),
+      offsetRequests: 0,
+      tokensOffset: new BN(0),
+    }
+  );
+}
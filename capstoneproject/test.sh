@@ -8,8 +8,14 @@ if [ ! -f mpl_token_metadata.so ]; then
     curl -L -o mpl_token_metadata.so https://github.com/metaplex-foundation/mpl-token-metadata/releases/download/v1.13.3/mpl_token_metadata.so
 fi
 
-# Start validator with Metaplex program on a different port
-solana-test-validator --bpf-program metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s mpl_token_metadata.so --rpc-port 8900 &
+# Start validator with Metaplex program, the mock SOL/USD price account and the
+# project and purchase fixtures from the first deployment on a different port
+solana-test-validator --bpf-program metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s mpl_token_metadata.so \
+    --account 86aRPnwsRtVspBDPWLeSzusD8cDdFVJh5DzA9prYc1k2 tests/fixtures/mock_sol_usd_price.json \
+    --account AS5UgQLj7ZNvJnd7Mijd1YTwKpr8VsQtZ9K8W9Jrv8J6 tests/fixtures/legacy_purchase.json \
+    --account 7t2km3GdufVm2XThQZviRqERSnSPRkUNhE3D7T6ep9ni tests/fixtures/legacy_project.json \
+    --account DVofn9sWMZDnpnZgEhswCvzHbNnStJAqR5QKHmrrxDQj tests/fixtures/legacy_project_purchase.json \
+    --rpc-port 8900 &
 
 # Wait for validator to start
 sleep 5
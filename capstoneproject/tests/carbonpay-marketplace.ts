@@ -1,7 +1,9 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN } from "@coral-xyz/anchor";
 import { CarbonPay } from "../target/types/carbon_pay";
+import { CarbonNeutralTransfer } from "../target/types/carbon_neutral_transfer";
 import assert from "assert";
+import { createHash } from "crypto";
 import {
   Keypair,
   PublicKey,
@@ -9,15 +11,33 @@ import {
   SYSVAR_RENT_PUBKEY,
   Connection,
   Transaction,
+  Ed25519Program,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
 } from "@solana/web3.js";
 import {
   createMint,
   getMint,
   getAssociatedTokenAddress,
   createAssociatedTokenAccountInstruction,
+  createTransferInstruction,
+  createAssociatedTokenAccount,
+  mintTo,
+  setAuthority,
+  AuthorityType,
   TOKEN_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
+import {
+  fetchDailyStats,
+  findPlatformStatsPda,
+  findProjectStatsPda,
+  sumDailyStats,
+} from "../sdk/stats";
+import {
+  fetchDepositorRetirement,
+  findFundEpochPda,
+  findRetirementFundPda,
+} from "../sdk/retirement_fund";
 
 describe("CarbonPay Program Test Suite", () => {
   const provider = anchor.AnchorProvider.env();
@@ -28,6 +48,10 @@ describe("CarbonPay Program Test Suite", () => {
   // CarbonCredits PDA and bump
   let carbonCreditsPda: PublicKey;
   let carbonCreditsBump: number;
+  let verifierRegistryPda: PublicKey;
+  let platformCollectionMint: PublicKey;
+  let registryOracle: Keypair;
+  let registryOraclePda: PublicKey;
 
   // Metadata program constant
   const METADATA_PROGRAM_ID = new PublicKey(
@@ -48,15 +72,328 @@ describe("CarbonPay Program Test Suite", () => {
   const PROJECT_AMOUNT = 100;
   const PRICE_PER_TOKEN = 10_000_000; // 0.01 SOL
   const CARBON_PAY_FEE = 500; // 5%
+  const ACCOUNT_VERSION = 3; // layout version of new and migrated accounts
   const PROJECT_URI = "https://uri.test/1";
   const PROJECT_NAME = "MyProject";
   const PROJECT_SYMBOL = "MPRJ";
+  const PROJECT_DOCUMENT_HASH = Array.from(
+    createHash("sha256").update("project-design-document-v1").digest()
+  );
+
+  // Mock Pyth SOL/USD price account loaded into the local validator ($150.00)
+  const MOCK_SOL_USD_PRICE_FEED = new PublicKey(
+    "86aRPnwsRtVspBDPWLeSzusD8cDdFVJh5DzA9prYc1k2"
+  );
+
+  // Purchase loaded into the local validator as the first deployment wrote it
+  // (buyer 57azdtRR73bxDBxbBNr6YUTh9uzKC1tHG3fr7X3tRG1r, 10 credits, 4 remaining)
+  const LEGACY_PURCHASE = new PublicKey(
+    "AS5UgQLj7ZNvJnd7Mijd1YTwKpr8VsQtZ9K8W9Jrv8J6"
+  );
+
+  // Project and one of its purchases loaded into the local validator as the first
+  // deployment wrote them. Their keys come from these labels; the test recreates
+  // the mints, tokens and project NFT metadata the first deployment had made.
+  const LEGACY_PROJECT = new PublicKey(
+    "7t2km3GdufVm2XThQZviRqERSnSPRkUNhE3D7T6ep9ni"
+  );
+  const LEGACY_PROJECT_PURCHASE = new PublicKey(
+    "DVofn9sWMZDnpnZgEhswCvzHbNnStJAqR5QKHmrrxDQj"
+  );
+  const legacyKeypair = (label: string) =>
+    Keypair.fromSeed(createHash("sha256").update(label).digest());
+
+  const U64_MAX = new BN("18446744073709551615");
+
+  const findMetadataPda = (mint: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("metadata"), METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+      METADATA_PROGRAM_ID
+    )[0];
+
+  const findMasterEditionPda = (mint: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("metadata"),
+        METADATA_PROGRAM_ID.toBuffer(),
+        mint.toBuffer(),
+        Buffer.from("edition"),
+      ],
+      METADATA_PROGRAM_ID
+    )[0];
+
+  const u64Le = (n: number | BN) => new BN(n).toArrayLike(Buffer, "le", 8);
+
+  // Purchase NFT mints are numbered by the project's purchase count
+  const findPurchaseNftMint = (project: PublicKey, purchaseCount: number | BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("purchase_nft"), project.toBuffer(), u64Le(purchaseCount)],
+      program.programId
+    )[0];
+
+  // Remaining-balance NFT mints are numbered by the purchase's offset count
+  const findOffsetNftMint = (purchase: PublicKey, offsetCount: number | BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("offset_nft"), purchase.toBuffer(), u64Le(offsetCount)],
+      program.programId
+    )[0];
+
+  // Records of retirements settled without a purchase (checkout, subscriptions,
+  // offset pools and retirement funds)
+  const findRetirementRequest = (requester: PublicKey, requestId: string) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("offset_request"),
+        requester.toBuffer(),
+        PublicKey.default.toBuffer(),
+        Buffer.from(requestId),
+      ],
+      program.programId
+    )[0];
+
+  // Borsh-encodes a TokenizationAttestation, the message the registry oracle signs
+  const encodeAttestation = (a: {
+    registryProjectId: string;
+    serialStart: BN;
+    serialEnd: BN;
+    amount: BN;
+    vintageYear: number;
+    projectOwner: PublicKey;
+    tokenMint: PublicKey;
+  }) => {
+    const id = Buffer.from(a.registryProjectId);
+    const len = Buffer.alloc(4);
+    len.writeUInt32LE(id.length);
+    const year = Buffer.alloc(2);
+    year.writeUInt16LE(a.vintageYear);
+    return Buffer.concat([
+      len,
+      id,
+      a.serialStart.toArrayLike(Buffer, "le", 8),
+      a.serialEnd.toArrayLike(Buffer, "le", 8),
+      a.amount.toArrayLike(Buffer, "le", 8),
+      year,
+      a.projectOwner.toBuffer(),
+      a.tokenMint.toBuffer(),
+    ]);
+  };
+
+  // Registry attestation for `amount` credits in `tokenMint`, and the Ed25519
+  // instruction that must precede initializeProject, issueCredits and
+  // addProjectVintage
+  let nextSerial = 1;
+  const attest = (
+    owner: PublicKey,
+    tokenMint: PublicKey,
+    amount: number,
+    signer: Keypair = registryOracle,
+    vintageYear = 2024
+  ) => {
+    const attestation = {
+      registryProjectId: "VCS-1234",
+      serialStart: new BN(nextSerial),
+      serialEnd: new BN(nextSerial + amount - 1),
+      amount: new BN(amount),
+      vintageYear,
+      projectOwner: owner,
+      tokenMint,
+    };
+    nextSerial += amount;
+    const verifyIx = Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: signer.secretKey,
+      message: encodeAttestation(attestation),
+    });
+    return { attestation, verifyIx };
+  };
+
+  const airdrop = async (to: PublicKey, sol: number) =>
+    connection.confirmTransaction(
+      await connection.requestAirdrop(to, sol * anchor.web3.LAMPORTS_PER_SOL)
+    );
+
+  // Creates a project for `owner` and has the admin verifier approve it
+  // (assumes a 1-of-N verifier threshold)
+  async function createApprovedProject(
+    owner: Keypair,
+    opts: {
+      amount: number;
+      price: number;
+      priceCurrency: any;
+      beneficiaries?: { recipient: PublicKey; shareBps: number }[];
+    }
+  ) {
+    const nftMint = await createMint(
+      connection,
+      owner,
+      owner.publicKey,
+      owner.publicKey,
+      0
+    );
+    const tokenMint = await createMint(
+      connection,
+      owner,
+      owner.publicKey,
+      owner.publicKey,
+      0
+    );
+    const ownerNftAccount = await getAssociatedTokenAddress(
+      nftMint,
+      owner.publicKey
+    );
+    const vault = await getAssociatedTokenAddress(
+      tokenMint,
+      carbonCreditsPda,
+      true
+    );
+    const [project] = PublicKey.findProgramAddressSync(
+      [Buffer.from("project"), owner.publicKey.toBuffer(), nftMint.toBuffer()],
+      program.programId
+    );
+    const { attestation, verifyIx } = attest(
+      owner.publicKey,
+      tokenMint,
+      opts.amount
+    );
+    await program.methods
+      .initializeProject(
+        new BN(opts.amount),
+        new BN(opts.price),
+        new BN(CARBON_PAY_FEE),
+        PROJECT_URI,
+        PROJECT_NAME,
+        PROJECT_SYMBOL,
+        PROJECT_DOCUMENT_HASH,
+        opts.priceCurrency,
+        opts.beneficiaries ?? [],
+        attestation
+      )
+      .accountsPartial({
+        projectOwner: owner.publicKey,
+        project,
+        nftMint,
+        tokenMint,
+        projectOwnerNftAccount: ownerNftAccount,
+        vault,
+        carbonCredits: carbonCreditsPda,
+        metadata: findMetadataPda(nftMint),
+        masterEdition: findMasterEditionPda(nftMint),
+        platformCollectionMint,
+        platformCollectionMetadata: findMetadataPda(platformCollectionMint),
+        platformCollectionMasterEdition: findMasterEditionPda(
+          platformCollectionMint
+        ),
+        registryOracle: registryOraclePda,
+        instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+      })
+      .preInstructions([verifyIx])
+      .signers([owner])
+      .rpc();
+
+    await program.methods
+      .approveProject(PROJECT_DOCUMENT_HASH)
+      .accountsPartial({
+        verifier: provider.wallet.publicKey,
+        project,
+        verifierRegistry: verifierRegistryPda,
+      })
+      .rpc();
+
+    return { owner, project, nftMint, tokenMint, ownerNftAccount, vault };
+  }
+
+  type TestProject = Awaited<ReturnType<typeof createApprovedProject>>;
+
+  // Buys `amount` credits from the project's own tranche or from `opts.vintage`;
+  // the program creates the purchase NFT mint and the buyer's ATAs
+  async function purchaseCredits(
+    buyer: Keypair,
+    project: TestProject,
+    amount: number,
+    opts: {
+      priceFeed?: PublicKey | null;
+      maxTotalLamports?: BN;
+      expectedFeeBps?: number;
+      vintage?: { vintage: PublicKey; tokenMint: PublicKey; vault: PublicKey };
+      allowlistProof?: number[][];
+      buyerAllocation?: PublicKey;
+      referrer?: PublicKey;
+    } = {}
+  ) {
+    const tokenMint = opts.vintage?.tokenMint ?? project.tokenMint;
+    const { beneficiaries, purchaseCount } = await program.account.project.fetch(
+      project.project
+    );
+    const nftMint = findPurchaseNftMint(project.project, purchaseCount);
+    const nftAccount = await getAssociatedTokenAddress(
+      nftMint,
+      buyer.publicKey
+    );
+    const tokenAccount = await getAssociatedTokenAddress(
+      tokenMint,
+      buyer.publicKey
+    );
+
+    const [purchase] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("purchase"),
+        buyer.publicKey.toBuffer(),
+        project.project.toBuffer(),
+        nftMint.toBuffer(),
+      ],
+      program.programId
+    );
+    await program.methods
+      .purchaseCarbonCredits(
+        new BN(amount),
+        opts.maxTotalLamports ?? U64_MAX,
+        new BN(opts.expectedFeeBps ?? CARBON_PAY_FEE),
+        opts.allowlistProof ?? []
+      )
+      .accountsPartial({
+        project: project.project,
+        projectMint: tokenMint,
+        carbonCredits: carbonCreditsPda,
+        projectTokenAccount: opts.vintage?.vault ?? project.vault,
+        purchaseNftMint: nftMint,
+        buyerNftAccount: nftAccount,
+        buyerTokenAccount: tokenAccount,
+        purchase,
+        purchaseMetadata: findMetadataPda(nftMint),
+        projectCollectionMint: project.nftMint,
+        projectCollectionMetadata: findMetadataPda(project.nftMint),
+        projectCollectionMasterEdition: findMasterEditionPda(project.nftMint),
+        priceFeed: opts.priceFeed ?? null,
+        vintage: opts.vintage?.vintage ?? null,
+        buyerAllocation: opts.buyerAllocation ?? null,
+        referrer: opts.referrer ?? null,
+        buyer: buyer.publicKey,
+      })
+      .remainingAccounts(
+        beneficiaries.map((b) => ({
+          pubkey: b.recipient,
+          isWritable: true,
+          isSigner: false,
+        }))
+      )
+      .signers([buyer])
+      .rpc();
+
+    return { purchase, nftMint, nftAccount, tokenAccount };
+  }
 
   before(async () => {
     [carbonCreditsPda, carbonCreditsBump] = await PublicKey.findProgramAddress(
       [Buffer.from("carbon_credits")],
       program.programId
     );
+    [verifierRegistryPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("verifier_registry")],
+      program.programId
+    );
+    [platformCollectionMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("platform_collection")],
+      program.programId
+    );
 
     // Setup project owner
     projectOwner = Keypair.generate();
@@ -71,7 +408,7 @@ describe("CarbonPay Program Test Suite", () => {
   // ──────────────────────────────────────────────────────────────────────────────
   // 1) InitializeCarbonCreditsAccountConstraints
   // ──────────────────────────────────────────────────────────────────────────────
-  it("1. Initialize CarbonCredits PDA", async () => {
+  it("1. Initialize CarbonCredits PDA, verifier registry and platform collection", async () => {
     await program.methods
       .initializeCarbonCredits()
       .accountsPartial({
@@ -86,6 +423,63 @@ describe("CarbonPay Program Test Suite", () => {
     assert.equal(cc.bump, carbonCreditsBump);
     assert.equal(cc.totalCredits.toNumber(), 0);
     assert.equal(cc.offsetCredits.toNumber(), 0);
+
+    // The verifier registry starts with the admin as the only verifier (1-of-1)
+    await program.methods
+      .initializeVerifierRegistry()
+      .accountsPartial({
+        authority: provider.wallet.publicKey,
+        carbonCredits: carbonCreditsPda,
+        verifierRegistry: verifierRegistryPda,
+      })
+      .rpc();
+
+    const registry = await program.account.verifierRegistry.fetch(
+      verifierRegistryPda
+    );
+    assert.equal(registry.threshold, 1);
+    assert.deepEqual(
+      registry.verifiers.map((v) => v.toBase58()),
+      [provider.wallet.publicKey.toBase58()]
+    );
+
+    // The platform collection NFT is held and controlled by the carbon_credits PDA
+    await program.methods
+      .initializePlatformCollection("https://carbonpay.com/collection.json")
+      .accountsPartial({
+        authority: provider.wallet.publicKey,
+        carbonCredits: carbonCreditsPda,
+        collectionMint: platformCollectionMint,
+        collectionTokenAccount: await getAssociatedTokenAddress(
+          platformCollectionMint,
+          carbonCreditsPda,
+          true
+        ),
+        collectionMetadata: findMetadataPda(platformCollectionMint),
+        collectionMasterEdition: findMasterEditionPda(platformCollectionMint),
+      })
+      .rpc();
+
+    const ccAfter = await program.account.carbonCredits.fetch(carbonCreditsPda);
+    assert.equal(
+      ccAfter.collectionMint.toBase58(),
+      platformCollectionMint.toBase58()
+    );
+
+    // A carbon registry's signing key, required to tokenize credits
+    registryOracle = Keypair.generate();
+    [registryOraclePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("registry_oracle"), registryOracle.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .registerRegistryOracle(registryOracle.publicKey, "Verra")
+      .accountsPartial({
+        authority: provider.wallet.publicKey,
+        carbonCredits: carbonCreditsPda,
+        registryOracle: registryOraclePda,
+      })
+      .rpc();
   });
 
   // ──────────────────────────────────────────────────────────────────────────────
@@ -115,14 +509,14 @@ describe("CarbonPay Program Test Suite", () => {
     );
     console.log("Token Mint created:", tokenMint.toBase58());
 
-    // 2. Create ATA for project owner (NFT)
+    // 2. Derive ATA for project owner (NFT), created by the program
     projectOwnerNftAccount = await getAssociatedTokenAddress(
       nftMint,
       projectOwner.publicKey
     );
     console.log("Owner NFT ATA:", projectOwnerNftAccount.toBase58());
 
-    // 3. Create ATA for vault (Tokens)
+    // 3. Derive ATA for vault (Tokens), created by the program
     vaultAta = await getAssociatedTokenAddress(
       tokenMint,
       carbonCreditsPda,
@@ -130,31 +524,7 @@ describe("CarbonPay Program Test Suite", () => {
     );
     console.log("Vault ATA:", vaultAta.toBase58());
 
-    // 4. Create ATAs in a single transaction
-    const createAtaTx = new Transaction()
-      .add(
-        createAssociatedTokenAccountInstruction(
-          projectOwner.publicKey, // payer
-          projectOwnerNftAccount,
-          projectOwner.publicKey, // owner
-          nftMint
-        )
-      )
-      .add(
-        createAssociatedTokenAccountInstruction(
-          projectOwner.publicKey, // payer
-          vaultAta,
-          carbonCreditsPda, // owner - the carbon_credits PDA
-          tokenMint
-        )
-      );
-
-    const createAtaTxSig = await provider.sendAndConfirm(createAtaTx, [
-      projectOwner,
-    ]);
-    console.log("ATAs creation: ", createAtaTxSig);
-
-    // 5. Derive Project PDA
+    // 4. Derive Project PDA
     [projectPda, projectBump] = await PublicKey.findProgramAddress(
       [
         Buffer.from("project"),
@@ -165,7 +535,7 @@ describe("CarbonPay Program Test Suite", () => {
     );
     console.log("Project PDA:", projectPda.toBase58());
 
-    // 6. Derive metadata and master edition PDAs
+    // 5. Derive metadata and master edition PDAs
     const [metadataPda] = await PublicKey.findProgramAddress(
       [
         Buffer.from("metadata"),
@@ -187,37 +557,113 @@ describe("CarbonPay Program Test Suite", () => {
     );
     console.log("Master Edition PDA:", masterEditionPda.toBase58());
 
-    // 7. Call initializeProject
+    // 6. Call initializeProject, backed by a registry attestation
     console.log("Calling initializeProject...");
-
-    try {
-      const tx = await program.methods
+    const initializeProject = (
+      attestation: ReturnType<typeof attest>["attestation"],
+      verifyIx: anchor.web3.TransactionInstruction,
+      projectTokenMint = tokenMint,
+      projectVault = vaultAta
+    ) =>
+      program.methods
         .initializeProject(
           new BN(PROJECT_AMOUNT),
           new BN(PRICE_PER_TOKEN),
           new BN(CARBON_PAY_FEE),
           PROJECT_URI,
           PROJECT_NAME,
-          PROJECT_SYMBOL
+          PROJECT_SYMBOL,
+          PROJECT_DOCUMENT_HASH,
+          { lamports: {} },
+          [], // all proceeds to the project owner
+          attestation
         )
         .accountsStrict({
           projectOwner: projectOwner.publicKey,
           project: projectPda,
           nftMint: nftMint,
-          tokenMint: tokenMint,
+          tokenMint: projectTokenMint,
           projectOwnerNftAccount: projectOwnerNftAccount,
-          vault: vaultAta,
+          vault: projectVault,
           carbonCredits: carbonCreditsPda,
           metadata: metadataPda,
           masterEdition: masterEditionPda,
+          platformCollectionMint,
+          platformCollectionMetadata: findMetadataPda(platformCollectionMint),
+          platformCollectionMasterEdition: findMasterEditionPda(
+            platformCollectionMint
+          ),
           tokenProgram: TOKEN_PROGRAM_ID,
           tokenMetadataProgram: METADATA_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           rent: SYSVAR_RENT_PUBKEY,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          registryOracle: registryOraclePda,
+          instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
         })
-        .signers([projectOwner])
-        .rpc();
+        .preInstructions([verifyIx])
+        .signers([projectOwner]);
+
+    // 7.1 An attestation signed by an unregistered key is rejected
+    const forged = attest(
+      projectOwner.publicKey,
+      tokenMint,
+      PROJECT_AMOUNT,
+      Keypair.generate()
+    );
+    try {
+      await initializeProject(forged.attestation, forged.verifyIx).rpc();
+      assert.fail("A forged attestation should be rejected");
+    } catch (error) {
+      assert.ok(String(error).includes("InvalidAttestationSigner"));
+    }
+
+    // 7.2 A token mint with tokens minted outside the attestation is rejected
+    const preMinted = await createMint(
+      connection,
+      projectOwner,
+      projectOwner.publicKey,
+      projectOwner.publicKey,
+      0
+    );
+    const ownerPreMinted = await createAssociatedTokenAccount(
+      connection,
+      projectOwner,
+      preMinted,
+      projectOwner.publicKey
+    );
+    await mintTo(
+      connection,
+      projectOwner,
+      preMinted,
+      ownerPreMinted,
+      projectOwner,
+      1
+    );
+    try {
+      const { attestation, verifyIx } = attest(
+        projectOwner.publicKey,
+        preMinted,
+        PROJECT_AMOUNT
+      );
+      await initializeProject(
+        attestation,
+        verifyIx,
+        preMinted,
+        await getAssociatedTokenAddress(preMinted, carbonCreditsPda, true)
+      ).rpc();
+      assert.fail("A token mint with a supply should be rejected");
+    } catch (error) {
+      assert.ok(String(error).includes("InvalidProjectMint"));
+    }
+
+    try {
+      const { attestation, verifyIx } = attest(
+        projectOwner.publicKey,
+        tokenMint,
+        PROJECT_AMOUNT
+      );
+      const tx = await initializeProject(attestation, verifyIx).rpc();
 
       console.log("Project initialized successfully! Tx:", tx);
 
@@ -248,7 +694,19 @@ describe("CarbonPay Program Test Suite", () => {
         PROJECT_AMOUNT,
         "Incorrect remainingAmount"
       );
-      assert.ok(projAcc.isActive, "Project is not active");
+      assert.ok(
+        projAcc.status.pendingReview !== undefined,
+        "Project should start pending review"
+      );
+      assert.deepEqual(projAcc.documentHash, PROJECT_DOCUMENT_HASH);
+      assert.equal(
+        projAcc.registryOracle.toBase58(),
+        registryOracle.publicKey.toBase58()
+      );
+      assert.deepEqual(
+        Buffer.from(projAcc.attestationHash),
+        createHash("sha256").update(encodeAttestation(attestation)).digest()
+      );
 
       // Verify that NFT was minted to the project owner
       const ownerNftBal = await connection.getTokenAccountBalance(
@@ -284,6 +742,11 @@ describe("CarbonPay Program Test Suite", () => {
         carbonCreditsPda.toBase58(),
         "Incorrect token mint authority"
       );
+      assert.equal(
+        tokenMintInfo.freezeAuthority,
+        null,
+        "Token mint freeze authority should be dropped"
+      );
     } catch (error) {
       console.error("Error during project initialization:", error);
       // Provide more error information for debugging
@@ -298,7 +761,58 @@ describe("CarbonPay Program Test Suite", () => {
   });
 
   // ──────────────────────────────────────────────────────────────────────────────
-  // 3) PurchaseCarbonCredits
+  // 3) ReviewProject (approve)
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("3. Approve Project (verifier checks document hash)", async () => {
+    const [projectVoteRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vote_record"), projectPda.toBuffer()],
+      program.programId
+    );
+
+    // a) A hash that differs from the submitted documents is refused
+    const wrongHash = Array.from(
+      createHash("sha256").update("some-other-document").digest()
+    );
+    try {
+      await program.methods
+        .approveProject(wrongHash)
+        .accountsPartial({
+          verifier: provider.wallet.publicKey,
+          project: projectPda,
+          verifierRegistry: verifierRegistryPda,
+          voteRecord: projectVoteRecordPda,
+        })
+        .rpc();
+      assert.fail("Approval with a mismatched document hash should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("DocumentHashMismatch"));
+    }
+
+    // b) The admin is the default verifier
+    await program.methods
+      .approveProject(PROJECT_DOCUMENT_HASH)
+      .accountsPartial({
+        verifier: provider.wallet.publicKey,
+        project: projectPda,
+        verifierRegistry: verifierRegistryPda,
+        voteRecord: projectVoteRecordPda,
+      })
+      .rpc();
+
+    const projAcc = await program.account.project.fetch(projectPda);
+    assert.ok(projAcc.status.active !== undefined, "Project should be active");
+    assert.equal(
+      projAcc.reviewer?.toBase58(),
+      provider.wallet.publicKey.toBase58()
+    );
+
+    const vote = await program.account.voteRecord.fetch(projectVoteRecordPda);
+    assert.ok(vote.resolved);
+    assert.equal(vote.approvals.length, 1);
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 4) PurchaseCarbonCredits
   // ──────────────────────────────────────────────────────────────────────────────
   let buyer: Keypair;
   let purchaseNftMint: PublicKey;
@@ -308,25 +822,19 @@ describe("CarbonPay Program Test Suite", () => {
   let purchaseBump: number;
   const purchaseAmount = 10;
 
-  it("3. Purchase Carbon Credits (SOL → owner + fee, mint NFT and tokens)", async () => {
+  it("4. Purchase Carbon Credits (SOL → owner + fee, mint NFT and tokens)", async () => {
     // a) Setup buyer and airdrop
     buyer = Keypair.generate();
     await connection
       .requestAirdrop(buyer.publicKey, 10 * anchor.web3.LAMPORTS_PER_SOL)
       .then((sig) => connection.confirmTransaction(sig));
 
-    // b) Create mint for purchase NFT
-    purchaseNftMint = await createMint(
-      connection,
-      buyer,
-      buyer.publicKey,
-      buyer.publicKey,
-      0
-    );
-    console.log("Purchase NFT mint created:", purchaseNftMint.toBase58());
-    console.log("Mint authority:", buyer.publicKey.toBase58());
+    // b) Derive the purchase NFT mint, numbered by the project's purchase count
+    const { purchaseCount } = await program.account.project.fetch(projectPda);
+    purchaseNftMint = findPurchaseNftMint(projectPda, purchaseCount);
+    console.log("Purchase NFT mint:", purchaseNftMint.toBase58());
 
-    // c) Derive buyer's ATAs
+    // c) Derive buyer's ATAs, created by the program
     buyerNftAta = await getAssociatedTokenAddress(
       purchaseNftMint,
       buyer.publicKey
@@ -335,58 +843,7 @@ describe("CarbonPay Program Test Suite", () => {
     console.log("Buyer NFT ATA:", buyerNftAta.toBase58());
     console.log("Buyer token ATA:", buyerTokenAta.toBase58());
 
-    // d) Create ATAs (buyer pays for their own accounts)
-    const ixBuyNftAta = createAssociatedTokenAccountInstruction(
-      buyer.publicKey, // buyer pays for their own accounts
-      buyerNftAta,
-      buyer.publicKey,
-      purchaseNftMint
-    );
-    const ixBuyTokenAta = createAssociatedTokenAccountInstruction(
-      buyer.publicKey, // buyer pays for their own accounts
-      buyerTokenAta,
-      buyer.publicKey,
-      tokenMint
-    );
-
-    // Have the buyer sign the transaction to create their own accounts
-    await provider.sendAndConfirm(
-      new Transaction().add(ixBuyNftAta, ixBuyTokenAta),
-      [buyer] // buyer signs
-    );
-    console.log("Buyer ATAs created successfully");
-
-    // Verify account ownerships
-    try {
-      // Check ATA ownerships
-      const buyerNftAccount = await connection.getAccountInfo(buyerNftAta);
-      const buyerTokenAccount = await connection.getAccountInfo(buyerTokenAta);
-      const vaultAccount = await connection.getAccountInfo(vaultAta);
-
-      console.log(
-        "Buyer NFT ATA owner program:",
-        buyerNftAccount?.owner.toBase58()
-      );
-      console.log(
-        "Buyer Token ATA owner program:",
-        buyerTokenAccount?.owner.toBase58()
-      );
-      console.log("Vault ATA owner program:", vaultAccount?.owner.toBase58());
-
-      // Check mints
-      const nftMintInfo = await getMint(connection, purchaseNftMint);
-      const tokenMintInfo = await getMint(connection, tokenMint);
-
-      console.log("NFT Mint Authority:", nftMintInfo.mintAuthority?.toBase58());
-      console.log(
-        "Token Mint Authority:",
-        tokenMintInfo.mintAuthority?.toBase58()
-      );
-    } catch (e) {
-      console.error("Error checking account ownerships:", e);
-    }
-
-    // e) Derive Purchase PDA
+    // d) Derive Purchase PDA
     [purchasePda, purchaseBump] = await PublicKey.findProgramAddress(
       [
         Buffer.from("purchase"),
@@ -398,7 +855,7 @@ describe("CarbonPay Program Test Suite", () => {
     );
     console.log("Purchase PDA:", purchasePda.toBase58());
 
-    // f) Derive purchase metadata PDA
+    // e) Derive purchase metadata PDA
     const [purchaseMetadataPda] = await PublicKey.findProgramAddress(
       [
         Buffer.from("metadata"),
@@ -409,7 +866,7 @@ describe("CarbonPay Program Test Suite", () => {
     );
     console.log("Purchase Metadata PDA:", purchaseMetadataPda.toBase58());
 
-    // g) Print all account info for debugging
+    // f) Print all account info for debugging
     console.log("--- Purchase Call Accounts ---");
     console.log("Project:", projectPda.toBase58());
     console.log("Project Owner:", projectOwner.publicKey.toBase58());
@@ -424,28 +881,83 @@ describe("CarbonPay Program Test Suite", () => {
     console.log("Purchase Metadata:", purchaseMetadataPda.toBase58());
     console.log("---------------------------");
 
-    // h) Call purchaseCarbonCredits
+    // g) Call purchaseCarbonCredits
+    const purchaseAccounts = {
+      project: projectPda,
+      projectMint: tokenMint,
+      carbonCredits: carbonCreditsPda,
+      projectTokenAccount: vaultAta,
+      purchaseNftMint,
+      buyerNftAccount: buyerNftAta,
+      buyerTokenAccount: buyerTokenAta,
+      purchase: purchasePda,
+      purchaseMetadata: purchaseMetadataPda,
+      projectCollectionMint: nftMint,
+      projectCollectionMetadata: findMetadataPda(nftMint),
+      projectCollectionMasterEdition: findMasterEditionPda(nftMint),
+      priceFeed: null, // only needed for USD-priced projects
+      vintage: null, // buy from the project's own tranche
+      buyerAllocation: null, // only needed during a presale
+      referrer: null, // no sales partner
+      buyer: buyer.publicKey,
+    };
+    const expectedTotal = purchaseAmount * PRICE_PER_TOKEN;
+    // Proceeds go to the project's beneficiaries, here only the owner
+    const ownerPayout = [
+      { pubkey: projectOwner.publicKey, isWritable: true, isSigner: false },
+    ];
+
+    // A quote below the current price or fee is rejected
+    try {
+      await program.methods
+        .purchaseCarbonCredits(
+          new BN(purchaseAmount),
+          new BN(expectedTotal - 1),
+          new BN(CARBON_PAY_FEE),
+          []
+        )
+        .accountsPartial(purchaseAccounts)
+        .remainingAccounts(ownerPayout)
+        .signers([buyer])
+        .rpc();
+      assert.fail("Purchase above max_total_lamports should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("SlippageExceeded"));
+    }
+    try {
+      await program.methods
+        .purchaseCarbonCredits(
+          new BN(purchaseAmount),
+          new BN(expectedTotal),
+          new BN(CARBON_PAY_FEE - 1),
+          []
+        )
+        .accountsPartial(purchaseAccounts)
+        .remainingAccounts(ownerPayout)
+        .signers([buyer])
+        .rpc();
+      assert.fail("Purchase above expected_fee_bps should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("FeeAboveExpected"));
+    }
+
     console.log("Calling purchaseCarbonCredits with amount:", purchaseAmount);
     try {
       const tx = await program.methods
-        .purchaseCarbonCredits(new BN(purchaseAmount))
+        .purchaseCarbonCredits(
+          new BN(purchaseAmount),
+          new BN(expectedTotal),
+          new BN(CARBON_PAY_FEE),
+          []
+        )
         .accountsPartial({
-          project: projectPda,
-          projectOwner: projectOwner.publicKey,
-          projectMint: tokenMint,
-          carbonCredits: carbonCreditsPda,
-          projectTokenAccount: vaultAta,
-          purchaseNftMint,
-          buyerNftAccount: buyerNftAta,
-          buyerTokenAccount: buyerTokenAta,
-          purchase: purchasePda,
-          purchaseMetadata: purchaseMetadataPda,
-          buyer: buyer.publicKey,
+          ...purchaseAccounts,
           tokenProgram: TOKEN_PROGRAM_ID,
           tokenMetadataProgram: METADATA_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           rent: SYSVAR_RENT_PUBKEY,
         })
+        .remainingAccounts(ownerPayout)
         .signers([buyer])
         .rpc();
       console.log("Purchase successful! Tx:", tx);
@@ -460,7 +972,7 @@ describe("CarbonPay Program Test Suite", () => {
       throw error;
     }
 
-    // i) Post-purchase verifications
+    // h) Post-purchase verifications
     const projAfter = await program.account.project.fetch(projectPda);
     assert.equal(projAfter.remainingAmount.toNumber(), 100 - purchaseAmount);
 
@@ -470,6 +982,14 @@ describe("CarbonPay Program Test Suite", () => {
     const buyTokenBal = await connection.getTokenAccountBalance(buyerTokenAta);
     assert.equal(buyTokenBal.value.uiAmount, purchaseAmount);
 
+    // The purchase NFT mint belongs to the program, not the buyer
+    const nftMintInfo = await getMint(connection, purchaseNftMint);
+    assert.equal(
+      nftMintInfo.mintAuthority?.toBase58(),
+      carbonCreditsPda.toBase58()
+    );
+    assert.equal(projAfter.purchaseCount.toNumber(), 1);
+
     const purchaseAcc = await program.account.purchase.fetch(purchasePda);
     assert.equal(purchaseAcc.amount.toNumber(), purchaseAmount);
     assert.equal(purchaseAcc.remainingAmount.toNumber(), purchaseAmount);
@@ -477,14 +997,24 @@ describe("CarbonPay Program Test Suite", () => {
   });
 
   // ──────────────────────────────────────────────────────────────────────────────
-  // 4) RequestOffset
+  // 5) RequestOffset
   // ──────────────────────────────────────────────────────────────────────────────
-  it("4. Request Offset (burn NFT, partial mint and register)", async () => {
+  let offsetReqPda: PublicKey;
+
+  it("5. Request Offset (burn NFT, partial mint and register)", async () => {
     const offsetAmount = 5;
     const requestId = "REQ123";
+    const beneficiary = Keypair.generate().publicKey;
+    const retirement = {
+      beneficiary,
+      beneficiaryName: "Acme Subsidiary Ltd",
+      reason: "Offsetting 2024 business travel",
+      reportingPeriodStart: new BN(1704067200), // 2024-01-01
+      reportingPeriodEnd: new BN(1735689599), // 2024-12-31
+    };
 
     // a) Derive OffsetRequest PDA
-    const [offsetReqPda] = await PublicKey.findProgramAddress(
+    [offsetReqPda] = await PublicKey.findProgramAddress(
       [
         Buffer.from("offset_request"),
         buyer.publicKey.toBuffer(),
@@ -494,28 +1024,12 @@ describe("CarbonPay Program Test Suite", () => {
       program.programId
     );
 
-    // b) Create new mint for residual NFT
-    const newNftMint = await createMint(
-      connection,
-      buyer,
-      buyer.publicKey,
-      buyer.publicKey,
-      0
-    );
+    // b) Derive the residual NFT mint and ATA, created by the program
+    const newNftMint = findOffsetNftMint(purchasePda, 0);
     const newNftAta = await getAssociatedTokenAddress(
       newNftMint,
       buyer.publicKey
     );
-    await provider.sendAndConfirm(
-      new Transaction().add(
-        createAssociatedTokenAccountInstruction(
-          provider.wallet.publicKey,
-          newNftAta,
-          buyer.publicKey,
-          newNftMint
-        )
-      )
-    );
 
     // c) Derive new NFT metadata PDA
     const [newNftMetadataPda] = await PublicKey.findProgramAddress(
@@ -527,27 +1041,47 @@ describe("CarbonPay Program Test Suite", () => {
       METADATA_PROGRAM_ID
     );
 
-    // d) Call requestOffset
+    // d) Call requestOffset; a partial offset needs the remaining-balance NFT
+    const offsetAccounts = {
+      offsetRequester: buyer.publicKey,
+      purchase: purchasePda,
+      project: projectPda,
+      vintage: null,
+      originalNftMint: purchaseNftMint,
+      originalNftAccount: buyerNftAta,
+      newNftMint,
+      newNftAccount: newNftAta,
+      newNftMetadata: newNftMetadataPda,
+      projectCollectionMint: nftMint,
+      projectCollectionMetadata: findMetadataPda(nftMint),
+      projectCollectionMasterEdition: findMasterEditionPda(nftMint),
+      tokenMint: tokenMint,
+      buyerTokenAccount: buyerTokenAta,
+      carbonCredits: carbonCreditsPda,
+      offsetRequest: offsetReqPda,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      tokenMetadataProgram: METADATA_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      rent: SYSVAR_RENT_PUBKEY,
+    };
+    try {
+      await program.methods
+        .requestOffset(new BN(offsetAmount), requestId, retirement)
+        .accountsPartial({
+          ...offsetAccounts,
+          newNftMint: null,
+          newNftAccount: null,
+          newNftMetadata: null,
+        })
+        .signers([buyer])
+        .rpc();
+      assert.fail("Partial offset without the remaining-balance NFT should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("MissingRemainderNft"));
+    }
     await program.methods
-      .requestOffset(new BN(offsetAmount), requestId)
-      .accountsPartial({
-        offsetRequester: buyer.publicKey,
-        purchase: purchasePda,
-        project: projectPda,
-        originalNftMint: purchaseNftMint,
-        originalNftAccount: buyerNftAta,
-        newNftMint,
-        newNftAccount: newNftAta,
-        newNftMetadata: newNftMetadataPda,
-        tokenMint: tokenMint,
-        buyerTokenAccount: buyerTokenAta,
-        carbonCredits: carbonCreditsPda,
-        offsetRequest: offsetReqPda,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        tokenMetadataProgram: METADATA_PROGRAM_ID,
-        systemProgram: SystemProgram.programId,
-        rent: SYSVAR_RENT_PUBKEY,
-      })
+      .requestOffset(new BN(offsetAmount), requestId, retirement)
+      .accountsPartial(offsetAccounts)
       .signers([buyer])
       .rpc();
 
@@ -564,6 +1098,30 @@ describe("CarbonPay Program Test Suite", () => {
     const offsetAcc = await program.account.offsetRequest.fetch(offsetReqPda);
     assert.equal(offsetAcc.amount.toNumber(), offsetAmount);
     assert.ok(offsetAcc.status.pending !== undefined);
+    assert.equal(
+      offsetAcc.offsetRequester.toBase58(),
+      buyer.publicKey.toBase58()
+    );
+    assert.equal(
+      offsetAcc.retirement.beneficiary.toBase58(),
+      beneficiary.toBase58()
+    );
+    assert.equal(
+      offsetAcc.retirement.beneficiaryName,
+      retirement.beneficiaryName
+    );
+    assert.equal(offsetAcc.retirement.reason, retirement.reason);
+    assert.equal(
+      offsetAcc.retirement.reportingPeriodEnd.toNumber(),
+      retirement.reportingPeriodEnd.toNumber()
+    );
+
+    // Retirements can be looked up by beneficiary, independently of the payer
+    const byBeneficiary = await program.account.offsetRequest.all([
+      { memcmp: { offset: 8 + 32, bytes: beneficiary.toBase58() } },
+    ]);
+    assert.equal(byBeneficiary.length, 1);
+    assert.equal(byBeneficiary[0].publicKey.toBase58(), offsetReqPda.toBase58());
 
     const origBal = await connection.getTokenAccountBalance(buyerNftAta);
     assert.equal(origBal.value.uiAmount, 0);
@@ -571,6 +1129,10 @@ describe("CarbonPay Program Test Suite", () => {
     const newBal = await connection.getTokenAccountBalance(newNftAta);
     assert.equal(newBal.value.uiAmount, 1);
 
+    // The remaining-balance NFT lists the beneficiary among its creators
+    const newNftMetadata = await connection.getAccountInfo(newNftMetadataPda);
+    assert.ok(newNftMetadata!.data.includes(beneficiary.toBuffer()));
+
     // Verify that fungible tokens were burned
     const buyerTokenBal = await connection.getTokenAccountBalance(
       buyerTokenAta
@@ -581,6 +1143,14 @@ describe("CarbonPay Program Test Suite", () => {
       "Buyer should have purchaseAmount - offsetAmount tokens remaining"
     );
 
+    // The remaining balance now lives in the new NFT
+    assert.equal(
+      purchaseAfter.currentNftMint.toBase58(),
+      newNftMint.toBase58()
+    );
+    assert.equal(purchaseAfter.offsetCount.toNumber(), 1);
+    assert.equal(purchaseAfter.pendingRequests, 1);
+
     // Verify project offset amount
     const projectAfter = await program.account.project.fetch(projectPda);
     assert.equal(
@@ -589,4 +1159,2492 @@ describe("CarbonPay Program Test Suite", () => {
       "Project offsetAmount should be updated"
     );
   });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 6) ReviewOffsetRequest (M-of-N quorum)
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("6. Review Offset Request (2-of-2 verifier quorum)", async () => {
+    // a) Register a second verifier and require both votes
+    const secondVerifier = Keypair.generate();
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(
+        secondVerifier.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      )
+    );
+    await program.methods
+      .addVerifier(secondVerifier.publicKey)
+      .accountsPartial({
+        authority: provider.wallet.publicKey,
+        carbonCredits: carbonCreditsPda,
+        verifierRegistry: verifierRegistryPda,
+      })
+      .rpc();
+    await program.methods
+      .setVerifierThreshold(2)
+      .accountsPartial({
+        authority: provider.wallet.publicKey,
+        carbonCredits: carbonCreditsPda,
+        verifierRegistry: verifierRegistryPda,
+      })
+      .rpc();
+
+    const [offsetVoteRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vote_record"), offsetReqPda.toBuffer()],
+      program.programId
+    );
+
+    // b) First vote does not reach the quorum
+    await program.methods
+      .reviewOffsetRequest(true)
+      .accountsPartial({
+        verifier: provider.wallet.publicKey,
+        offsetRequest: offsetReqPda,
+        project: projectPda,
+        purchase: purchasePda,
+        verifierRegistry: verifierRegistryPda,
+        voteRecord: offsetVoteRecordPda,
+      })
+      .rpc();
+    let offsetAcc = await program.account.offsetRequest.fetch(offsetReqPda);
+    assert.ok(offsetAcc.status.pending !== undefined);
+
+    // c) Second vote approves the request and records both voters
+    await program.methods
+      .reviewOffsetRequest(true)
+      .accountsPartial({
+        verifier: secondVerifier.publicKey,
+        offsetRequest: offsetReqPda,
+        project: projectPda,
+        purchase: purchasePda,
+        verifierRegistry: verifierRegistryPda,
+        voteRecord: offsetVoteRecordPda,
+      })
+      .signers([secondVerifier])
+      .rpc();
+    offsetAcc = await program.account.offsetRequest.fetch(offsetReqPda);
+    assert.ok(offsetAcc.status.approved !== undefined);
+    assert.deepEqual(
+      offsetAcc.processors.map((p) => p.toBase58()).sort(),
+      [provider.wallet.publicKey, secondVerifier.publicKey]
+        .map((p) => p.toBase58())
+        .sort()
+    );
+    assert.ok(offsetAcc.processedDate.toNumber() > 0);
+    const purchaseAcc = await program.account.purchase.fetch(purchasePda);
+    assert.equal(purchaseAcc.pendingRequests, 0);
+
+    // d) Back to a single verifier for the rest of the suite
+    await program.methods
+      .setVerifierThreshold(1)
+      .accountsPartial({
+        authority: provider.wallet.publicKey,
+        carbonCredits: carbonCreditsPda,
+        verifierRegistry: verifierRegistryPda,
+      })
+      .rpc();
+    await program.methods
+      .removeVerifier(secondVerifier.publicKey)
+      .accountsPartial({
+        authority: provider.wallet.publicKey,
+        carbonCredits: carbonCreditsPda,
+        verifierRegistry: verifierRegistryPda,
+      })
+      .rpc();
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 7) PurchaseCarbonCredits (USD-priced project)
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("7. Purchase USD-priced credits (SOL/USD oracle conversion)", async () => {
+    // a) Point the platform at the mock SOL/USD feed. The fixture has a fixed
+    //    publish time, so the allowed price age is generous here.
+    await program.methods
+      .setPriceOracle(new BN(10 * 365 * 24 * 60 * 60), 50)
+      .accountsPartial({
+        authority: provider.wallet.publicKey,
+        carbonCredits: carbonCreditsPda,
+        priceFeed: MOCK_SOL_USD_PRICE_FEED,
+      })
+      .rpc();
+
+    // b) $15.00 per tonne at $150.00/SOL is 0.1 SOL per token
+    const usdOwner = Keypair.generate();
+    await airdrop(usdOwner.publicKey, 10);
+    const usdProject = await createApprovedProject(usdOwner, {
+      amount: 50,
+      price: 1_500,
+      priceCurrency: { usdCents: {} },
+    });
+
+    const usdBuyer = Keypair.generate();
+    await airdrop(usdBuyer.publicKey, 10);
+
+    // c) The price feed is required for USD-priced projects
+    try {
+      await purchaseCredits(usdBuyer, usdProject, 2);
+      assert.fail("Purchase without a price feed should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("InvalidPriceFeed"));
+    }
+
+    const ownerBefore = await connection.getBalance(usdOwner.publicKey);
+    const { purchase } = await purchaseCredits(usdBuyer, usdProject, 2, {
+      priceFeed: MOCK_SOL_USD_PRICE_FEED,
+    });
+
+    const total = 2 * 100_000_000; // 2 tokens at 0.1 SOL
+    const fee = (total * CARBON_PAY_FEE) / 10_000;
+    const ownerAfter = await connection.getBalance(usdOwner.publicKey);
+    assert.equal(ownerAfter - ownerBefore, total - fee);
+
+    const purchaseAcc = await program.account.purchase.fetch(purchase);
+    assert.equal(purchaseAcc.solUsdPrice.toNumber(), 15_000_000_000);
+    assert.equal(purchaseAcc.solUsdExpo, -8);
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 8) Revenue split across project beneficiaries
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("8. Split purchase proceeds across project beneficiaries", async () => {
+    const developer = Keypair.generate();
+    const communityFund = Keypair.generate();
+    const verifierShare = Keypair.generate();
+    await airdrop(developer.publicKey, 10);
+
+    const splitProject = await createApprovedProject(developer, {
+      amount: 50,
+      price: PRICE_PER_TOKEN,
+      priceCurrency: { lamports: {} },
+      beneficiaries: [
+        { recipient: developer.publicKey, shareBps: 7_000 },
+        { recipient: communityFund.publicKey, shareBps: 2_000 },
+        { recipient: verifierShare.publicKey, shareBps: 1_000 },
+      ],
+    });
+
+    const splitBuyer = Keypair.generate();
+    await airdrop(splitBuyer.publicKey, 10);
+
+    const recipients = [developer, communityFund, verifierShare].map(
+      (k) => k.publicKey
+    );
+    const before = await Promise.all(
+      recipients.map((r) => connection.getBalance(r))
+    );
+    await purchaseCredits(splitBuyer, splitProject, 4);
+    const after = await Promise.all(
+      recipients.map((r) => connection.getBalance(r))
+    );
+
+    const total = 4 * PRICE_PER_TOKEN;
+    const toOwners = total - (total * CARBON_PAY_FEE) / 10_000;
+    assert.equal(after[0] - before[0], (toOwners * 7_000) / 10_000);
+    assert.equal(after[1] - before[1], (toOwners * 2_000) / 10_000);
+    assert.equal(after[2] - before[2], (toOwners * 1_000) / 10_000);
+
+    // Shares that don't sum to 10,000 bps are rejected
+    try {
+      await program.methods
+        .setProjectBeneficiaries([
+          { recipient: developer.publicKey, shareBps: 5_000 },
+        ])
+        .accountsPartial({
+          projectOwner: developer.publicKey,
+          authority: provider.wallet.publicKey,
+          project: splitProject.project,
+          carbonCredits: carbonCreditsPda,
+        })
+        .signers([developer])
+        .rpc();
+      assert.fail("Shares not summing to 10,000 bps should be rejected");
+    } catch (error) {
+      assert.ok(String(error).includes("InvalidBeneficiaryShares"));
+    }
+
+    // Owner and platform authority together can change the split
+    await program.methods
+      .setProjectBeneficiaries([
+        { recipient: developer.publicKey, shareBps: 6_000 },
+        { recipient: communityFund.publicKey, shareBps: 4_000 },
+      ])
+      .accountsPartial({
+        projectOwner: developer.publicKey,
+        authority: provider.wallet.publicKey,
+        project: splitProject.project,
+        carbonCredits: carbonCreditsPda,
+      })
+      .signers([developer])
+      .rpc();
+    const projAcc = await program.account.project.fetch(splitProject.project);
+    assert.equal(projAcc.beneficiaries.length, 2);
+    assert.equal(projAcc.beneficiaries[1].shareBps, 4_000);
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 9) Metadata updates
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("9. Update project metadata and refresh purchase NFT URI", async () => {
+    const metadataContains = async (mint: PublicKey, text: string) =>
+      (await connection.getAccountInfo(findMetadataPda(mint)))!.data
+        .toString()
+        .includes(text);
+
+    // a) The project owner renames the project NFT; the program signs as update authority
+    const newProjectUri = "https://uri.test/1-v2";
+    await program.methods
+      .updateProjectMetadata("MyProject v2", newProjectUri)
+      .accountsPartial({
+        projectOwner: projectOwner.publicKey,
+        project: projectPda,
+        carbonCredits: carbonCreditsPda,
+        metadata: findMetadataPda(nftMint),
+        tokenMetadataProgram: METADATA_PROGRAM_ID,
+      })
+      .signers([projectOwner])
+      .rpc();
+    assert.ok(await metadataContains(nftMint, newProjectUri));
+
+    // b) The admin moves purchase metadata to a new host and refreshes an NFT
+    const newBaseUri = "https://meta.carbonpay.com/purchases";
+    await program.methods
+      .setMetadataBaseUri(newBaseUri)
+      .accountsPartial({
+        authority: provider.wallet.publicKey,
+        carbonCredits: carbonCreditsPda,
+      })
+      .rpc();
+
+    const purchaseAcc = await program.account.purchase.fetch(purchasePda);
+    await program.methods
+      .refreshPurchaseMetadata()
+      .accountsPartial({
+        authority: provider.wallet.publicKey,
+        carbonCredits: carbonCreditsPda,
+        purchase: purchasePda,
+        metadata: findMetadataPda(purchaseAcc.currentNftMint),
+        tokenMetadataProgram: METADATA_PROGRAM_ID,
+      })
+      .rpc();
+    assert.ok(
+      await metadataContains(
+        purchaseAcc.currentNftMint,
+        `${newBaseUri}/${purchaseAcc.currentNftMint.toBase58()}/remaining`
+      )
+    );
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 10) IssueCredits
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("10. Issue additional credits (owner request, registry attestation)", async () => {
+    const issued = 20;
+    const issuanceHash = Array.from(
+      createHash("sha256").update("issuance-2025-verification-report").digest()
+    );
+    const projBefore = await program.account.project.fetch(projectPda);
+    const ccBefore = await program.account.carbonCredits.fetch(carbonCreditsPda);
+
+    const issue = (
+      attestation: ReturnType<typeof attest>["attestation"],
+      verifyIx: anchor.web3.TransactionInstruction
+    ) =>
+      program.methods
+        .issueCredits(new BN(issued), issuanceHash, attestation)
+        .accountsPartial({
+          projectOwner: projectOwner.publicKey,
+          project: projectPda,
+          carbonCredits: carbonCreditsPda,
+          tokenMint,
+          vault: vaultAta,
+          registryOracle: registryOraclePda,
+          issuanceRecord: PublicKey.findProgramAddressSync(
+            [
+              Buffer.from("issuance"),
+              projectPda.toBuffer(),
+              attestation.serialStart.toArrayLike(Buffer, "le", 8),
+            ],
+            program.programId
+          )[0],
+          instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        })
+        .preInstructions([verifyIx])
+        .signers([projectOwner])
+        .rpc();
+
+    // Without a registry attestation the issuance is refused
+    const outsider = Keypair.generate();
+    const forged = attest(projectOwner.publicKey, tokenMint, issued, outsider);
+    try {
+      await issue(forged.attestation, forged.verifyIx);
+      assert.fail("Issuance without a registry attestation should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("InvalidAttestationSigner"));
+    }
+
+    const { attestation, verifyIx } = attest(
+      projectOwner.publicKey,
+      tokenMint,
+      issued
+    );
+    await issue(attestation, verifyIx);
+
+    const projAfter = await program.account.project.fetch(projectPda);
+    assert.equal(
+      projAfter.amount.toNumber(),
+      projBefore.amount.toNumber() + issued
+    );
+    assert.equal(
+      projAfter.remainingAmount.toNumber(),
+      projBefore.remainingAmount.toNumber() + issued
+    );
+
+    const ccAfter = await program.account.carbonCredits.fetch(carbonCreditsPda);
+    assert.equal(
+      ccAfter.totalCredits.toNumber(),
+      ccBefore.totalCredits.toNumber() + issued
+    );
+    assert.equal(
+      ccAfter.activeCredits.toNumber(),
+      ccBefore.activeCredits.toNumber() + issued
+    );
+
+    const vaultBal = await connection.getTokenAccountBalance(vaultAta);
+    assert.equal(
+      Number(vaultBal.value.amount),
+      projAfter.remainingAmount.toNumber()
+    );
+
+    // The same attestation cannot issue its serial range twice
+    try {
+      await issue(attestation, verifyIx);
+      assert.fail("Replaying an issuance attestation should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("custom program error: 0x0"));
+    }
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 11) ProjectVintage tranches
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("11. Add a vintage tranche, buy and offset from it", async () => {
+    const vintageOwner = Keypair.generate();
+    await airdrop(vintageOwner.publicKey, 10);
+    const vintageProject = await createApprovedProject(vintageOwner, {
+      amount: 40,
+      price: PRICE_PER_TOKEN,
+      priceCurrency: { lamports: {} },
+    });
+
+    // a) Add the 2023 vintage with its own mint, vault and price
+    const vintageYear = 2023;
+    const vintageAmount = 30;
+    const vintagePrice = 2 * PRICE_PER_TOKEN;
+    const vintageMint = await createMint(
+      connection,
+      vintageOwner,
+      vintageOwner.publicKey,
+      vintageOwner.publicKey,
+      0
+    );
+    const vintageVault = await getAssociatedTokenAddress(
+      vintageMint,
+      carbonCreditsPda,
+      true
+    );
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          vintageOwner.publicKey,
+          vintageVault,
+          carbonCreditsPda,
+          vintageMint
+        )
+      ),
+      [vintageOwner]
+    );
+    const yearSeed = Buffer.alloc(2);
+    yearSeed.writeUInt16LE(vintageYear);
+    const [vintagePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vintage"), vintageProject.project.toBuffer(), yearSeed],
+      program.programId
+    );
+    const vintageAttestation = attest(
+      vintageOwner.publicKey,
+      vintageMint,
+      vintageAmount,
+      registryOracle,
+      vintageYear
+    );
+    await program.methods
+      .addProjectVintage(
+        vintageYear,
+        new BN(vintageAmount),
+        new BN(vintagePrice),
+        vintageAttestation.attestation
+      )
+      .accountsPartial({
+        projectOwner: vintageOwner.publicKey,
+        project: vintageProject.project,
+        vintage: vintagePda,
+        tokenMint: vintageMint,
+        vault: vintageVault,
+        carbonCredits: carbonCreditsPda,
+        registryOracle: registryOraclePda,
+        instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+      })
+      .preInstructions([vintageAttestation.verifyIx])
+      .signers([vintageOwner])
+      .rpc();
+
+    const vintageAcc = await program.account.projectVintage.fetch(vintagePda);
+    assert.equal(vintageAcc.vintageYear, vintageYear);
+    assert.equal(vintageAcc.remainingAmount.toNumber(), vintageAmount);
+    const vintageMintInfo = await getMint(connection, vintageMint);
+    assert.equal(
+      vintageMintInfo.mintAuthority?.toBase58(),
+      carbonCreditsPda.toBase58()
+    );
+    assert.equal(vintageMintInfo.freezeAuthority, null);
+    let projAcc = await program.account.project.fetch(vintageProject.project);
+    assert.equal(projAcc.amount.toNumber(), 40 + vintageAmount);
+    assert.equal(projAcc.remainingAmount.toNumber(), 40 + vintageAmount);
+
+    // b) Buy 6 credits of the vintage at the vintage price
+    const vintageBuyer = Keypair.generate();
+    await airdrop(vintageBuyer.publicKey, 10);
+    const ownerBefore = await connection.getBalance(vintageOwner.publicKey);
+    const bought = await purchaseCredits(vintageBuyer, vintageProject, 6, {
+      vintage: {
+        vintage: vintagePda,
+        tokenMint: vintageMint,
+        vault: vintageVault,
+      },
+    });
+    const ownerAfter = await connection.getBalance(vintageOwner.publicKey);
+    const total = 6 * vintagePrice;
+    assert.equal(
+      ownerAfter - ownerBefore,
+      total - (total * CARBON_PAY_FEE) / 10_000
+    );
+    const tokenBal = await connection.getTokenAccountBalance(
+      bought.tokenAccount
+    );
+    assert.equal(Number(tokenBal.value.amount), 6);
+    const purchaseAcc = await program.account.purchase.fetch(bought.purchase);
+    assert.equal(purchaseAcc.vintage.toBase58(), vintagePda.toBase58());
+
+    // c) Offset 4 of them; the request must name the purchase's vintage
+    const remainderMint = findOffsetNftMint(bought.purchase, 0);
+    const remainderAccount = await getAssociatedTokenAddress(
+      remainderMint,
+      vintageBuyer.publicKey
+    );
+    const requestId = "VINTAGE-2023";
+    const [offsetRequest] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("offset_request"),
+        vintageBuyer.publicKey.toBuffer(),
+        bought.purchase.toBuffer(),
+        Buffer.from(requestId),
+      ],
+      program.programId
+    );
+    const offsetAccounts = {
+      offsetRequester: vintageBuyer.publicKey,
+      purchase: bought.purchase,
+      project: vintageProject.project,
+      vintage: vintagePda,
+      originalNftMint: bought.nftMint,
+      originalNftAccount: bought.nftAccount,
+      newNftMint: remainderMint,
+      newNftAccount: remainderAccount,
+      newNftMetadata: findMetadataPda(remainderMint),
+      projectCollectionMint: vintageProject.nftMint,
+      projectCollectionMetadata: findMetadataPda(vintageProject.nftMint),
+      projectCollectionMasterEdition: findMasterEditionPda(
+        vintageProject.nftMint
+      ),
+      tokenMint: vintageMint,
+      buyerTokenAccount: bought.tokenAccount,
+      carbonCredits: carbonCreditsPda,
+      offsetRequest,
+    };
+    const retirement = {
+      beneficiary: vintageBuyer.publicKey,
+      beneficiaryName: "Vintage Buyer",
+      reason: "2023 vintage retirement",
+      reportingPeriodStart: new BN(1672531200), // 2023-01-01
+      reportingPeriodEnd: new BN(1704067199), // 2023-12-31
+    };
+    try {
+      await program.methods
+        .requestOffset(new BN(4), requestId, retirement)
+        .accountsPartial({ ...offsetAccounts, vintage: null })
+        .signers([vintageBuyer])
+        .rpc();
+      assert.fail(
+        "Offsetting a vintage purchase without its vintage should fail"
+      );
+    } catch (error) {
+      assert.ok(String(error).includes("InvalidProjectMint"));
+    }
+    await program.methods
+      .requestOffset(new BN(4), requestId, retirement)
+      .accountsPartial(offsetAccounts)
+      .signers([vintageBuyer])
+      .rpc();
+
+    // d) Vintage stats roll up into the project
+    const vintageAfter = await program.account.projectVintage.fetch(
+      vintagePda
+    );
+    assert.equal(vintageAfter.remainingAmount.toNumber(), vintageAmount - 6);
+    assert.equal(vintageAfter.offsetAmount.toNumber(), 4);
+    projAcc = await program.account.project.fetch(vintageProject.project);
+    assert.equal(projAcc.remainingAmount.toNumber(), 40 + vintageAmount - 6);
+    assert.equal(projAcc.offsetAmount.toNumber(), 4);
+
+    // e) The project's own tranche only sells its own stock, not the vintage's
+    try {
+      await purchaseCredits(vintageBuyer, vintageProject, 41);
+      assert.fail("Buying more than the tranche holds should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("InsufficientTokens"));
+    }
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 12) Close finished accounts
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("12. Close offset request, purchase and finished project (reclaim rent)", async () => {
+    const closingOwner = Keypair.generate();
+    await airdrop(closingOwner.publicKey, 10);
+    const closingProject = await createApprovedProject(closingOwner, {
+      amount: 3,
+      price: PRICE_PER_TOKEN,
+      priceCurrency: { lamports: {} },
+    });
+    const closingBuyer = Keypair.generate();
+    await airdrop(closingBuyer.publicKey, 10);
+    const bought = await purchaseCredits(closingBuyer, closingProject, 3);
+
+    // a) Offset the whole purchase; no remaining-balance NFT is minted
+    const requestId = "CLOSE-1";
+    const [offsetRequest] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("offset_request"),
+        closingBuyer.publicKey.toBuffer(),
+        bought.purchase.toBuffer(),
+        Buffer.from(requestId),
+      ],
+      program.programId
+    );
+    await program.methods
+      .requestOffset(new BN(3), requestId, {
+        beneficiary: closingBuyer.publicKey,
+        beneficiaryName: "Closing Buyer",
+        reason: "Full retirement",
+        reportingPeriodStart: new BN(1704067200),
+        reportingPeriodEnd: new BN(1735689599),
+      })
+      .accountsPartial({
+        offsetRequester: closingBuyer.publicKey,
+        purchase: bought.purchase,
+        project: closingProject.project,
+        vintage: null,
+        originalNftMint: bought.nftMint,
+        originalNftAccount: bought.nftAccount,
+        newNftMint: null,
+        newNftAccount: null,
+        newNftMetadata: null,
+        projectCollectionMint: closingProject.nftMint,
+        projectCollectionMetadata: findMetadataPda(closingProject.nftMint),
+        projectCollectionMasterEdition: findMasterEditionPda(
+          closingProject.nftMint
+        ),
+        tokenMint: closingProject.tokenMint,
+        buyerTokenAccount: bought.tokenAccount,
+        carbonCredits: carbonCreditsPda,
+        offsetRequest,
+      })
+      .signers([closingBuyer])
+      .rpc();
+
+    // b) Nothing can be closed while the offset request is pending
+    try {
+      await program.methods
+        .closeOffsetRequest()
+        .accountsPartial({
+          offsetRequester: closingBuyer.publicKey,
+          offsetRequest,
+        })
+        .signers([closingBuyer])
+        .rpc();
+      assert.fail("Closing a pending offset request should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("RequestNotProcessed"));
+    }
+    try {
+      await program.methods
+        .closePurchase()
+        .accountsPartial({
+          buyer: closingBuyer.publicKey,
+          purchase: bought.purchase,
+        })
+        .signers([closingBuyer])
+        .rpc();
+      assert.fail("Closing a purchase with a pending offset request should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("PurchaseHasPendingRequests"));
+    }
+    const closeProject = () =>
+      program.methods
+        .closeProject()
+        .accountsPartial({
+          projectOwner: closingOwner.publicKey,
+          project: closingProject.project,
+          vault: closingProject.vault,
+          carbonCredits: carbonCreditsPda,
+        })
+        .signers([closingOwner])
+        .rpc();
+    try {
+      await closeProject();
+      assert.fail("Closing a project with a pending offset should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("ProjectNotFinished"));
+    }
+
+    // c) Once approved, the requester closes the request and the purchase
+    await program.methods
+      .reviewOffsetRequest(true)
+      .accountsPartial({
+        verifier: provider.wallet.publicKey,
+        offsetRequest,
+        project: closingProject.project,
+        purchase: bought.purchase,
+        verifierRegistry: verifierRegistryPda,
+      })
+      .rpc();
+
+    const buyerBefore = await connection.getBalance(closingBuyer.publicKey);
+    const requestRent = (await connection.getAccountInfo(offsetRequest))!
+      .lamports;
+    const purchaseRent = (await connection.getAccountInfo(bought.purchase))!
+      .lamports;
+    await program.methods
+      .closeOffsetRequest()
+      .accountsPartial({
+        offsetRequester: closingBuyer.publicKey,
+        offsetRequest,
+      })
+      .signers([closingBuyer])
+      .rpc();
+    await program.methods
+      .closePurchase()
+      .accountsPartial({
+        buyer: closingBuyer.publicKey,
+        purchase: bought.purchase,
+      })
+      .signers([closingBuyer])
+      .rpc();
+    assert.equal(await connection.getAccountInfo(offsetRequest), null);
+    assert.equal(await connection.getAccountInfo(bought.purchase), null);
+    const buyerAfter = await connection.getBalance(closingBuyer.publicKey);
+    // two transactions at 5000 lamports each
+    assert.equal(
+      buyerAfter - buyerBefore,
+      requestRent + purchaseRent - 2 * 5000
+    );
+
+    // d) A vintage tranche must be finished and closed before the project
+    const vintageYear = 2022;
+    const vintageMint = await createMint(
+      connection,
+      closingOwner,
+      closingOwner.publicKey,
+      closingOwner.publicKey,
+      0
+    );
+    const vintageVault = await getAssociatedTokenAddress(
+      vintageMint,
+      carbonCreditsPda,
+      true
+    );
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          closingOwner.publicKey,
+          vintageVault,
+          carbonCreditsPda,
+          vintageMint
+        )
+      ),
+      [closingOwner]
+    );
+    const yearSeed = Buffer.alloc(2);
+    yearSeed.writeUInt16LE(vintageYear);
+    const [vintagePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vintage"), closingProject.project.toBuffer(), yearSeed],
+      program.programId
+    );
+    const vintageAttestation = attest(
+      closingOwner.publicKey,
+      vintageMint,
+      2,
+      registryOracle,
+      vintageYear
+    );
+    await program.methods
+      .addProjectVintage(
+        vintageYear,
+        new BN(2),
+        new BN(PRICE_PER_TOKEN),
+        vintageAttestation.attestation
+      )
+      .accountsPartial({
+        projectOwner: closingOwner.publicKey,
+        project: closingProject.project,
+        vintage: vintagePda,
+        tokenMint: vintageMint,
+        vault: vintageVault,
+        carbonCredits: carbonCreditsPda,
+        registryOracle: registryOraclePda,
+        instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+      })
+      .preInstructions([vintageAttestation.verifyIx])
+      .signers([closingOwner])
+      .rpc();
+    const closeVintage = () =>
+      program.methods
+        .closeProjectVintage()
+        .accountsPartial({
+          projectOwner: closingOwner.publicKey,
+          project: closingProject.project,
+          vintage: vintagePda,
+          vault: vintageVault,
+          carbonCredits: carbonCreditsPda,
+        })
+        .signers([closingOwner])
+        .rpc();
+    try {
+      await closeVintage();
+      assert.fail("Closing a vintage with unsold credits should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("VintageNotFinished"));
+    }
+
+    // the unsold vintage expires and is burned, which finishes it
+    await program.methods
+      .setSaleExpiry(new BN(Math.floor(Date.now() / 1000) - 60))
+      .accountsPartial({
+        projectOwner: closingOwner.publicKey,
+        project: closingProject.project,
+        vintage: vintagePda,
+      })
+      .signers([closingOwner])
+      .rpc();
+    await program.methods
+      .burnExpiredCredits(new BN(2))
+      .accountsPartial({
+        projectOwner: closingOwner.publicKey,
+        project: closingProject.project,
+        vintage: vintagePda,
+        tokenMint: vintageMint,
+        vault: vintageVault,
+        carbonCredits: carbonCreditsPda,
+      })
+      .signers([closingOwner])
+      .rpc();
+    try {
+      await closeProject();
+      assert.fail("Closing a project with an open vintage should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("ProjectHasOpenVintages"));
+    }
+    await closeVintage();
+    assert.equal(await connection.getAccountInfo(vintagePda), null);
+    assert.equal(await connection.getAccountInfo(vintageVault), null);
+    const projAcc = await program.account.project.fetch(closingProject.project);
+    assert.equal(projAcc.openVintages, 0);
+
+    // e) The sold-out, fully offset project and its vault are closed
+    await closeProject();
+    assert.equal(
+      await connection.getAccountInfo(closingProject.project),
+      null
+    );
+    assert.equal(await connection.getAccountInfo(closingProject.vault), null);
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 13) Allowlisted presale
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("13. Presale: allowlist proof, per-transaction and per-wallet caps", async () => {
+    const presaleOwner = Keypair.generate();
+    await airdrop(presaleOwner.publicKey, 10);
+    const presaleProject = await createApprovedProject(presaleOwner, {
+      amount: 50,
+      price: PRICE_PER_TOKEN,
+      priceCurrency: { lamports: {} },
+    });
+
+    // a) Allowlist of two wallets; leaves are sha256(wallet), pairs hashed sorted
+    const partner = Keypair.generate();
+    const otherPartner = Keypair.generate();
+    const outsider = Keypair.generate();
+    await airdrop(partner.publicKey, 10);
+    await airdrop(outsider.publicKey, 10);
+    const sha256 = (...parts: Buffer[]) =>
+      createHash("sha256").update(Buffer.concat(parts)).digest();
+    const partnerLeaf = sha256(partner.publicKey.toBuffer());
+    const otherLeaf = sha256(otherPartner.publicKey.toBuffer());
+    const merkleRoot =
+      Buffer.compare(partnerLeaf, otherLeaf) <= 0
+        ? sha256(partnerLeaf, otherLeaf)
+        : sha256(otherLeaf, partnerLeaf);
+    const partnerProof = [Array.from(otherLeaf)];
+
+    const now = await connection.getBlockTime(await connection.getSlot());
+    await program.methods
+      .setPresale({
+        merkleRoot: Array.from(merkleRoot),
+        startTime: new BN(now! - 60),
+        endTime: new BN(now! + 3600),
+        maxPerWallet: new BN(5),
+        maxPerTx: new BN(3),
+      })
+      .accountsPartial({
+        projectOwner: presaleOwner.publicKey,
+        project: presaleProject.project,
+      })
+      .signers([presaleOwner])
+      .rpc();
+
+    const allocationOf = (wallet: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("buyer_allocation"),
+          presaleProject.project.toBuffer(),
+          wallet.toBuffer(),
+        ],
+        program.programId
+      )[0];
+    const expectFailure = async (buy: Promise<unknown>, code: string) => {
+      try {
+        await buy;
+        assert.fail(`Expected ${code}`);
+      } catch (error) {
+        assert.ok(String(error).includes(code), String(error));
+      }
+    };
+
+    // b) Wallets outside the allowlist cannot buy during the presale
+    await expectFailure(
+      purchaseCredits(outsider, presaleProject, 1, {
+        allowlistProof: partnerProof,
+        buyerAllocation: allocationOf(outsider.publicKey),
+      }),
+      "NotOnAllowlist"
+    );
+    const [checkoutRequest] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("offset_request"),
+        outsider.publicKey.toBuffer(),
+        PublicKey.default.toBuffer(),
+        Buffer.from("PRESALE-CHECKOUT"),
+      ],
+      program.programId
+    );
+    await expectFailure(
+      program.methods
+        .purchaseAndRetire(
+          new BN(1),
+          new BN(PRICE_PER_TOKEN),
+          new BN(CARBON_PAY_FEE),
+          "PRESALE-CHECKOUT",
+          {
+            beneficiary: outsider.publicKey,
+            beneficiaryName: "Outsider",
+            reason: "Checkout during the presale",
+            reportingPeriodStart: new BN(0),
+            reportingPeriodEnd: new BN(0),
+          }
+        )
+        .accountsPartial({
+          buyer: outsider.publicKey,
+          project: presaleProject.project,
+          tokenMint: presaleProject.tokenMint,
+          vault: presaleProject.vault,
+          carbonCredits: carbonCreditsPda,
+          priceFeed: null,
+          offsetRequest: checkoutRequest,
+        })
+        .remainingAccounts([
+          { pubkey: presaleOwner.publicKey, isWritable: true, isSigner: false },
+        ])
+        .signers([outsider])
+        .rpc(),
+      "PresaleActive"
+    );
+
+    // c) Caps are enforced for allowlisted wallets
+    const partnerOpts = {
+      allowlistProof: partnerProof,
+      buyerAllocation: allocationOf(partner.publicKey),
+    };
+    await expectFailure(
+      purchaseCredits(partner, presaleProject, 4, partnerOpts),
+      "PresaleTxCapExceeded"
+    );
+    await purchaseCredits(partner, presaleProject, 3, partnerOpts);
+    await expectFailure(
+      purchaseCredits(partner, presaleProject, 3, partnerOpts),
+      "PresaleWalletCapExceeded"
+    );
+    await purchaseCredits(partner, presaleProject, 2, partnerOpts);
+    const allocation = await program.account.buyerAllocation.fetch(
+      allocationOf(partner.publicKey)
+    );
+    assert.equal(allocation.purchased.toNumber(), 5);
+
+    // d) Removing the presale opens the public sale
+    await program.methods
+      .setPresale(null)
+      .accountsPartial({
+        projectOwner: presaleOwner.publicKey,
+        project: presaleProject.project,
+      })
+      .signers([presaleOwner])
+      .rpc();
+    await purchaseCredits(outsider, presaleProject, 4);
+    const projAcc = await program.account.project.fetch(
+      presaleProject.project
+    );
+    assert.equal(projAcc.remainingAmount.toNumber(), 50 - 5 - 4);
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 14) Referrals
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("14. Referrer earns a share of the platform fee on referred purchases", async () => {
+    const referralOwner = Keypair.generate();
+    await airdrop(referralOwner.publicKey, 10);
+    const referralProject = await createApprovedProject(referralOwner, {
+      amount: 20,
+      price: PRICE_PER_TOKEN,
+      priceCurrency: { lamports: {} },
+    });
+
+    // a) The platform registers a sales partner with 20% of the platform fee
+    const partner = Keypair.generate();
+    await airdrop(partner.publicKey, 1);
+    const [referrerPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("referrer"), partner.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .registerReferrer(partner.publicKey, 2_000)
+      .accountsPartial({
+        authority: provider.wallet.publicKey,
+        carbonCredits: carbonCreditsPda,
+        referrer: referrerPda,
+      })
+      .rpc();
+
+    // b) A referred purchase splits the fee; the owner proceeds are untouched
+    const referredBuyer = Keypair.generate();
+    await airdrop(referredBuyer.publicKey, 10);
+    const ownerBefore = await connection.getBalance(referralOwner.publicKey);
+    const platformBefore = await connection.getBalance(carbonCreditsPda);
+    const ccBefore = await program.account.carbonCredits.fetch(
+      carbonCreditsPda
+    );
+    await purchaseCredits(referredBuyer, referralProject, 5, {
+      referrer: referrerPda,
+    });
+    const total = 5 * PRICE_PER_TOKEN;
+    const fee = (total * CARBON_PAY_FEE) / 10_000;
+    const referralFee = (fee * 2_000) / 10_000;
+    assert.equal(
+      (await connection.getBalance(referralOwner.publicKey)) - ownerBefore,
+      total - fee
+    );
+    assert.equal(
+      (await connection.getBalance(carbonCreditsPda)) - platformBefore,
+      fee - referralFee
+    );
+    const ccAfter = await program.account.carbonCredits.fetch(carbonCreditsPda);
+    assert.equal(
+      ccAfter.totalFeesEarned.toNumber() - ccBefore.totalFeesEarned.toNumber(),
+      fee - referralFee
+    );
+    const referrerAcc = await program.account.referrer.fetch(referrerPda);
+    assert.equal(referrerAcc.accruedLamports.toNumber(), referralFee);
+    assert.equal(referrerAcc.referredVolume.toNumber(), total);
+    assert.equal(referrerAcc.referredTokens.toNumber(), 5);
+    assert.equal(referrerAcc.referredPurchases.toNumber(), 1);
+
+    // c) Partners cannot refer themselves
+    try {
+      await purchaseCredits(partner, referralProject, 1, {
+        referrer: referrerPda,
+      });
+      assert.fail("Self-referral should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("SelfReferral"));
+    }
+
+    // d) The partner claims the accrued fees
+    const partnerBefore = await connection.getBalance(partner.publicKey);
+    await program.methods
+      .claimReferralFees()
+      .accountsPartial({ wallet: partner.publicKey, referrer: referrerPda })
+      .signers([partner])
+      .rpc();
+    assert.equal(
+      (await connection.getBalance(partner.publicKey)) - partnerBefore,
+      referralFee - 5000
+    );
+    const claimed = await program.account.referrer.fetch(referrerPda);
+    assert.equal(claimed.accruedLamports.toNumber(), 0);
+    assert.equal(claimed.totalEarned.toNumber(), referralFee);
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 15) Subscriptions
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("15. Subscription is cranked once per period and retires its credits", async () => {
+    const subscriptionOwner = Keypair.generate();
+    await airdrop(subscriptionOwner.publicKey, 10);
+    const subscriptionProject = await createApprovedProject(subscriptionOwner, {
+      amount: 20,
+      price: PRICE_PER_TOKEN,
+      priceCurrency: { lamports: {} },
+    });
+
+    // a) A customer subscribes to 3 tonnes per day and pre-funds it
+    const customer = Keypair.generate();
+    await airdrop(customer.publicKey, 10);
+    const subscriptionId = new BN(1);
+    const [subscriptionPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("subscription"),
+        customer.publicKey.toBuffer(),
+        subscriptionId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const crankTip = 100_000;
+    await program.methods
+      .createSubscription(
+        subscriptionId,
+        [subscriptionProject.project],
+        new BN(3),
+        new BN(86_400),
+        new BN(PRICE_PER_TOKEN),
+        new BN(crankTip),
+        new BN(anchor.web3.LAMPORTS_PER_SOL)
+      )
+      .accountsPartial({
+        owner: customer.publicKey,
+        subscription: subscriptionPda,
+      })
+      .signers([customer])
+      .rpc();
+
+    // b) Anyone cranks the first period: credits are retired, owner and cranker paid
+    const cranker = Keypair.generate();
+    await airdrop(cranker.publicKey, 1);
+    const execute = (project: TestProject, execution: number) =>
+      program.methods
+        .executeSubscription()
+        .accountsPartial({
+          cranker: cranker.publicKey,
+          subscription: subscriptionPda,
+          project: project.project,
+          tokenMint: project.tokenMint,
+          vault: project.vault,
+          carbonCredits: carbonCreditsPda,
+          priceFeed: null,
+          offsetRequest: findRetirementRequest(
+            customer.publicKey,
+            `sub-${subscriptionId}-${execution}`
+          ),
+        })
+        .remainingAccounts([
+          { pubkey: project.owner.publicKey, isWritable: true, isSigner: false },
+        ])
+        .signers([cranker])
+        .rpc();
+    const ownerBefore = await connection.getBalance(
+      subscriptionOwner.publicKey
+    );
+    const crankerBefore = await connection.getBalance(cranker.publicKey);
+    const ccBefore = await program.account.carbonCredits.fetch(
+      carbonCreditsPda
+    );
+    await execute(subscriptionProject, 1);
+    const total = 3 * PRICE_PER_TOKEN;
+    const fee = (total * CARBON_PAY_FEE) / 10_000;
+    assert.equal(
+      (await connection.getBalance(subscriptionOwner.publicKey)) - ownerBefore,
+      total - fee
+    );
+    assert.equal(
+      (await connection.getBalance(cranker.publicKey)) - crankerBefore,
+      crankTip - 5000
+    );
+    const projAcc = await program.account.project.fetch(
+      subscriptionProject.project
+    );
+    assert.equal(projAcc.remainingAmount.toNumber(), 20 - 3);
+    assert.equal(projAcc.offsetAmount.toNumber(), 3);
+    const ccAfter = await program.account.carbonCredits.fetch(carbonCreditsPda);
+    assert.equal(
+      ccAfter.offsetCredits.toNumber() - ccBefore.offsetCredits.toNumber(),
+      3
+    );
+    const subAcc = await program.account.subscription.fetch(subscriptionPda);
+    assert.equal(subAcc.executions.toNumber(), 1);
+    assert.equal(subAcc.totalRetired.toNumber(), 3);
+    assert.equal(subAcc.totalSpent.toNumber(), total + crankTip);
+
+    // c) The retirement is recorded for review like a checkout, for the customer
+    const requestAcc = await program.account.offsetRequest.fetch(
+      findRetirementRequest(customer.publicKey, "sub-1-1")
+    );
+    assert.ok(requestAcc.offsetRequester.equals(customer.publicKey));
+    assert.ok(requestAcc.retirement.beneficiary.equals(customer.publicKey));
+    assert.ok(requestAcc.purchase.equals(PublicKey.default));
+    assert.equal(requestAcc.amount.toNumber(), 3);
+    assert.ok(requestAcc.status.pending !== undefined);
+    assert.equal(projAcc.pendingOffsets, 1);
+
+    // d) The next period is not due yet
+    try {
+      await execute(subscriptionProject, 2);
+      assert.fail("Executing before the period elapsed should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("SubscriptionNotDue"));
+    }
+
+    // e) The owner cancels and gets the remaining balance back
+    const customerBefore = await connection.getBalance(customer.publicKey);
+    const subscriptionBalance = await connection.getBalance(subscriptionPda);
+    await program.methods
+      .cancelSubscription()
+      .accountsPartial({
+        owner: customer.publicKey,
+        subscription: subscriptionPda,
+      })
+      .signers([customer])
+      .rpc();
+    assert.equal(
+      (await connection.getBalance(customer.publicKey)) - customerBefore,
+      subscriptionBalance - 5000
+    );
+    assert.equal(await connection.getAccountInfo(subscriptionPda), null);
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 16) ExpireOffsetRequest
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("16. Expire a stale offset request (auto-refund after the review deadline)", async () => {
+    const setPolicy = (period: number, expiryAction: any) =>
+      program.methods
+        .setOffsetReviewPolicy(new BN(period), expiryAction)
+        .accountsPartial({
+          authority: provider.wallet.publicKey,
+          carbonCredits: carbonCreditsPda,
+        })
+        .rpc();
+    // a) Verifiers get 2 seconds; undecided requests are refunded
+    await setPolicy(2, { autoRefund: {} });
+
+    const staleOwner = Keypair.generate();
+    await airdrop(staleOwner.publicKey, 10);
+    const staleProject = await createApprovedProject(staleOwner, {
+      amount: 3,
+      price: PRICE_PER_TOKEN,
+      priceCurrency: { lamports: {} },
+    });
+    const staleBuyer = Keypair.generate();
+    await airdrop(staleBuyer.publicKey, 10);
+    const bought = await purchaseCredits(staleBuyer, staleProject, 3);
+
+    const ccBeforeRequest = await program.account.carbonCredits.fetch(
+      carbonCreditsPda
+    );
+    const requestId = "STALE-1";
+    const [offsetRequest] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("offset_request"),
+        staleBuyer.publicKey.toBuffer(),
+        bought.purchase.toBuffer(),
+        Buffer.from(requestId),
+      ],
+      program.programId
+    );
+    await program.methods
+      .requestOffset(new BN(3), requestId, {
+        beneficiary: staleBuyer.publicKey,
+        beneficiaryName: "Stale Buyer",
+        reason: "Never reviewed",
+        reportingPeriodStart: new BN(1704067200),
+        reportingPeriodEnd: new BN(1735689599),
+      })
+      .accountsPartial({
+        offsetRequester: staleBuyer.publicKey,
+        purchase: bought.purchase,
+        project: staleProject.project,
+        vintage: null,
+        originalNftMint: bought.nftMint,
+        originalNftAccount: bought.nftAccount,
+        newNftMint: null,
+        newNftAccount: null,
+        newNftMetadata: null,
+        projectCollectionMint: staleProject.nftMint,
+        projectCollectionMetadata: findMetadataPda(staleProject.nftMint),
+        projectCollectionMasterEdition: findMasterEditionPda(
+          staleProject.nftMint
+        ),
+        tokenMint: staleProject.tokenMint,
+        buyerTokenAccount: bought.tokenAccount,
+        carbonCredits: carbonCreditsPda,
+        offsetRequest,
+      })
+      .signers([staleBuyer])
+      .rpc();
+    const requested = await program.account.offsetRequest.fetch(offsetRequest);
+    assert.equal(
+      requested.reviewDeadline.toNumber(),
+      requested.requestDate.toNumber() + 2
+    );
+    const ccRequested = await program.account.carbonCredits.fetch(
+      carbonCreditsPda
+    );
+    assert.equal(
+      ccBeforeRequest.activeCredits.toNumber() -
+        ccRequested.activeCredits.toNumber(),
+      3
+    );
+
+    // b) Anyone can expire it, but not before the deadline
+    const expire = () =>
+      program.methods
+        .expireOffsetRequest()
+        .accountsPartial({
+          caller: provider.wallet.publicKey,
+          offsetRequest,
+          project: staleProject.project,
+          vintage: null,
+          purchase: bought.purchase,
+          carbonCredits: carbonCreditsPda,
+          tokenMint: staleProject.tokenMint,
+          requesterTokenAccount: bought.tokenAccount,
+          balanceNftMint: bought.nftMint,
+          requesterNftAccount: bought.nftAccount,
+        })
+        .rpc();
+    try {
+      await expire();
+      assert.fail("Expiring before the review deadline should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("ReviewDeadlineNotReached"));
+    }
+
+    // c) After the deadline the offset is reversed and the credits refunded
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+    const ccBefore = await program.account.carbonCredits.fetch(
+      carbonCreditsPda
+    );
+    await expire();
+    const expired = await program.account.offsetRequest.fetch(offsetRequest);
+    assert.deepEqual(expired.status, { expired: {} });
+    assert.deepEqual(expired.expiryAction, { autoRefund: {} });
+    const refundedBal = await connection.getTokenAccountBalance(
+      bought.tokenAccount
+    );
+    assert.equal(refundedBal.value.amount, "3");
+    // the purchase balance and its burned NFT are back, so it can be offset again
+    const purchaseAcc = await program.account.purchase.fetch(bought.purchase);
+    assert.equal(purchaseAcc.remainingAmount.toNumber(), 3);
+    const nftBal = await connection.getTokenAccountBalance(bought.nftAccount);
+    assert.equal(nftBal.value.amount, "1");
+    const projAcc = await program.account.project.fetch(staleProject.project);
+    assert.equal(projAcc.offsetAmount.toNumber(), 0);
+    assert.equal(projAcc.pendingOffsets, 0);
+    const ccAfter = await program.account.carbonCredits.fetch(carbonCreditsPda);
+    assert.equal(
+      ccAfter.expiredRequests.toNumber() - ccBefore.expiredRequests.toNumber(),
+      1
+    );
+    assert.equal(
+      ccBefore.offsetCredits.toNumber() - ccAfter.offsetCredits.toNumber(),
+      3
+    );
+    assert.equal(
+      ccAfter.refundedCredits.toNumber() - ccBefore.refundedCredits.toNumber(),
+      3
+    );
+    // refunded credits are active again, back where they were before the request
+    assert.equal(
+      ccAfter.activeCredits.toNumber(),
+      ccBeforeRequest.activeCredits.toNumber()
+    );
+
+    // d) Back to no deadline for the rest of the suite
+    await setPolicy(0, { autoApprove: {} });
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 17) Sale expiry
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("17. Sales stop after the sale expiry and the owner burns unsold credits", async () => {
+    const expiringOwner = Keypair.generate();
+    await airdrop(expiringOwner.publicKey, 10);
+    const expiringProject = await createApprovedProject(expiringOwner, {
+      amount: 5,
+      price: PRICE_PER_TOKEN,
+      priceCurrency: { lamports: {} },
+    });
+    const expiringBuyer = Keypair.generate();
+    await airdrop(expiringBuyer.publicKey, 10);
+    await purchaseCredits(expiringBuyer, expiringProject, 2);
+
+    const burnExpired = (amount: number) =>
+      program.methods
+        .burnExpiredCredits(new BN(amount))
+        .accountsPartial({
+          projectOwner: expiringOwner.publicKey,
+          project: expiringProject.project,
+          vintage: null,
+          tokenMint: expiringProject.tokenMint,
+          vault: expiringProject.vault,
+          carbonCredits: carbonCreditsPda,
+        })
+        .signers([expiringOwner])
+        .rpc();
+
+    // a) Unsold credits cannot be burned before they expire
+    try {
+      await burnExpired(3);
+      assert.fail("Burning credits that have not expired should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("CreditsNotExpired"));
+    }
+
+    // b) Once past the expiry, new sales are rejected
+    await program.methods
+      .setSaleExpiry(new BN(Math.floor(Date.now() / 1000) - 60))
+      .accountsPartial({
+        projectOwner: expiringOwner.publicKey,
+        project: expiringProject.project,
+        vintage: null,
+      })
+      .signers([expiringOwner])
+      .rpc();
+    try {
+      await purchaseCredits(expiringBuyer, expiringProject, 1);
+      assert.fail("Buying expired credits should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("CreditsExpired"));
+    }
+
+    // c) The owner burns the unsold inventory, reported as expired
+    const ccBefore = await program.account.carbonCredits.fetch(
+      carbonCreditsPda
+    );
+    await burnExpired(3);
+    const projAcc = await program.account.project.fetch(
+      expiringProject.project
+    );
+    assert.equal(projAcc.remainingAmount.toNumber(), 0);
+    assert.equal(projAcc.expiredAmount.toNumber(), 3);
+    const vaultBal = await connection.getTokenAccountBalance(
+      expiringProject.vault
+    );
+    assert.equal(vaultBal.value.amount, "0");
+    const ccAfter = await program.account.carbonCredits.fetch(carbonCreditsPda);
+    assert.equal(
+      ccAfter.expiredCredits.toNumber() - ccBefore.expiredCredits.toNumber(),
+      3
+    );
+    assert.equal(
+      ccBefore.activeCredits.toNumber() - ccAfter.activeCredits.toNumber(),
+      3
+    );
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 18) Account migrations
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("18. Migrate a purchase of the first deployment to the current account layout", async () => {
+    const expectFailure = async (call: Promise<unknown>, code: string) => {
+      try {
+        await call;
+        assert.fail(`Expected ${code}`);
+      } catch (error) {
+        assert.ok(String(error).includes(code), String(error));
+      }
+    };
+    const migratePurchase = (authority: Keypair | null) =>
+      program.methods
+        .migratePurchase()
+        .accountsPartial({
+          authority: authority?.publicKey ?? provider.wallet.publicKey,
+          account: LEGACY_PURCHASE,
+          carbonCredits: carbonCreditsPda,
+          projectNftMint: null,
+          projectNftMetadata: null,
+          tokenMetadataProgram: null,
+        })
+        .signers(authority ? [authority] : [])
+        .rpc();
+
+    // a) Accounts created by this program are already versioned
+    const cc = await program.account.carbonCredits.fetch(carbonCreditsPda);
+    assert.equal(cc.version, ACCOUNT_VERSION);
+    await expectFailure(
+      program.methods
+        .migrateCarbonCredits()
+        .accountsPartial({
+          authority: provider.wallet.publicKey,
+          carbonCredits: carbonCreditsPda,
+        })
+        .rpc(),
+      "AccountAlreadyMigrated"
+    );
+
+    // b) The fixture has the version 0 size and cannot be decoded as is
+    const before = (await connection.getAccountInfo(LEGACY_PURCHASE))!;
+    assert.equal(before.data.length, 8 + 121);
+    await assert.rejects(program.account.purchase.fetch(LEGACY_PURCHASE));
+
+    // c) Only the buyer or the platform authority can migrate it
+    const stranger = Keypair.generate();
+    await airdrop(stranger.publicKey, 1);
+    await expectFailure(migratePurchase(stranger), "Unauthorized");
+
+    // d) The platform authority migrates it, topping up the rent
+    await migratePurchase(null);
+    const after = (await connection.getAccountInfo(LEGACY_PURCHASE))!;
+    assert.equal(after.data.length, 8 + 205 + 1 + 64);
+    const nftMint = new PublicKey(before.data.subarray(8 + 89, 8 + 121));
+    assert.equal(
+      after.lamports,
+      await connection.getMinimumBalanceForRentExemption(after.data.length)
+    );
+    const purchase = await program.account.purchase.fetch(LEGACY_PURCHASE);
+    assert.equal(purchase.version, ACCOUNT_VERSION);
+    assert.deepEqual(purchase.reserved, new Array(64).fill(0));
+    assert.equal(
+      purchase.buyer.toBase58(),
+      "57azdtRR73bxDBxbBNr6YUTh9uzKC1tHG3fr7X3tRG1r"
+    );
+    assert.equal(purchase.amount.toNumber(), 10);
+    assert.equal(purchase.remainingAmount.toNumber(), 4);
+    assert.equal(purchase.offsetCount.toNumber(), 0);
+    assert.ok(purchase.currentNftMint.equals(nftMint));
+    assert.ok(purchase.nftMint.equals(nftMint));
+
+    // e) Migrating twice is rejected
+    await expectFailure(migratePurchase(null), "AccountAlreadyMigrated");
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 19) Daily statistics
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("19. Daily sales and offset statistics per project and platform", async () => {
+    const statsOwner = Keypair.generate();
+    await airdrop(statsOwner.publicKey, 10);
+    const statsProject = await createApprovedProject(statsOwner, {
+      amount: 20,
+      price: PRICE_PER_TOKEN,
+      priceCurrency: { lamports: {} },
+    });
+    const projectStats = findProjectStatsPda(
+      program.programId,
+      statsProject.project
+    );
+    const platformStats = findPlatformStatsPda(program.programId);
+    const now = async () =>
+      (await connection.getBlockTime(await connection.getSlot()))!;
+    const platformToday = async () =>
+      (
+        await fetchDailyStats(program, platformStats, {
+          days: 1,
+          now: await now(),
+        })
+      )[0];
+
+    // a) Nothing recorded before the first sale
+    const before = await fetchDailyStats(program, projectStats, { days: 7 });
+    assert.equal(before.length, 7);
+    assert.equal(sumDailyStats(before).purchases, 0);
+    const platformBefore = await platformToday();
+
+    // b) Two purchases and a full offset, all on the same day
+    const statsBuyer = Keypair.generate();
+    await airdrop(statsBuyer.publicKey, 10);
+    await purchaseCredits(statsBuyer, statsProject, 4);
+    const bought = await purchaseCredits(statsBuyer, statsProject, 2);
+    const requestId = "STATS-1";
+    const [offsetRequest] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("offset_request"),
+        statsBuyer.publicKey.toBuffer(),
+        bought.purchase.toBuffer(),
+        Buffer.from(requestId),
+      ],
+      program.programId
+    );
+    await program.methods
+      .requestOffset(new BN(2), requestId, {
+        beneficiary: statsBuyer.publicKey,
+        beneficiaryName: "Stats Buyer",
+        reason: "Daily stats",
+        reportingPeriodStart: new BN(1704067200),
+        reportingPeriodEnd: new BN(1735689599),
+      })
+      .accountsPartial({
+        offsetRequester: statsBuyer.publicKey,
+        purchase: bought.purchase,
+        project: statsProject.project,
+        vintage: null,
+        originalNftMint: bought.nftMint,
+        originalNftAccount: bought.nftAccount,
+        newNftMint: null,
+        newNftAccount: null,
+        newNftMetadata: null,
+        projectCollectionMint: statsProject.nftMint,
+        projectCollectionMetadata: findMetadataPda(statsProject.nftMint),
+        projectCollectionMasterEdition: findMasterEditionPda(
+          statsProject.nftMint
+        ),
+        tokenMint: statsProject.tokenMint,
+        buyerTokenAccount: bought.tokenAccount,
+        carbonCredits: carbonCreditsPda,
+        offsetRequest,
+      })
+      .signers([statsBuyer])
+      .rpc();
+
+    // c) Today's project bucket holds the figures, older days stay empty
+    const revenue = (tokens: number) => tokens * PRICE_PER_TOKEN;
+    const fee = (tokens: number) =>
+      Math.floor((revenue(tokens) * CARBON_PAY_FEE) / 10_000);
+    const week = await fetchDailyStats(program, projectStats, {
+      days: 7,
+      now: await now(),
+    });
+    const today = week[6];
+    assert.equal(today.purchases, 2);
+    assert.equal(today.tokensSold.toNumber(), 6);
+    assert.equal(today.revenue.toNumber(), revenue(6));
+    assert.equal(today.fees.toNumber(), fee(4) + fee(2));
+    assert.equal(today.offsetRequests, 1);
+    assert.equal(today.tokensOffset.toNumber(), 2);
+    assert.equal(sumDailyStats(week.slice(0, 6)).purchases, 0);
+    const statsAcc = await program.account.salesStats.fetch(projectStats);
+    assert.equal(
+      statsAcc.scope.toBase58(),
+      statsProject.project.toBase58()
+    );
+
+    // d) The platform bucket of the day grew by the same amounts
+    const platformAfter = await platformToday();
+    if (platformAfter.day === platformBefore.day) {
+      assert.equal(platformAfter.purchases - platformBefore.purchases, 2);
+      assert.equal(
+        platformAfter.tokensSold.sub(platformBefore.tokensSold).toNumber(),
+        6
+      );
+      assert.equal(
+        platformAfter.tokensOffset.sub(platformBefore.tokensOffset).toNumber(),
+        2
+      );
+    }
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 20) PurchaseAndRetire
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("20. Buy and retire in one instruction (checkout)", async () => {
+    const checkoutOwner = Keypair.generate();
+    await airdrop(checkoutOwner.publicKey, 10);
+    const checkoutProject = await createApprovedProject(checkoutOwner, {
+      amount: 20,
+      price: PRICE_PER_TOKEN,
+      priceCurrency: { lamports: {} },
+    });
+    const shopper = Keypair.generate();
+    await airdrop(shopper.publicKey, 10);
+
+    const requestId = "CHECKOUT-1";
+    const [offsetRequest] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("offset_request"),
+        shopper.publicKey.toBuffer(),
+        PublicKey.default.toBuffer(),
+        Buffer.from(requestId),
+      ],
+      program.programId
+    );
+    const retirement = {
+      beneficiary: shopper.publicKey,
+      beneficiaryName: "Checkout Customer",
+      reason: "Order #1042 delivery emissions",
+      reportingPeriodStart: new BN(1704067200),
+      reportingPeriodEnd: new BN(1735689599),
+    };
+    const checkout = (amount: number, maxTotal: number) =>
+      program.methods
+        .purchaseAndRetire(
+          new BN(amount),
+          new BN(maxTotal),
+          new BN(CARBON_PAY_FEE),
+          requestId,
+          retirement
+        )
+        .accountsPartial({
+          buyer: shopper.publicKey,
+          project: checkoutProject.project,
+          tokenMint: checkoutProject.tokenMint,
+          vault: checkoutProject.vault,
+          carbonCredits: carbonCreditsPda,
+          priceFeed: null,
+          offsetRequest,
+        })
+        .remainingAccounts([
+          { pubkey: checkoutOwner.publicKey, isWritable: true, isSigner: false },
+        ])
+        .signers([shopper])
+        .rpc();
+
+    // a) A quote that moved above the shopper's limit is rejected
+    try {
+      await checkout(3, 3 * PRICE_PER_TOKEN - 1);
+      assert.fail("Paying more than max_total_lamports should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("SlippageExceeded"));
+    }
+
+    // b) Pay for 3 tonnes: owner and platform are paid, the credits are burned
+    const ownerBefore = await connection.getBalance(checkoutOwner.publicKey);
+    const ccBefore = await program.account.carbonCredits.fetch(
+      carbonCreditsPda
+    );
+    await checkout(3, 3 * PRICE_PER_TOKEN);
+    const total = 3 * PRICE_PER_TOKEN;
+    const fee = Math.floor((total * CARBON_PAY_FEE) / 10_000);
+    assert.equal(
+      (await connection.getBalance(checkoutOwner.publicKey)) - ownerBefore,
+      total - fee
+    );
+    const vaultBal = await connection.getTokenAccountBalance(
+      checkoutProject.vault
+    );
+    assert.equal(vaultBal.value.amount, "17");
+    const mintAcc = await getMint(connection, checkoutProject.tokenMint);
+    assert.equal(Number(mintAcc.supply), 17);
+    const ccAfter = await program.account.carbonCredits.fetch(carbonCreditsPda);
+    assert.equal(
+      ccAfter.offsetCredits.toNumber() - ccBefore.offsetCredits.toNumber(),
+      3
+    );
+    assert.equal(
+      ccAfter.totalFeesEarned.toNumber() - ccBefore.totalFeesEarned.toNumber(),
+      fee
+    );
+
+    // c) The retirement is recorded for review, without a purchase behind it
+    const requestAcc = await program.account.offsetRequest.fetch(offsetRequest);
+    assert.equal(requestAcc.amount.toNumber(), 3);
+    assert.ok(requestAcc.status.pending !== undefined);
+    assert.ok(requestAcc.purchase.equals(PublicKey.default));
+    assert.equal(
+      requestAcc.retirement.beneficiaryName,
+      retirement.beneficiaryName
+    );
+    const projAcc = await program.account.project.fetch(
+      checkoutProject.project
+    );
+    assert.equal(projAcc.remainingAmount.toNumber(), 17);
+    assert.equal(projAcc.offsetAmount.toNumber(), 3);
+    assert.equal(projAcc.pendingOffsets, 1);
+
+    // d) No purchase NFT or fungible tokens were left with the shopper
+    const shopperTokens = await connection.getParsedTokenAccountsByOwner(
+      shopper.publicKey,
+      { programId: TOKEN_PROGRAM_ID }
+    );
+    assert.equal(shopperTokens.value.length, 0);
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 21) RetireFromPool by CPI
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("21. Carbon-neutral SOL transfer retires credits from an offset pool by CPI", async () => {
+    const example = anchor.workspace
+      .CarbonNeutralTransfer as Program<CarbonNeutralTransfer>;
+    const poolOwner = Keypair.generate();
+    await airdrop(poolOwner.publicKey, 10);
+    const poolProject = await createApprovedProject(poolOwner, {
+      amount: 20,
+      price: PRICE_PER_TOKEN,
+      priceCurrency: { lamports: {} },
+    });
+    const [poolAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_authority")],
+      example.programId
+    );
+    const [offsetPool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("offset_pool"), poolAuthority.toBuffer()],
+      program.programId
+    );
+
+    // a) The example program creates its pool through CPI, anyone tops it up
+    await example.methods
+      .initialize(new BN(5 * PRICE_PER_TOKEN))
+      .accountsPartial({
+        payer: provider.wallet.publicKey,
+        poolAuthority,
+        offsetPool,
+        carbonPayProgram: program.programId,
+      })
+      .rpc();
+    await program.methods
+      .fundOffsetPool(new BN(5 * PRICE_PER_TOKEN))
+      .accountsPartial({ funder: provider.wallet.publicKey, offsetPool })
+      .rpc();
+    let poolAcc = await program.account.offsetPool.fetch(offsetPool);
+    assert.ok(poolAcc.authority.equals(poolAuthority));
+
+    // b) A transfer of 0.5 SOL retires 2 credits, paid from the pool
+    const sender = Keypair.generate();
+    await airdrop(sender.publicKey, 2);
+    const recipient = Keypair.generate().publicKey;
+    const sent = anchor.web3.LAMPORTS_PER_SOL / 2;
+    const transferSol = (credits: number, maxTotal: number, retirement = 1) =>
+      example.methods
+        .transferSol(new BN(sent), new BN(credits), new BN(maxTotal))
+        .accountsPartial({
+          sender: sender.publicKey,
+          recipient,
+          poolAuthority,
+          offsetPool,
+          project: poolProject.project,
+          tokenMint: poolProject.tokenMint,
+          vault: poolProject.vault,
+          carbonCredits: carbonCreditsPda,
+          priceFeed: null,
+          offsetRequest: findRetirementRequest(
+            poolAuthority,
+            `pool-${retirement}`
+          ),
+          projectStats: findProjectStatsPda(
+            program.programId,
+            poolProject.project
+          ),
+          platformStats: findPlatformStatsPda(program.programId),
+          carbonPayProgram: program.programId,
+        })
+        .remainingAccounts([
+          { pubkey: poolOwner.publicKey, isWritable: true, isSigner: false },
+        ])
+        .signers([sender])
+        .rpc();
+    const poolBefore = await connection.getBalance(offsetPool);
+    const ownerBefore = await connection.getBalance(poolOwner.publicKey);
+    await transferSol(2, 2 * PRICE_PER_TOKEN);
+    const total = 2 * PRICE_PER_TOKEN;
+    const fee = (total * CARBON_PAY_FEE) / 10_000;
+    const poolRequest = findRetirementRequest(poolAuthority, "pool-1");
+    const requestRent = (await connection.getAccountInfo(poolRequest))!
+      .lamports;
+    assert.equal(await connection.getBalance(recipient), sent);
+    // the pool also covers the rent of the retirement record
+    assert.equal(
+      poolBefore - (await connection.getBalance(offsetPool)),
+      total + requestRent
+    );
+    assert.equal(
+      (await connection.getBalance(poolOwner.publicKey)) - ownerBefore,
+      total - fee
+    );
+    const vaultBal = await connection.getTokenAccountBalance(poolProject.vault);
+    assert.equal(vaultBal.value.amount, "18");
+    poolAcc = await program.account.offsetPool.fetch(offsetPool);
+    assert.equal(poolAcc.retirements.toNumber(), 1);
+    assert.equal(poolAcc.totalRetired.toNumber(), 2);
+    assert.equal(poolAcc.totalSpent.toNumber(), total);
+    const projAcc = await program.account.project.fetch(poolProject.project);
+    assert.equal(projAcc.offsetAmount.toNumber(), 2);
+    assert.equal(projAcc.pendingOffsets, 1);
+    const requestAcc = await program.account.offsetRequest.fetch(poolRequest);
+    assert.ok(requestAcc.offsetRequester.equals(poolAuthority));
+    assert.ok(requestAcc.retirement.beneficiary.equals(poolAuthority));
+    assert.ok(requestAcc.status.pending !== undefined);
+
+    // c) A price above the caller's limit fails the whole transfer
+    try {
+      await transferSol(1, PRICE_PER_TOKEN - 1, 2);
+      assert.fail("Paying more than max_total_lamports should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("SlippageExceeded"));
+    }
+    assert.equal(await connection.getBalance(recipient), sent);
+
+    // d) Only the pool's authority can spend it
+    const intruder = Keypair.generate();
+    await airdrop(intruder.publicKey, 1);
+    try {
+      await program.methods
+        .retireFromPool(new BN(1), new BN(PRICE_PER_TOKEN))
+        .accountsPartial({
+          authority: intruder.publicKey,
+          payer: intruder.publicKey,
+          offsetPool,
+          project: poolProject.project,
+          tokenMint: poolProject.tokenMint,
+          vault: poolProject.vault,
+          carbonCredits: carbonCreditsPda,
+          priceFeed: null,
+          offsetRequest: findRetirementRequest(intruder.publicKey, "pool-2"),
+        })
+        .remainingAccounts([
+          { pubkey: poolOwner.publicKey, isWritable: true, isSigner: false },
+        ])
+        .signers([intruder])
+        .rpc();
+      assert.fail("Retiring from another authority's pool should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("ConstraintSeeds"));
+    }
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 22) Retirement fund
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("22. Pooled retirement fund retires in bulk, pro rata to unspent deposits", async () => {
+    const fundOwner = Keypair.generate();
+    await airdrop(fundOwner.publicKey, 10);
+    const fundProject = await createApprovedProject(fundOwner, {
+      amount: 20,
+      price: PRICE_PER_TOKEN,
+      priceCurrency: { lamports: {} },
+    });
+
+    // a) A manager creates a fund buying from one project, once a day for cranks
+    const manager = Keypair.generate();
+    await airdrop(manager.publicKey, 2);
+    const fundId = new BN(1);
+    const fund = findRetirementFundPda(
+      program.programId,
+      manager.publicKey,
+      fundId
+    );
+    await program.methods
+      .createRetirementFund(
+        fundId,
+        [fundProject.project],
+        new BN(PRICE_PER_TOKEN),
+        new BN(86_400)
+      )
+      .accountsPartial({ manager: manager.publicKey, retirementFund: fund })
+      .signers([manager])
+      .rpc();
+
+    // b) Two small donors deposit 3:1
+    const alice = Keypair.generate();
+    const bob = Keypair.generate();
+    const carol = Keypair.generate();
+    await airdrop(alice.publicKey, 1);
+    await airdrop(bob.publicKey, 1);
+    await airdrop(carol.publicKey, 1);
+    const deposit = (depositor: Keypair, lamports: number) =>
+      program.methods
+        .depositToFund(new BN(lamports))
+        .accountsPartial({
+          depositor: depositor.publicKey,
+          retirementFund: fund,
+          endedEpoch: null,
+          nextEpoch: null,
+        })
+        .signers([depositor])
+        .rpc();
+    await deposit(alice, 6 * PRICE_PER_TOKEN);
+    await deposit(bob, 2 * PRICE_PER_TOKEN);
+    let fundAcc = await program.account.retirementFund.fetch(fund);
+    assert.equal(fundAcc.totalUnspent.toNumber(), 8 * PRICE_PER_TOKEN);
+
+    // c) The manager retires 4 tonnes in bulk with the pooled balance
+    const retire = async (caller: Keypair, amount: number, retirement: number) =>
+      program.methods
+        .retireFromFund(new BN(amount))
+        .accountsPartial({
+          caller: caller.publicKey,
+          retirementFund: fund,
+          project: fundProject.project,
+          tokenMint: fundProject.tokenMint,
+          vault: fundProject.vault,
+          carbonCredits: carbonCreditsPda,
+          priceFeed: null,
+          offsetRequest: findRetirementRequest(
+            manager.publicKey,
+            `fund-${fundId}-${retirement}`
+          ),
+          fundEpoch: findFundEpochPda(
+            program.programId,
+            fund,
+            (await program.account.retirementFund.fetch(fund)).epoch
+          ),
+        })
+        .remainingAccounts([
+          { pubkey: fundOwner.publicKey, isWritable: true, isSigner: false },
+        ])
+        .signers([caller])
+        .rpc();
+    const fundBefore = await connection.getBalance(fund);
+    const ownerBefore = await connection.getBalance(fundOwner.publicKey);
+    await retire(manager, 4, 1);
+    const total = 4 * PRICE_PER_TOKEN;
+    const fee = (total * CARBON_PAY_FEE) / 10_000;
+    const fundRequest = findRetirementRequest(manager.publicKey, "fund-1-1");
+    const requestRent = (await connection.getAccountInfo(fundRequest))!
+      .lamports;
+    const epochRent = (await connection.getAccountInfo(
+      findFundEpochPda(program.programId, fund, new BN(0))
+    ))!.lamports;
+    // the fund also covers the rent of the retirement and epoch records
+    const spent = total + requestRent + epochRent;
+    assert.equal(fundBefore - (await connection.getBalance(fund)), spent);
+    assert.equal(
+      (await connection.getBalance(fundOwner.publicKey)) - ownerBefore,
+      total - fee
+    );
+    const vaultBal = await connection.getTokenAccountBalance(fundProject.vault);
+    assert.equal(vaultBal.value.amount, "16");
+    fundAcc = await program.account.retirementFund.fetch(fund);
+    assert.equal(fundAcc.totalRetired.toNumber(), 4);
+    assert.equal(fundAcc.retirements.toNumber(), 1);
+    assert.equal(fundAcc.totalUnspent.toNumber(), 8 * PRICE_PER_TOKEN - spent);
+    const requestAcc = await program.account.offsetRequest.fetch(fundRequest);
+    assert.ok(requestAcc.offsetRequester.equals(manager.publicKey));
+    assert.ok(requestAcc.retirement.beneficiary.equals(fund));
+    assert.ok(requestAcc.status.pending !== undefined);
+
+    // d) Each depositor's tonnage and spending are pro rata to their unspent balance
+    const aliceRetired = await fetchDepositorRetirement(
+      program,
+      fund,
+      alice.publicKey
+    );
+    const bobRetired = await fetchDepositorRetirement(
+      program,
+      fund,
+      bob.publicKey
+    );
+    assert.equal(aliceRetired.retiredTonnes, 3);
+    assert.equal(bobRetired.retiredTonnes, 1);
+    assert.equal(aliceRetired.deposited.toNumber(), 6 * PRICE_PER_TOKEN);
+    const left = fundAcc.totalUnspent.toNumber();
+    assert.ok(Math.abs(aliceRetired.unspent.toNumber() - (left * 3) / 4) <= 1);
+
+    // e) A later deposit as large as what is left carries half the next retirement
+    await deposit(carol, left);
+    await retire(manager, 2, 2);
+    const grams = async (depositor: Keypair) =>
+      (
+        await fetchDepositorRetirement(program, fund, depositor.publicKey)
+      ).retiredGrams.toNumber();
+    assert.ok(Math.abs((await grams(carol)) - 1_000_000) <= 1);
+    assert.ok(Math.abs((await grams(alice)) - 3_750_000) <= 1);
+    assert.ok(Math.abs((await grams(bob)) - 1_250_000) <= 1);
+
+    // f) Anyone else has to wait for the period before cranking again
+    const cranker = Keypair.generate();
+    await airdrop(cranker.publicKey, 1);
+    try {
+      await retire(cranker, 1, 3);
+      assert.fail("Retiring before the period elapsed should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("FundRetirementNotDue"));
+    }
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 23) TransferProjectOwnership
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("23. Transfer project ownership by handing over the project NFT", async () => {
+    const seller = Keypair.generate();
+    await airdrop(seller.publicKey, 10);
+    const soldProject = await createApprovedProject(seller, {
+      amount: 10,
+      price: PRICE_PER_TOKEN,
+      priceCurrency: { lamports: {} },
+    });
+    const acquirer = Keypair.generate();
+    await airdrop(acquirer.publicKey, 2);
+    const acquirerNftAccount = await getAssociatedTokenAddress(
+      soldProject.nftMint,
+      acquirer.publicKey
+    );
+    const claim = (projectNftAccount: PublicKey) =>
+      program.methods
+        .transferProjectOwnership()
+        .accountsPartial({
+          newOwner: acquirer.publicKey,
+          project: soldProject.project,
+          projectNftAccount,
+        });
+
+    // a) Without the NFT the project cannot be claimed
+    try {
+      await claim(soldProject.ownerNftAccount).signers([acquirer]).rpc();
+      assert.fail("Claiming without holding the project NFT should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("NotProjectNftHolder"));
+    }
+
+    // b) Atomic sale: the NFT changes hands and the acquirer claims in one transaction
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          acquirer.publicKey,
+          acquirerNftAccount,
+          acquirer.publicKey,
+          soldProject.nftMint
+        ),
+        createTransferInstruction(
+          soldProject.ownerNftAccount,
+          acquirerNftAccount,
+          seller.publicKey,
+          1
+        ),
+        await claim(acquirerNftAccount).instruction()
+      ),
+      [seller, acquirer]
+    );
+    const projAcc = await program.account.project.fetch(soldProject.project);
+    assert.ok(projAcc.owner.equals(acquirer.publicKey));
+    assert.ok(projAcc.creator.equals(seller.publicKey));
+    assert.equal(projAcc.beneficiaries.length, 1);
+    assert.ok(projAcc.beneficiaries[0].recipient.equals(acquirer.publicKey));
+
+    // c) The project keeps its address and sales now pay the new owner
+    const buyer = Keypair.generate();
+    await airdrop(buyer.publicKey, 2);
+    const acquirerBefore = await connection.getBalance(acquirer.publicKey);
+    await purchaseCredits(buyer, soldProject, 2);
+    const total = 2 * PRICE_PER_TOKEN;
+    const fee = (total * CARBON_PAY_FEE) / 10_000;
+    assert.equal(
+      (await connection.getBalance(acquirer.publicKey)) - acquirerBefore,
+      total - fee
+    );
+
+    // d) Owner-only instructions follow the new owner
+    const setExpiry = (owner: Keypair) =>
+      program.methods
+        .setSaleExpiry(null)
+        .accountsPartial({
+          projectOwner: owner.publicKey,
+          project: soldProject.project,
+          vintage: null,
+        })
+        .signers([owner])
+        .rpc();
+    try {
+      await setExpiry(seller);
+      assert.fail("The previous owner should no longer manage the project");
+    } catch (error) {
+      assert.ok(String(error).includes("InvalidProjectOwner"));
+    }
+    await setExpiry(acquirer);
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 24) SuspendProject
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("24. Suspend Project (platform authority takes listing off sale)", async () => {
+    const reasonCode = 3;
+    await program.methods
+      .suspendProject(reasonCode)
+      .accountsPartial({
+        authority: provider.wallet.publicKey,
+        project: projectPda,
+        carbonCredits: carbonCreditsPda,
+      })
+      .rpc();
+
+    const projAcc = await program.account.project.fetch(projectPda);
+    assert.ok(projAcc.status.suspended !== undefined);
+    assert.equal(projAcc.statusReason, reasonCode);
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 25) Rejected offset request
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("25. Rejected offset request gives the buyer back their balance", async () => {
+    const rejectOwner = Keypair.generate();
+    await airdrop(rejectOwner.publicKey, 10);
+    const rejectProject = await createApprovedProject(rejectOwner, {
+      amount: 4,
+      price: PRICE_PER_TOKEN,
+      priceCurrency: { lamports: {} },
+    });
+    const rejectBuyer = Keypair.generate();
+    await airdrop(rejectBuyer.publicKey, 10);
+    const bought = await purchaseCredits(rejectBuyer, rejectProject, 4);
+
+    // a) The buyer offsets the whole purchase, burning its NFT and tokens
+    const ccBefore = await program.account.carbonCredits.fetch(carbonCreditsPda);
+    const requestId = "REJECT-1";
+    const [offsetRequest] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("offset_request"),
+        rejectBuyer.publicKey.toBuffer(),
+        bought.purchase.toBuffer(),
+        Buffer.from(requestId),
+      ],
+      program.programId
+    );
+    await program.methods
+      .requestOffset(new BN(4), requestId, {
+        beneficiary: rejectBuyer.publicKey,
+        beneficiaryName: "Reject Buyer",
+        reason: "Unsupported claim",
+        reportingPeriodStart: new BN(1704067200),
+        reportingPeriodEnd: new BN(1735689599),
+      })
+      .accountsPartial({
+        offsetRequester: rejectBuyer.publicKey,
+        purchase: bought.purchase,
+        project: rejectProject.project,
+        vintage: null,
+        originalNftMint: bought.nftMint,
+        originalNftAccount: bought.nftAccount,
+        newNftMint: null,
+        newNftAccount: null,
+        newNftMetadata: null,
+        projectCollectionMint: rejectProject.nftMint,
+        projectCollectionMetadata: findMetadataPda(rejectProject.nftMint),
+        projectCollectionMasterEdition: findMasterEditionPda(
+          rejectProject.nftMint
+        ),
+        tokenMint: rejectProject.tokenMint,
+        buyerTokenAccount: bought.tokenAccount,
+        carbonCredits: carbonCreditsPda,
+        offsetRequest,
+      })
+      .signers([rejectBuyer])
+      .rpc();
+    let purchaseAcc = await program.account.purchase.fetch(bought.purchase);
+    assert.equal(purchaseAcc.remainingAmount.toNumber(), 0);
+
+    // b) The verifier quorum rejects it, without the refund accounts it fails
+    const reject = (refundAccounts: object) =>
+      program.methods
+        .reviewOffsetRequest(false)
+        .accountsPartial({
+          verifier: provider.wallet.publicKey,
+          offsetRequest,
+          project: rejectProject.project,
+          vintage: null,
+          purchase: bought.purchase,
+          carbonCredits: carbonCreditsPda,
+          verifierRegistry: verifierRegistryPda,
+          ...refundAccounts,
+        })
+        .rpc();
+    try {
+      await reject({
+        tokenMint: null,
+        requesterTokenAccount: null,
+        balanceNftMint: null,
+        requesterNftAccount: null,
+      });
+      assert.fail("Rejecting without the refund accounts should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("MissingRefundAccounts"));
+    }
+    await reject({
+      tokenMint: rejectProject.tokenMint,
+      requesterTokenAccount: bought.tokenAccount,
+      balanceNftMint: bought.nftMint,
+      requesterNftAccount: bought.nftAccount,
+    });
+
+    // c) The tokens, the purchase balance and its NFT are back, nothing is retired
+    const rejected = await program.account.offsetRequest.fetch(offsetRequest);
+    assert.deepEqual(rejected.status, { rejected: {} });
+    const tokenBal = await connection.getTokenAccountBalance(bought.tokenAccount);
+    assert.equal(tokenBal.value.amount, "4");
+    const nftBal = await connection.getTokenAccountBalance(bought.nftAccount);
+    assert.equal(nftBal.value.amount, "1");
+    purchaseAcc = await program.account.purchase.fetch(bought.purchase);
+    assert.equal(purchaseAcc.remainingAmount.toNumber(), 4);
+    const projAcc = await program.account.project.fetch(rejectProject.project);
+    assert.equal(projAcc.offsetAmount.toNumber(), 0);
+    assert.equal(projAcc.pendingOffsets, 0);
+    const ccAfter = await program.account.carbonCredits.fetch(carbonCreditsPda);
+    assert.equal(
+      ccAfter.offsetCredits.toNumber(),
+      ccBefore.offsetCredits.toNumber()
+    );
+    assert.equal(
+      ccAfter.activeCredits.toNumber(),
+      ccBefore.activeCredits.toNumber()
+    );
+  });
+
+  // ──────────────────────────────────────────────────────────────────────────────
+  // 26) Projects of the first deployment keep selling once migrated
+  // ──────────────────────────────────────────────────────────────────────────────
+  it("26. Buy from and partially offset a migrated project of the first deployment", async () => {
+    // Fixtures: a project of 100 credits, 90 unsold at 0.01 SOL, and a purchase
+    // of 10 of them
+    const owner = legacyKeypair("legacy-project-owner");
+    const nftMint = legacyKeypair("legacy-project-nft-mint");
+    const tokenMint = legacyKeypair("legacy-project-token-mint");
+    const buyer = legacyKeypair("legacy-project-buyer");
+    const purchaseNftMint = legacyKeypair("legacy-purchase-nft-mint");
+    await airdrop(owner.publicKey, 10);
+    await airdrop(buyer.publicKey, 10);
+    assert.ok(
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("project"),
+          owner.publicKey.toBuffer(),
+          nftMint.publicKey.toBuffer(),
+        ],
+        program.programId
+      )[0].equals(LEGACY_PROJECT)
+    );
+
+    // a) What the first deployment created next to the accounts: the project NFT,
+    // with the owner as update authority and no collection size, the vault and
+    // the buyer's tokens and purchase NFT
+    await createMint(connection, owner, owner.publicKey, owner.publicKey, 0, nftMint);
+    const ownerNftAccount = await createAssociatedTokenAccount(
+      connection,
+      owner,
+      nftMint.publicKey,
+      owner.publicKey
+    );
+    await mintTo(connection, owner, nftMint.publicKey, ownerNftAccount, owner, 1);
+    const borshString = (value: string) => {
+      const len = Buffer.alloc(4);
+      len.writeUInt32LE(value.length);
+      return Buffer.concat([len, Buffer.from(value)]);
+    };
+    const projectMetadata = findMetadataPda(nftMint.publicKey);
+    const signer = (pubkey: PublicKey, isWritable = false) => ({
+      pubkey,
+      isSigner: true,
+      isWritable,
+    });
+    const account = (pubkey: PublicKey, isWritable = false) => ({
+      pubkey,
+      isSigner: false,
+      isWritable,
+    });
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        // CreateMetadataAccountV3, without collection details
+        new anchor.web3.TransactionInstruction({
+          programId: METADATA_PROGRAM_ID,
+          keys: [
+            account(projectMetadata, true),
+            account(nftMint.publicKey),
+            signer(owner.publicKey),
+            signer(owner.publicKey, true),
+            signer(owner.publicKey),
+            account(SystemProgram.programId),
+            account(SYSVAR_RENT_PUBKEY),
+          ],
+          data: Buffer.concat([
+            Buffer.from([33]),
+            borshString(PROJECT_NAME),
+            borshString(PROJECT_SYMBOL),
+            borshString(PROJECT_URI),
+            Buffer.from([0, 0]), // seller_fee_basis_points
+            Buffer.from([0, 0, 0]), // creators, collection, uses: None
+            Buffer.from([1]), // is_mutable
+            Buffer.from([0]), // collection_details: None
+          ]),
+        }),
+        // CreateMasterEditionV3, max supply 0
+        new anchor.web3.TransactionInstruction({
+          programId: METADATA_PROGRAM_ID,
+          keys: [
+            account(findMasterEditionPda(nftMint.publicKey), true),
+            account(nftMint.publicKey, true),
+            signer(owner.publicKey),
+            signer(owner.publicKey),
+            signer(owner.publicKey, true),
+            account(projectMetadata, true),
+            account(TOKEN_PROGRAM_ID),
+            account(SystemProgram.programId),
+            account(SYSVAR_RENT_PUBKEY),
+          ],
+          data: Buffer.concat([Buffer.from([17, 1]), u64Le(0)]),
+        })
+      ),
+      [owner]
+    );
+
+    await createMint(connection, owner, owner.publicKey, owner.publicKey, 0, tokenMint);
+    const vault = await createAssociatedTokenAccount(
+      connection,
+      owner,
+      tokenMint.publicKey,
+      carbonCreditsPda,
+      undefined,
+      undefined,
+      undefined,
+      true
+    );
+    await mintTo(connection, owner, tokenMint.publicKey, vault, owner, 90);
+    const buyerTokenAccount = await createAssociatedTokenAccount(
+      connection,
+      buyer,
+      tokenMint.publicKey,
+      buyer.publicKey
+    );
+    await mintTo(connection, owner, tokenMint.publicKey, buyerTokenAccount, owner, 10);
+    await setAuthority(
+      connection,
+      owner,
+      tokenMint.publicKey,
+      owner,
+      AuthorityType.MintTokens,
+      carbonCreditsPda
+    );
+    await createMint(connection, buyer, buyer.publicKey, buyer.publicKey, 0, purchaseNftMint);
+    const buyerNftAccount = await createAssociatedTokenAccount(
+      connection,
+      buyer,
+      purchaseNftMint.publicKey,
+      buyer.publicKey
+    );
+    await mintTo(connection, buyer, purchaseNftMint.publicKey, buyerNftAccount, buyer, 1);
+
+    // b) The owner migrates the project, handing its NFT metadata to carbon_credits
+    const migrateProject = (metadataAccounts: object) =>
+      program.methods
+        .migrateProject()
+        .accountsPartial({
+          authority: owner.publicKey,
+          account: LEGACY_PROJECT,
+          carbonCredits: carbonCreditsPda,
+          ...metadataAccounts,
+        })
+        .signers([owner])
+        .rpc();
+    const noMetadata = {
+      projectNftMint: null,
+      projectNftMetadata: null,
+      tokenMetadataProgram: null,
+    };
+    try {
+      await migrateProject(noMetadata);
+      assert.fail("Migrating a first deployment project without its NFT should fail");
+    } catch (error) {
+      assert.ok(String(error).includes("InvalidCollection"));
+    }
+    await migrateProject({
+      projectNftMint: nftMint.publicKey,
+      projectNftMetadata: projectMetadata,
+      tokenMetadataProgram: METADATA_PROGRAM_ID,
+    });
+    await program.methods
+      .migratePurchase()
+      .accountsPartial({
+        authority: buyer.publicKey,
+        account: LEGACY_PROJECT_PURCHASE,
+        carbonCredits: carbonCreditsPda,
+        ...noMetadata,
+      })
+      .signers([buyer])
+      .rpc();
+    const metadataInfo = (await connection.getAccountInfo(projectMetadata))!;
+    assert.ok(
+      new PublicKey(metadataInfo.data.subarray(1, 33)).equals(carbonCreditsPda)
+    );
+    const projAcc = await program.account.project.fetch(LEGACY_PROJECT);
+    assert.equal(projAcc.version, ACCOUNT_VERSION);
+    assert.ok(projAcc.creator.equals(owner.publicKey));
+
+    // c) A new buyer purchases from it, the purchase NFT joins its collection
+    const legacyProject = {
+      project: LEGACY_PROJECT,
+      nftMint: nftMint.publicKey,
+      tokenMint: tokenMint.publicKey,
+      vault,
+    };
+    const newBuyer = Keypair.generate();
+    await airdrop(newBuyer.publicKey, 10);
+    const bought = await purchaseCredits(newBuyer, legacyProject, 5);
+    const boughtBal = await connection.getTokenAccountBalance(bought.tokenAccount);
+    assert.equal(boughtBal.value.amount, "5");
+
+    // d) The migrated purchase is partially offset, its remaining balance NFT is
+    // verified into the project's collection
+    const requestId = "LEGACY-1";
+    const [offsetRequest] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("offset_request"),
+        buyer.publicKey.toBuffer(),
+        LEGACY_PROJECT_PURCHASE.toBuffer(),
+        Buffer.from(requestId),
+      ],
+      program.programId
+    );
+    const remainderMint = findOffsetNftMint(LEGACY_PROJECT_PURCHASE, 0);
+    await program.methods
+      .requestOffset(new BN(4), requestId, {
+        beneficiary: buyer.publicKey,
+        beneficiaryName: "Legacy Buyer",
+        reason: "Retirement of a first deployment purchase",
+        reportingPeriodStart: new BN(1704067200),
+        reportingPeriodEnd: new BN(1735689599),
+      })
+      .accountsPartial({
+        offsetRequester: buyer.publicKey,
+        purchase: LEGACY_PROJECT_PURCHASE,
+        project: LEGACY_PROJECT,
+        vintage: null,
+        originalNftMint: purchaseNftMint.publicKey,
+        originalNftAccount: buyerNftAccount,
+        newNftMint: remainderMint,
+        newNftAccount: await getAssociatedTokenAddress(
+          remainderMint,
+          buyer.publicKey
+        ),
+        newNftMetadata: findMetadataPda(remainderMint),
+        projectCollectionMint: nftMint.publicKey,
+        projectCollectionMetadata: projectMetadata,
+        projectCollectionMasterEdition: findMasterEditionPda(nftMint.publicKey),
+        tokenMint: tokenMint.publicKey,
+        buyerTokenAccount,
+        carbonCredits: carbonCreditsPda,
+        offsetRequest,
+      })
+      .signers([buyer])
+      .rpc();
+    const purchaseAcc = await program.account.purchase.fetch(
+      LEGACY_PROJECT_PURCHASE
+    );
+    assert.equal(purchaseAcc.remainingAmount.toNumber(), 6);
+    assert.ok(purchaseAcc.currentNftMint.equals(remainderMint));
+    const projAfter = await program.account.project.fetch(LEGACY_PROJECT);
+    assert.equal(projAfter.remainingAmount.toNumber(), 85);
+    assert.equal(projAfter.offsetAmount.toNumber(), 4);
+    assert.equal(projAfter.pendingOffsets, 1);
+  });
 });